@@ -0,0 +1,87 @@
+//! The channel measurements of `sink = "channel"` are sent to.
+//!
+//! The channel is a bounded `std::sync::mpsc::sync_channel` of [`Measurement`]s, created at
+//! runtime by either of:
+//! - [`init`], returning the `Receiver` to drain yourself.
+//! - [`install`], spawning a thread calling a consumer with the name and the duration of each
+//!   measurement, and returning its `JoinHandle`.
+//!
+//! Both return `None` if the channel was already created. Timed code never waits for the consumer:
+//! once `capacity` measurements are pending, new ones are dropped until it catches up.
+//! Measurements are also dropped before the channel is created and after the `Receiver` is
+//! dropped. [`dropped`] returns the number of measurements lost this way, so a capacity too small
+//! for the load shows up there:
+//! ```
+//! use time_it::time_it;
+//!
+//! #[time_it(sink = "channel")]
+//! fn handle_request() {
+//!     println!("Some slow work");
+//! }
+//!
+//! let receiver = time_it::channel::init(64).unwrap();
+//! handle_request();
+//! for (name, duration) in receiver.try_iter() {
+//!     println!("{name} took {duration:?}");
+//! }
+//! assert_eq!(time_it::channel::dropped(), 0);
+//! ```
+//!
+//! There's one channel per process, which every crate using `sink = "channel"` sends to. With the
+//! `disabled` feature, it can still be created, so code creating it keeps compiling, but nothing
+//! is sent to it.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use std::sync::OnceLock;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::{self, JoinHandle};
+
+/// A measurement: the name of the timed code and the elapsed time.
+///
+/// The name is the one written in the annotation, so it's borrowed for `'static` rather than
+/// copied into a `String`, and sending a measurement never allocates on the timed thread.
+pub type Measurement = (&'static str, Duration);
+
+static SENDER: OnceLock<SyncSender<Measurement>> = OnceLock::new();
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+
+/// Creates the channel, holding up to `capacity` measurements, and returns its receiving end.
+/// Returns `None` if the channel was already created.
+pub fn init(capacity: usize) -> Option<Receiver<Measurement>> {
+    let (sender, receiver) = mpsc::sync_channel(capacity);
+    SENDER.set(sender).ok()?;
+    Some(receiver)
+}
+
+/// Creates the channel, holding up to `capacity` measurements, and spawns a thread passing each of
+/// them to `consumer`. Returns `None` if the channel was already created.
+pub fn install<F>(capacity: usize, mut consumer: F) -> Option<JoinHandle<()>>
+where
+    F: FnMut(&'static str, Duration) + Send + 'static,
+{
+    let receiver = init(capacity)?;
+    Some(thread::spawn(move || {
+        for (name, duration) in receiver {
+            consumer(name, duration);
+        }
+    }))
+}
+
+/// Number of measurements dropped, as the channel was full, not created yet, or its receiving end
+/// was dropped.
+pub fn dropped() -> u64 {
+    DROPPED.load(Ordering::Relaxed)
+}
+
+/// Sends a measurement to the channel, or counts it as dropped.
+#[doc(hidden)]
+pub fn send(name: &'static str, duration: Duration) {
+    // Never blocks, a full channel drops the measurement instead.
+    let sent = SENDER
+        .get()
+        .is_some_and(|sender| sender.try_send((name, duration)).is_ok());
+    if !sent {
+        DROPPED.fetch_add(1, Ordering::Relaxed);
+    }
+}
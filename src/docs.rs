@@ -0,0 +1,1406 @@
+//! Reference of the [`time_it`](macro@crate::time_it) attribute and its arguments.
+//!
+//! Annotated functions emit an event with their execution time when they complete, at the DEBUG
+//! level unless another one is given. The arguments below change what is measured, how the
+//! measurement is reported and which calls are reported. `time_block!`, `time_scope!` and
+//! `time_closure!` accept most of them too. The events of the functions defined in the examples are
+//! only displayed by a subscriber, e.g. from `tracing-subscriber`.
+//!
+//! # Defaults
+//!
+//! To change the default level for every event that doesn't pass one, set the
+//! `TIME_IT_DEFAULT_LEVEL` environment variable when building, e.g. to `info`. An explicit level
+//! always takes precedence over the variable, which takes precedence over the DEBUG default.
+//! `TIME_IT_DEFAULT_UNIT` likewise sets the `unit` of messages given neither a `unit` nor a
+//! `format`, and `TIME_IT_DEFAULT_TARGET` the target of events and spans given no `target`,
+//! replacing `time_it::measurement`. The variables apply to every crate of the build using the
+//! macro, and changing them rebuilds them. Setting them in the `[env]` section of
+//! `.cargo/config.toml` keeps them in one file, consistent across builds:
+//! ```toml
+//! [env]
+//! TIME_IT_DEFAULT_LEVEL = "info"
+//! TIME_IT_DEFAULT_UNIT = "us"
+//! TIME_IT_DEFAULT_TARGET = "timings"
+//! ```
+//!
+//! # Timed items
+//!
+//! The event is emitted however the function is left, including early returns, `?` and panics.
+//! When the function panics, the message ends with `(panicked)`, or a `panicked = true` field
+//! is added in `structured` and `span` mode. In async functions the guard emitting the event is
+//! part of the future, so a panic while it's polled is reported the same way.
+//!
+//! Besides free functions, methods in `impl` blocks and default methods in `trait` definitions
+//! can be annotated. Trait methods without a default body have nothing to time, so the
+//! implementations have to be annotated instead.
+//!
+//! This includes `async fn`s in traits, which are timed like any other `async fn`, and methods
+//! returning `impl Future` with the `future` flag. The timed future is `Send` whenever the body's
+//! is, so implementations still satisfy a trait declaring `-> impl Future<Output = T> + Send`.
+//! Traits rewritten by `#[async_trait]` are the exception: their methods return
+//! `Pin<Box<dyn Future>>` by the time `#[time_it]` sees them, so they need the `future` flag to
+//! time the future rather than its creation.
+//!
+//! The signature is kept as written, so `extern "C"` functions keep their ABI, and `#[no_mangle]`
+//! or `#[export_name]` functions their symbol. By default, the statements of the body are kept
+//! in the function itself, with the measurement taken by a guard around them, so `let`-`else`,
+//! labeled blocks, `return` and macro statements behave exactly as written. Bodies whose value is
+//! inspected, e.g. with `log_result`, run in a closure that is only called from within the
+//! function, so it doesn't affect its ABI. The closure is called once, so it can return references
+//! borrowed from the arguments or `self`, including `&mut` ones, as the function itself can.
+//!
+//! Functions returning a future without being `async` only create the future, so timing their
+//! body would only measure how long that takes. Pass the `future` flag to time the returned
+//! future instead, from its first poll until it completes, like an `async fn`. The function must
+//! return `impl Future` or `Pin<Box<dyn Future>>`, and this cannot be combined with `span`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(future)]
+//! fn fetch(id: u32) -> impl Future<Output = u32> + Send {
+//!    async move { id }
+//! }
+//! ```
+//!
+//! `const fn`s are rejected with a compile error, as the clock cannot be read in const contexts.
+//! So are other items, such as statics or macro invocations: to time the initialization of a
+//! static, annotate the function computing its value instead.
+//!
+//! Generic parameters, lifetimes and where-clauses are kept as written, and the body is not moved
+//! into a separate item unless `inner_fn` is given, so it can use them exactly as before.
+//!
+//! Attributes are applied from the top down. Attributes above `#[time_it]` have already been
+//! applied when it runs, so another attribute macro placed there sees the function as written and
+//! `#[time_it]` times whatever that macro generates. Attributes below it are kept on the timed
+//! function, so attribute macros placed there expand the timed function. Doc comments and
+//! attributes such as `#[inline]`, `#[cold]`, `#[must_use]` and `#[deprecated]` stay on the timed
+//! function in every mode, and are not copied to the closures or nested functions wrapping its
+//! body. `#[cfg]` attributes are evaluated by the compiler before `#[time_it]` runs, wherever they
+//! are placed: when the condition is false, the function is compiled out along with anything
+//! `#[time_it]` would generate next to it:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it]
+//! #[cfg(feature = "cache")]
+//! #[tracing::instrument]
+//! fn warm_cache() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! Test functions can be timed with `#[time_it]` on either side of `#[test]`, and still register
+//! with the test harness and report each run once. For async tests, place `#[time_it]` above
+//! `#[tokio::test]` (or the test attribute of another runtime) so the `async fn` itself is timed,
+//! leaving out the creation of the runtime. Placed below it, `#[time_it]` receives the synchronous
+//! function the runtime attribute generates, and times the runtime along with the test:
+//! ```
+//! # use time_it::time_it;
+//! # async fn fetch_page() {}
+//! # #[cfg(feature = "tokio")]
+//! #[time_it]
+//! #[tokio::test]
+//! async fn fetches_the_page() {
+//!    fetch_page().await;
+//! }
+//! ```
+//!
+//! The generated code only refers to items through absolute paths such as `::core::option::Option`
+//! and `::time_it::__tracing::Level`, so it doesn't depend on the prelude or on the names in scope,
+//! and also compiles in `#![no_implicit_prelude]` crates and modules.
+//!
+//! # Passing arguments
+//!
+//! Arguments are passed as comma separated `key = "value"` pairs. The level can be passed
+//! the same way, so `#[time_it("trace")]` and `#[time_it(level = "trace")]` are equivalent. When
+//! passed positionally, the quotes are optional: `#[time_it(trace)]` is equivalent too. `level`,
+//! `err_level` and `span_level` also accept a `tracing::Level` constant, as in
+//! `#[time_it(level = Level::TRACE)]`. It is matched by name, so it doesn't have to be imported.
+//!
+//! Options can also be packed in a single string, as `key=value` pairs and flags separated by
+//! `;`. Values are written without quotes, and can't contain `;`. The packed string can be
+//! combined with options passed separately, but an option still can't be given twice:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it("level=info;threshold=10ms;unit=ms;location")]
+//! fn fetch_users() {
+//!    println!("Some slow work");
+//! }
+//!
+//! #[time_it("level=info;unit=ms", fields(team = "db"))]
+//! fn fetch_groups() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! # Labels
+//!
+//! To log a custom label instead of the function name, pass `label`, or its alias `name`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it("info", label = "db query")]
+//! fn fetch_users() {
+//!    println!("Some slow work");
+//! }
+//!
+//! #[time_it(name = "cache.lookup")]
+//! fn get(key: &str) {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To name each call after the value of an argument instead, pass `label_arg` with the name of
+//! that argument, which must implement `Display` and be bound to a plain name. The value is
+//! formatted into a `String` when the function is entered, before the body can move it, so a
+//! single dispatcher reports every operation under its own name. As the label is only known at
+//! runtime, this cannot be combined with `span`, `on_complete` or `collector`, which require a
+//! `&'static str` name:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(label_arg = "op")]
+//! fn dispatch(op: &str, payload: &[u8]) {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! # Filtering and levels
+//!
+//! To only emit an event when the execution time reaches a minimum duration, pass `threshold`.
+//! Durations are written as an integer directly followed by one of the units `ns`, `us` (or
+//! `µs`), `ms`, `s` or `m` for minutes, the same way for every argument taking a duration. Other
+//! forms, such as `"50 ms"`, `"50"` or `"1.5ms"`, are compile errors:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(threshold = "50ms")]
+//! fn hot_function() {
+//!    println!("Usually fast work");
+//! }
+//! ```
+//!
+//! Durations close to the resolution of the clock mostly measure the overhead of reading it. To
+//! discard such measurements entirely, pass `min_report` with the smallest duration worth
+//! reporting. Unlike `threshold`, which only filters events, discarded measurements are also
+//! kept out of `metric` histograms and `on_complete` handlers. This cannot be combined with
+//! `aggregate`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(min_report = "1us")]
+//! fn trivial() {
+//!    println!("Very fast work");
+//! }
+//! ```
+//!
+//! To turn the annotation into a lightweight performance test, pass `budget` with the longest
+//! duration a call may take. In the unit tests of the crate, where `cfg(test)` is set, a call
+//! taking longer panics with e.g. `[parse]: Execution time 12.5ms exceeded the budget of 10ms`
+//! once it has been reported, failing the test that made it. `cfg(test)` isn't set in the crate
+//! when it's compiled for its integration tests or for other crates, so the `enforce_budgets`
+//! feature enables the check in every build, for use in dev-dependencies. Otherwise the budget is
+//! ignored and calls are only reported as usual. The check happens however the call is reported,
+//! or even when the runtime switch turns reporting off, but not in functions emitted unchanged by
+//! the `disabled` or `max_level_*` features, and a call that's already panicking keeps its own
+//! panic:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(budget = "10ms")]
+//! fn parse(input: &str) {
+//!    println!("Parsing {input}");
+//! }
+//! ```
+//!
+//! To escalate the level of slow calls, pass `tiers` with a comma separated list of
+//! `duration:level` pairs in increasing order of duration. Each tier's level is used from its
+//! duration on, and faster calls use the default level. This cannot be combined with `span`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it("trace", tiers = "10ms:debug,100ms:warn")]
+//! fn escalating() {
+//!    println!("Sometimes slow work");
+//! }
+//! ```
+//!
+//! To pick the level at runtime, pass `level_fn` with a function returning a `tracing::Level`.
+//! It's called whenever an event is emitted, and a `match` on its result dispatches to the event
+//! macro of that level, which costs a call and a comparison per event. It requires the `tracing`
+//! backend, replaces `level` and cannot be combined with `span`, `via` or `output`:
+//! ```
+//! # use std::sync::atomic::{AtomicBool, Ordering};
+//! # use time_it::time_it;
+//! static VERBOSE: AtomicBool = AtomicBool::new(false);
+//!
+//! fn current_level() -> tracing::Level {
+//!     if VERBOSE.load(Ordering::Relaxed) { tracing::Level::INFO } else { tracing::Level::TRACE }
+//! }
+//!
+//! #[time_it(level_fn = current_level)]
+//! fn configurable() {
+//!    println!("Work logged at a configurable level");
+//! }
+//! ```
+//!
+//! # Formatting
+//!
+//! By default the duration is logged using its `Debug` representation (e.g. `1.234567891s`).
+//! Pass `format = "human"` to log a rounded value such as `1.23s`, `45.6ms` or `900µs` instead:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(format = "human")]
+//! fn readable() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To always log the duration in the same unit, pass `unit` with one of `ns`, `us`, `ms` or `s`.
+//! Seconds are logged as a floating point value, all other units as whole numbers:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(unit = "ms")]
+//! fn parseable() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! For logs read in columns, pass `format = "aligned"` to log the duration as a right-justified
+//! number of milliseconds with 3 decimals and a minimum width of 9 characters, as in
+//! `   45.612 ms`. `width` sets another width, from 1 to 32, and `precision` another number of
+//! decimals. Durations too long for the width widen the field rather than being cut:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(format = "aligned", width = 12, precision = 1)]
+//! fn columns() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! Alternatively, `repr` selects the representation with a single argument, standing for one of
+//! the above: `"debug"` (the default), `"human"`, `"aligned"`, `"secs_f64"` for `unit = "s"` and
+//! `"nanos"` for `unit = "ns"`. It cannot be combined with `format` or `unit`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(repr = "nanos")]
+//! fn parseable() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To round these durations to a number of decimals, pass `precision` with an integer from 0 to 9.
+//! With `unit`, the duration is then logged as a fractional amount of that unit, and with
+//! `format = "human"` the precision replaces the default decimals of every unit except
+//! nanoseconds. With `format = "aligned"`, it replaces the 3 decimals. `precision` requires one of
+//! them:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(unit = "ms", precision = 2)]
+//! fn rounded() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To round human readable durations to significant figures instead, which reads the same across
+//! magnitudes, pass `sig_figs` with an integer from 1 to 6. With 3, durations are logged as e.g.
+//! `1.23s`, `12.3ms` or `123µs`, and a duration rounding up to the next unit is logged in it, as
+//! `1.00ms`. Nanoseconds are whole, so they are rounded without decimals. This requires
+//! `format = "human"` and cannot be combined with `precision`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(format = "human", sig_figs = 3)]
+//! fn rounded() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To keep human readable durations from going below a unit, pass `min_unit` with `"ns"`, `"us"`,
+//! `"ms"` or `"s"`. Shorter durations are then logged as less than one of it, e.g. `<1µs` instead
+//! of `850ns`, or as a fraction of it with `precision` or `sig_figs`, as `0.85µs` with a precision
+//! of 2. Longer durations are scaled as usual. This requires `format = "human"`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(format = "human", min_unit = "us")]
+//! fn clamped() {
+//!    println!("Some fast work");
+//! }
+//! ```
+//!
+//! To render durations yourself, e.g. with locale specific separators, pass `fmt_fn` with the path
+//! to a function taking the `Duration` and returning anything implementing `Display`, such as a
+//! `String`. Its result replaces the duration in the message. This cannot be combined with
+//! `format`, `unit`, `structured` or `span`:
+//! ```
+//! # use std::time::Duration;
+//! # use time_it::time_it;
+//! fn french(duration: Duration) -> String {
+//!     format!("{:.1} ms", duration.as_secs_f64() * 1000.0).replace('.', ",")
+//! }
+//!
+//! #[time_it(fmt_fn = "french")]
+//! fn localized() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To change the wording of the message, pass a `template`. The `{name}` placeholder is replaced
+//! by the function name or label, and `{duration}` by the duration as selected by `format` or
+//! `unit`. Literal braces are written as `{{` and `}}`. Anything else added to the message,
+//! such as the source location, is still appended after the template. This cannot be combined
+//! with `structured` or `span`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(template = "{name} took {duration}")]
+//! fn reworded() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! Calls returning an `Err` can be worded differently with an `err_template`, written the same
+//! way. It replaces the `(failed)` marker and `template` for those calls, which are otherwise
+//! emitted at their usual level unless `err_level` is given as well. This requires a function
+//! returning `Result`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(err_template = "{name} FAILED after {duration}")]
+//! fn fallible() -> Result<(), std::io::Error> {
+//!    Ok(())
+//! }
+//! ```
+//!
+//! # Destinations
+//!
+//! To print the message directly instead of emitting an event, pass `output = "stdout"` or
+//! `output = "stderr"`. This gives immediate feedback in scripts and examples without setting up
+//! a subscriber or logger, but also bypasses their filtering: the message is always printed,
+//! regardless of its level or target. This cannot be combined with `structured`, `span` or
+//! `in_span`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(output = "stderr")]
+//! fn script_step() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To emit events through a logging macro of your own, pass `via` with the path to the macro,
+//! without the `!`. It is invoked in place of e.g. `tracing::debug!`, with the same arguments:
+//! an optional `target: "..."`, then any fields, then the message and its format arguments. The
+//! level is not passed, so the same macro is invoked for every level. The macro has to be in
+//! scope where the function is defined, e.g. through `pub(crate) use`. This cannot be combined
+//! with `span` or `output`:
+//! ```
+//! # use time_it::time_it;
+//! macro_rules! timing_log {
+//!     ($($arg:tt)*) => {
+//!         tracing::info!(team = "storage", $($arg)*)
+//!     };
+//! }
+//!
+//! #[time_it(via = "timing_log")]
+//! fn wrapped() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! Events and spans use the `time_it::measurement` target, so filters can select exactly the
+//! timing events, e.g. `RUST_LOG=time_it::measurement=info` with `tracing_subscriber::EnvFilter`,
+//! or leave them out from a module's events. No module of the timed code can have this path.
+//! Events emitted `via` a custom macro are not given a target, and keep the one of their module.
+//! Pass `target` to emit them under a custom target instead:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(target = "perf")]
+//! fn filtered() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! Events are children of the current span. To emit them under another span, such as one created
+//! for the request being handled, pass `parent` with an expression evaluating to it. The
+//! expression is evaluated when the function is entered, so it can use the arguments, and must
+//! convert into an `Option<tracing::Id>`, as `&tracing::Span`, `tracing::Span` and
+//! `Option<tracing::Id>` do. `None` makes the events roots. This requires the `tracing` backend
+//! and cannot be combined with `span`, `in_span`, `via` or `output`:
+//! ```
+//! # use time_it::time_it;
+//! struct Request {
+//!     span: tracing::Span,
+//! }
+//!
+//! #[time_it(parent = "&request.span")]
+//! fn handle(request: &Request) {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! # Fields
+//!
+//! To include the source file and line of the function definition in the event, pass the
+//! `location` flag. The message then ends with e.g. `at src/main.rs:12`.
+//!
+//! To emit the duration as a structured field instead of interpolating it into the message, pass
+//! the `structured` flag. The event then carries a `function` field with the name or label, a
+//! `duration_ms` field with the duration in milliseconds as a float, and the message
+//! `execution timed`. This requires the `tracing` backend and cannot be combined with `format`
+//! or `unit`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(structured)]
+//! fn indexed() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To follow a naming convention, `name_field` replaces the `function` key, in the events of
+//! `trace_entry` and `summarize` too. The key must be an identifier, such as `operation` or `fn`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(structured, name_field = "operation")]
+//! fn indexed() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To also include the exact duration as an integer, pass the `raw_ns` flag. A `duration_ns`
+//! field with the number of nanoseconds as a `u128` is then added to the event (or span), next
+//! to the usual message. This requires the `tracing` backend:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(raw_ns)]
+//! fn analyzed() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! Trace viewers such as `chrome://tracing` or Perfetto place calls on a timeline, which needs
+//! their start time. Pass the `chrome_trace` flag to add a `ts` field with the wall clock time the
+//! call started at and a `dur` field with its duration, both in microseconds as `u64`s, which are
+//! the fields of a complete (`"ph": "X"`) chrome trace event. The start time is read from
+//! `SystemTime` and counted from the Unix epoch. This requires the `tracing` backend and cannot
+//! be combined with `span`, as spans are converted by `tracing-chrome` already:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(chrome_trace, structured)]
+//! fn render_frame() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To place the calls on a timeline, e.g. for profiling the startup of a program, pass the
+//! `since_start` flag. The time each call started at, relative to a baseline shared by the whole
+//! process, is then added to the message as `t` (or as a `t_ms` field in milliseconds in
+//! `structured` and `span` mode). The baseline is set when the first call timed with
+//! `since_start` starts, so annotating `main` with it anchors the timestamps at the start of the
+//! program:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(since_start)]
+//! fn load_config() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To tag every event with constant fields, e.g. for filtering, pass `fields` with a list of
+//! `key = value` pairs. Values are string, integer, float or bool literals, and the fields are
+//! added to the event (or span) next to the ones generated by the macro. This requires the
+//! `tracing` backend:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(fields(component = "db", critical = true))]
+//! fn query() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To group timings by subsystem in dashboards and filters, pass `category` with a non-empty
+//! string. It's added as a `category` field before the ones given with `fields`, which then
+//! can't have a `category` key of their own. Using the same few categories across a service keeps
+//! the groups meaningful. This requires the `tracing` backend too:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(category = "network")]
+//! fn fetch() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! # Async functions
+//!
+//! Durations of async functions include the time spent suspended, waiting for other tasks. To tell
+//! them apart from sync ones downstream, pass the `kind` flag, adding a `kind` field set to
+//! `"async"` or `"sync"` to the event (or span). Functions timed in `future` mode are `"async"`.
+//! This requires the `tracing` backend and is not supported by `time_block!`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(kind)]
+//! async fn fetch() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! An event is also emitted when the future of an async function is dropped before completing,
+//! e.g. as the losing branch of a `select!` or on a timeout, with the time spent until then. To
+//! tell these cancelled calls apart, pass the `on_cancel` flag: their message then ends with
+//! `(cancelled)`, and events (or spans) get a `cancelled` field set to `true`. A future that
+//! panicked while polled is reported as panicked rather than cancelled. This only applies to
+//! async functions and functions timed in `future` mode, and is not supported by `time_block!`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(on_cancel)]
+//! async fn fetch() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To find futures that are woken much more often than they make progress, pass the `poll_count`
+//! flag. The body's future is then polled through a closure counting the polls until it completes,
+//! and the count is appended to the message, e.g. `, polls: 4`, or added as a `polls` field in
+//! `structured` and `span` mode. A future completing without suspending is polled once. This only
+//! applies to async functions and functions timed in `future` mode, and is not supported by
+//! `time_block!`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(poll_count)]
+//! async fn fetch() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To tell the synchronous setup of an async function apart from the time it spends waiting, pass
+//! the `split_await` flag. The time until a poll first returns `Pending` is then appended to the
+//! message next to the total, e.g. `, sync_prep: 1.2ms, total: 3.4ms`, or added as a
+//! `sync_prep_ms` field in `structured` and `span` mode. A future completing without suspending
+//! reports its whole duration as preparation. This is a heuristic: the split is wherever the future
+//! first waits, which may be an `.await` deep inside a callee, and an `.await` that completes right
+//! away doesn't split anything. It only applies to async functions and functions timed in `future`
+//! mode, and is not supported by `time_block!` or the `defmt` backend:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(split_await)]
+//! async fn fetch() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! The duration of an async call is wall time, from the first poll to completion, so it includes
+//! the time the future waited to be polled again after suspending: for the awaited I/O, but also
+//! for the executor to get to it once it's ready. To only measure the time spent running the
+//! function's code, pass the `exclude_idle` flag. Each poll is timed separately with the same
+//! clock, and the reported duration is their sum, its busy time. Comparing both tells a slow
+//! function from a busy executor or a slow dependency, but the busy time alone hides every wait,
+//! so a call blocked on the network for a second can report microseconds. Other fields of the
+//! event, such as `sync_prep`, stay in wall time. It only applies to async functions and functions
+//! timed in `future` mode, and is not supported by `time_block!`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(exclude_idle)]
+//! async fn fetch() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! # Excluded time
+//!
+//! To leave other waits out, such as the time spent acquiring a contended lock, pass `exclude`
+//! with a name and wrap each of them in `exclude!` with the same name. The time the wrapped
+//! expressions take while the function runs on the current thread, in its body or in the
+//! functions it calls, is subtracted from the reported duration. The exclusions are registered in
+//! the module defined by `exclusions!`. This requires a synchronous function, and is not supported
+//! by `time_block!`:
+//! ```
+//! # use std::sync::Mutex;
+//! # use time_it::time_it;
+//! time_it::exclusions!();
+//!
+//! #[time_it(exclude = "lock")]
+//! fn update(counter: &Mutex<u64>) {
+//!     let mut counter = time_it::exclude!("lock", counter.lock().unwrap());
+//!     *counter += 1;
+//! } // Reports the time spent holding the lock, not waiting for it
+//! # fn main() {}
+//! ```
+//!
+//! # Spans
+//!
+//! To wrap the function body in a `tracing` span instead of emitting an event, pass the `span`
+//! flag. The span is named after the function (or label), is entered for the whole body (including
+//! across `.await` points), and has its `duration_ms` field recorded once the body completes. This
+//! composes with `#[tracing::instrument]`, in which case the timed span is nested inside the
+//! instrumented one. This requires the `tracing` backend and cannot be combined with `structured`,
+//! `threshold`, `format` or `unit`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(span)]
+//! async fn traced() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! The same choice can be made with `mode`, which takes `"event"`, the default, or `"span"`,
+//! equivalent to the `span` flag. It cannot be combined with the flag itself:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(mode = "span")]
+//! async fn traced() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! In `span` mode, `level` sets the level of the span. To give the span its own level instead,
+//! pass `span_level`: the span is then created at that level, and a completion event is also
+//! emitted in the span at `level`, with the usual message:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(span, span_level = "info", level = "debug")]
+//! async fn traced() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! Spans are created with the regular `tracing` macros, so any subscriber sees them, including
+//! the one of `tokio-console`. The console's task list only shows the tasks of the runtime, under
+//! the name given with `tokio::task::Builder::name`; the spans of the timed functions are entered
+//! inside those tasks and reach the other layers of the subscriber, such as a `fmt` layer
+//! printing them when they close. Tasks are only instrumented with `--cfg tokio_unstable` and the
+//! `tracing` feature of `tokio`:
+//! ```
+//! # use time_it::time_it;
+//! # #[cfg(all(feature = "tokio", tokio_unstable))]
+//! # fn main() -> std::io::Result<()> {
+//! use tracing_subscriber::{fmt::format::FmtSpan, prelude::*};
+//!
+//! tracing_subscriber::registry()
+//!     .with(console_subscriber::spawn())
+//!     .with(tracing_subscriber::fmt::layer().with_span_events(FmtSpan::CLOSE))
+//!     .init();
+//!
+//! #[time_it(span, span_level = "info")]
+//! async fn handle_request() {
+//!    println!("Some slow work");
+//! }
+//!
+//! tokio::task::Builder::new()
+//!     .name("handler")
+//!     .spawn(handle_request())?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(all(feature = "tokio", tokio_unstable)))]
+//! # fn main() {}
+//! ```
+//!
+//! To add the duration to a span that already exists, such as one created by
+//! `#[tracing::instrument]`, pass the `in_span` flag. Instead of emitting an event, the duration
+//! in milliseconds is recorded into the `duration_ms` field of the current span once the body
+//! completes. Spans can only record fields they declare, so the span must declare the field,
+//! e.g. with `duration_ms = tracing::field::Empty`. Place `#[time_it]` above
+//! `#[tracing::instrument]`, so the body is still inside the span when it completes. This requires
+//! the `tracing` backend, and can only be combined with `threshold`, `clock`, `metric`, `otel`,
+//! `on_complete`, `collector`, `env_gate`, `first`, `sample` and `debug_only`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(in_span)]
+//! #[tracing::instrument(fields(duration_ms = tracing::field::Empty))]
+//! async fn handle(id: u32) {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To record into another field, name it with `record`, which implies `in_span`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(record = "elapsed_ms")]
+//! #[tracing::instrument(fields(elapsed_ms = tracing::field::Empty))]
+//! async fn handle(id: u32) {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! # Results and arguments
+//!
+//! To emit failed calls at a different level, pass `err_level`. When the function returns an
+//! `Err`, the event is emitted at that level and marked as failed: the message ends with
+//! `(failed)`, or a `failed = true` field is added in `structured` mode. This requires a function
+//! returning `Result` and cannot be combined with `span`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(err_level = "warn")]
+//! fn fallible() -> Result<(), std::io::Error> {
+//!    Ok(())
+//! }
+//! ```
+//!
+//! To say what went wrong, also pass the `log_err_value` flag. Failed calls then add the error
+//! using its `Debug` representation, as `, error: ...` in the message or an `error` field in
+//! `structured` mode, while the events of successful calls are unchanged. The error type must
+//! implement `Debug`, and the clock is stopped before formatting it. This requires `err_level`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(err_level = "warn", log_err_value)]
+//! fn fallible() -> Result<(), std::io::Error> {
+//!    Ok(())
+//! }
+//! ```
+//!
+//! To only report successful calls instead, pass the `skip_err` flag: calls returning an `Err`
+//! emit no event, and aren't counted by `aggregate` or `summarize` either. Histograms of `metric`
+//! and handlers such as `on_complete` still receive every call. This requires a function
+//! returning `Result` and cannot be combined with `err_level`, `err_template` or `span`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(skip_err)]
+//! fn fallible() -> Result<(), std::io::Error> {
+//!    Ok(())
+//! }
+//! ```
+//!
+//! To include the returned value in the event, pass the `log_result` flag. The value is logged
+//! using its `Debug` representation, so the return type must implement `Debug`. Functions
+//! returning `()` or `!` are logged as usual, without a value. This cannot be combined with
+//! `span`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(log_result)]
+//! fn compute() -> u64 {
+//!    42
+//! }
+//! ```
+//!
+//! To include the arguments in the event, pass the `log_args` flag. Every argument bound to a plain
+//! name is logged using its `Debug` representation as it was when the function was entered, so
+//! their types must implement `Debug`. `self` and arguments bound through a destructuring pattern
+//! are not logged:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(log_args)]
+//! fn lookup(id: u32, name: &str) {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! # Handlers
+//!
+//! To route every measurement to your own code, pass `on_complete` with the path to a function
+//! taking the name (or label) and the duration. It must be callable as
+//! `fn(&'static str, std::time::Duration)`, and is called after every call, regardless of any
+//! `threshold`, before the event is emitted:
+//! ```
+//! # use time_it::time_it;
+//! fn record(name: &'static str, duration: std::time::Duration) {
+//!    println!("{name} took {duration:?}");
+//! }
+//!
+//! #[time_it(on_complete = "record")]
+//! fn observed() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! For one-off handling, pass `handler` with an inline closure instead, which is called with the
+//! name (or label) and the duration in place of the event. It must take two arguments, which are
+//! inferred as `&'static str` and `std::time::Duration`, and return `()`. As it runs while the
+//! timed code completes, outside of the function's own scope, it can only refer to items such as
+//! statics and thread locals, not to local variables. Filters such as `threshold` and `sample`
+//! still apply before it's called, while arguments shaping the event can't be given:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(handler = |name, duration| println!("{name} took {duration:?}"))]
+//! fn handled() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To process the measurements out of band, on another thread, pass `sink = "channel"`. The name
+//! (or label) and the duration are then sent in place of the event to the channel of
+//! [`time_it::channel`](crate::channel). Sending never blocks: when the channel is full, or hasn't
+//! been created yet, the measurement is dropped and counted by `time_it::channel::dropped`.
+//! Filters such as `threshold` and `sample` still apply before sending, while arguments shaping the
+//! event can't be given:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(sink = "channel")]
+//! fn sent() {
+//!    println!("Some slow work");
+//! }
+//!
+//! fn main() {
+//!     time_it::channel::install(1024, |name, duration| println!("{name}: {duration:?}"));
+//!     sent();
+//! }
+//! ```
+//!
+//! To accumulate the measurements into a value of your own instead, such as a `static` summarized
+//! at shutdown, pass `collector` with the path to it. The value must implement
+//! `time_it::TimeItCollector`, whose `record` method is called with the name and the duration of
+//! each call, as an `on_complete` handler would be:
+//! ```
+//! # use time_it::{TimeItCollector, time_it};
+//! struct Timings(std::sync::Mutex<Vec<(&'static str, std::time::Duration)>>);
+//!
+//! impl TimeItCollector for Timings {
+//!     fn record(&self, name: &'static str, duration: std::time::Duration) {
+//!         self.0.lock().unwrap().push((name, duration));
+//!     }
+//! }
+//!
+//! static TIMINGS: Timings = Timings(std::sync::Mutex::new(Vec::new()));
+//!
+//! #[time_it(collector = "TIMINGS")]
+//! fn observed() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! # Selecting calls
+//!
+//! To only report calls while an environment variable is set, pass the `env_gate` flag. The
+//! variable is named after the function: its name in upper case, with any character other than
+//! an ASCII letter or digit replaced by `_`, prefixed by `TIME_IT_`. Any value enables reporting,
+//! including an empty one. The variable is read once, the first time the function completes, so
+//! changing it afterwards has no effect. This cannot be combined with `span`:
+//! ```
+//! # use time_it::time_it;
+//! // Only reported when `TIME_IT_HOT_PATH` is set
+//! #[time_it(env_gate)]
+//! fn hot_path() {
+//!    println!("Usually fast work");
+//! }
+//! ```
+//!
+//! To only report the first calls, e.g. while profiling startup, pass `first` with the number of
+//! calls to report. Calls are counted as they complete, by a `static` counter shared by every
+//! thread and by all instantiations of a generic function, so concurrent calls are never reported
+//! more than the given number of times. Every call completing before the limit is reached counts,
+//! even when `threshold` or `min_report` discard it. The clock is still read on every call, but
+//! calls past the limit only load the counter. This cannot be combined with `span` or `sample`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(first = 10)]
+//! fn load_config() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To only report a fraction of the calls of a frequently called function, pass `sample` with the
+//! interval between reported calls. Sampling is deterministic: every completed call increments a
+//! `static` counter shared by every thread and by all instantiations of a generic function, and
+//! the call is reported when the counter was a multiple of the interval, so the first call and
+//! then one call in every `sample` are reported. The clock is still read on every call. Calls
+//! discarded by sampling never reach `metric` or `on_complete` either. This cannot be combined
+//! with `first`, `span` or `aggregate`:
+//! ```
+//! # use time_it::time_it;
+//! // Reports one call in every 100
+//! #[time_it(sample = 100)]
+//! fn hot_path() {
+//!    println!("Usually fast work");
+//! }
+//! ```
+//!
+//! To keep track of the calls below a `threshold` without reporting each of them, pass
+//! `summarize` with the number of calls to report together. Slow calls are still reported as they
+//! complete, while fast calls are added to a `static` count and total, and every `summarize`th
+//! fast call emits e.g. `[hot_path]: 100 fast calls totaling 4.2ms` at the function's level (or
+//! `fast_calls` and `total_ms` fields in `structured` mode). A bare `summarize` flag reports them
+//! by 100. Summaries are only flushed by fast calls, so the last calls before the program exits,
+//! or before the function stops being called, are never reported. The count and total are
+//! updated independently, so under contention a call may be counted in one summary and have its
+//! duration added to the next. This requires a `threshold`, and is not supported by the `defmt`
+//! backend:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(threshold = "5ms", summarize = 100)]
+//! fn hot_path() {
+//!    println!("Usually fast work");
+//! }
+//! ```
+//!
+//! To alert on pathologically slow calls, pass `slow_level` with the level of the events of the
+//! calls reaching the `threshold`, which other events, such as summaries, don't use. To keep a
+//! flood of slow calls from filling the logs, pass `rate_limit` with the minimum time between two
+//! events: an event is dropped if the previous one was emitted less than this long ago. The time
+//! of the last event is kept in a `static` atomic, in nanoseconds since the first check, so the
+//! window is precise to the resolution of `std::time::Instant` and starts anew with each emitted
+//! event rather than at fixed intervals. Only events are limited, metrics and handlers still see
+//! every call. `slow_level` requires a `threshold` and cannot be combined with `tiers`, and
+//! `rate_limit` requires `std`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(threshold = "1s", slow_level = "error", rate_limit = "5s")]
+//! fn query() {
+//!    println!("Usually fast work");
+//! }
+//! ```
+//!
+//! To catch functions getting slower without picking a threshold, pass `regression` with a factor
+//! greater than 1: calls taking more than this factor times the fastest call so far are reported
+//! at WARN instead of their usual level. The fastest call is kept in a `static` atomic, and the
+//! first 10 calls only record it, as a handful of calls, some of them cold, is not a
+//! representative baseline yet. The fastest call is a baseline that slow calls can't drag up, but
+//! a single unusually fast one, e.g. an early return, makes later calls look slow against it.
+//! `regression` cannot be combined with `slow_level`, `tiers` or `span`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it("info", regression = 3.0)]
+//! fn query() {
+//!    println!("Usually steady work");
+//! }
+//! ```
+//!
+//! # Context
+//!
+//! To include the thread that ran the function, pass the `thread` flag. The thread name is logged,
+//! or its id for unnamed threads. For async functions this is the thread the future completed
+//! on, which may differ from the one it started on:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(thread)]
+//! fn worker_task() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! In applications running several async runtimes, pass the `runtime` flag with the `tokio`
+//! feature to include the runtime the future completed on. It is read from
+//! `tokio::runtime::Handle::try_current()`, as `tokio_multi_thread` or `tokio_current_thread`
+//! after the flavor of the runtime, `tokio` for other flavors, or `unknown` when the future isn't
+//! run by a tokio runtime, as on a custom pool or with `futures::executor::block_on`. Structured
+//! events get a `runtime` field, and spans record it. This requires an async function or `future`,
+//! and a dependency on `tokio`:
+//! ```
+//! # use time_it::time_it;
+//! # #[cfg(feature = "tokio")]
+//! #[time_it(runtime)]
+//! async fn fetch() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To report the throughput of functions processing data, pass `bytes_arg` with the name of an
+//! integer argument holding the number of bytes each call processes, or the `bytes_ret` flag to
+//! take it from the returned integer, or from the `Ok` value of a returned `Result`. The message
+//! then ends with e.g. `, throughput: 12.50 MB/s`, in megabytes of 1 000 000 bytes per second of
+//! the measured duration. The throughput is `unknown` when no time was measured, for errors, and
+//! for negative amounts. Structured events get `bytes` and `throughput_mb_s` fields, and spans
+//! record them. `bytes_ret` is not supported in `future` mode, whose output isn't in the signature:
+//! ```
+//! # use std::{fs::File, io::{self, Read}};
+//! # use time_it::time_it;
+//! #[time_it(bytes_arg = "len")]
+//! fn copy(src: &[u8], dst: &mut [u8], len: usize) {
+//!    dst[..len].copy_from_slice(&src[..len]);
+//! }
+//!
+//! #[time_it(bytes_ret)]
+//! fn read(file: &mut File, buf: &mut [u8]) -> io::Result<usize> {
+//!    file.read(buf)
+//! }
+//! ```
+//!
+//! To also see when long running calls start, pass the `trace_entry` flag. An event such as
+//! `[import]: Started` is emitted at the same level and target when the call starts, before
+//! the measurement begins, followed by the usual event once it completes. It includes the
+//! arguments and the sequence number if `log_args` or `seq` are passed, and is an `execution
+//! started` event with a `function` field in `structured` mode. The start event ignores any
+//! `threshold`, and `trace_entry` cannot be combined with `span`, `first`, `sample` or
+//! `env_gate`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it("info", trace_entry)]
+//! fn import(path: &str) {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To tell concurrent calls apart, pass the `seq` flag. Every call is numbered, starting from 0,
+//! by a `static` counter incremented when the call starts, and the number is logged with the
+//! event, or as a `seq` field in `structured` and `span` mode. All instantiations of a generic
+//! function share the same counter:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(seq)]
+//! async fn handle(id: u32) {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To follow a single call through the logs, pass the `corr_id` flag. Every call then gets a
+//! random 64 bit id, logged as 16 hexadecimal digits, e.g. `, corr_id: 3f2a9c04e1b7d865`, or as a
+//! `corr_id` field in `structured` and `span` mode. With `trace_entry`, the entry event carries
+//! the same id as the completion event. The id is hashed with a new `std::hash::RandomState`,
+//! whose keys are seeded randomly and differ for every instance, so unlike sequence numbers, ids
+//! are unique across functions and processes with overwhelming probability, without any shared
+//! counter. This requires `std` and cannot be used with the `defmt` backend:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(corr_id, trace_entry)]
+//! async fn handle(id: u32) {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! To see how deep a recursive function goes, pass the `depth` flag. A thread local counts the
+//! calls to the function the current thread is in, and each call logs the number of calls it was
+//! made from, e.g. `, depth: 2`, or a `depth` field in `structured` and `span` mode. The outermost
+//! call has depth 0, and the counter is restored however a call is left, including by unwinding.
+//! Adding `top_only` only reports the outermost calls, which then cover the whole recursion.
+//! Futures can move between threads and interleave, so `depth` requires a synchronous function:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(depth, top_only)]
+//! fn fibonacci(n: u64) -> u64 {
+//!     if n < 2 { n } else { fibonacci(n - 1) + fibonacci(n - 2) }
+//! }
+//! ```
+//!
+//! The counters of `seq`, `aggregate`, `ring`, `first`, `sample`, `summarize`, `rate_limit` and
+//! `regression` are atomics updated with `Relaxed` ordering, which only keeps each counter
+//! consistent on its own. To order their updates with the surrounding memory accesses, e.g. while
+//! debugging, pass `ordering` with `acqrel` or `seqcst`. Stronger orderings can be slower on weakly
+//! ordered architectures such as ARM, while x86 already provides them for these operations:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(seq, ordering = "seqcst")]
+//! fn handle(id: u32) {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! # Clocks
+//!
+//! By default the wall-clock time is measured. With the `cpu_time` feature enabled, pass
+//! `clock = "cpu"` to measure the CPU time spent by the current thread instead, using the
+//! `cpu-time` crate. The message then reports a `CPU time` instead of an `Execution time`. CPU
+//! time is not supported on async functions, as their future can move between threads while
+//! suspended:
+//! ```
+//! # use time_it::time_it;
+//! # #[cfg(feature = "cpu_time")]
+//! #[time_it(clock = "cpu")]
+//! fn busy() {
+//!    println!("Some CPU heavy work");
+//! }
+//! ```
+//!
+//! To see how much of a call was spent waiting, e.g. on a lock or I/O, pass the `both_clocks` flag
+//! to measure both times at once. The message keeps the wall-clock `Execution time`, followed by
+//! `wall` and `cpu`, and structured events and spans get `wall_ms` and `cpu_ms` fields. A wall
+//! time well above the CPU time means the thread was blocked or descheduled. Like `clock = "cpu"`,
+//! this requires the `cpu_time` feature and is not supported on async functions or with `future`,
+//! whose CPU time would be spread over whichever threads polled them:
+//! ```
+//! # use std::sync::Mutex;
+//! # use time_it::time_it;
+//! # #[cfg(feature = "cpu_time")]
+//! #[time_it(both_clocks)]
+//! fn contended(state: &Mutex<Vec<u32>>) {
+//!    state.lock().unwrap().push(1);
+//! }
+//! ```
+//!
+//! The wall-clock time is read from `std::time::Instant`, which uses the highest resolution
+//! monotonic clock of the platform: `QueryPerformanceCounter` on Windows, `CLOCK_MONOTONIC` on
+//! Linux and `mach_absolute_time` on macOS. Where that's still too coarse, e.g. on platforms whose
+//! OS clock only resolves microseconds, enable the `high_res` feature and pass
+//! `clock = "high_res"` to measure with the `quanta` crate instead. `quanta` reads the CPU's
+//! timestamp counter when it's invariant across cores, and otherwise falls back to the same OS
+//! clock as `Instant`, so the resolution never gets worse. Either way the message reports an
+//! `Execution time`:
+//! ```
+//! # use time_it::time_it;
+//! # #[cfg(feature = "high_res")]
+//! #[time_it(clock = "high_res")]
+//! fn tiny() -> u32 {
+//!    42
+//! }
+//! ```
+//!
+//! To notice calls running below the resolution of the clock, pass the `warn_zero` flag. The first
+//! call measuring no time then emits a WARN event, `[name]: Measured no time, below the resolution
+//! of the clock`, before its usual report. The warning is emitted once per annotated function,
+//! whatever the `threshold`, and hints at timing a larger piece of code or using a finer clock:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(warn_zero)]
+//! fn tiny() -> u32 {
+//!    42
+//! }
+//! ```
+//!
+//! To measure with a clock of your own, e.g. a fake clock asserting on logged durations in tests,
+//! pass `clock_fn` with the path to a function creating the starting point of a measurement. It
+//! must be callable as `now()`, and its result must have an `elapsed(&self) -> Duration` method
+//! returning the time since it was created, like `std::time::Instant::now`. This cannot be
+//! combined with `clock`:
+//! ```
+//! # use std::{cell::Cell, time::Duration};
+//! # use time_it::time_it;
+//! thread_local! {
+//!     static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+//! }
+//!
+//! struct FakeInstant(Duration);
+//!
+//! impl FakeInstant {
+//!     fn now() -> Self {
+//!         Self(NOW.with(Cell::get))
+//!     }
+//!
+//!     fn elapsed(&self) -> Duration {
+//!         NOW.with(Cell::get) - self.0
+//!     }
+//! }
+//!
+//! #[time_it(clock_fn = "FakeInstant::now")]
+//! fn work() {
+//!     NOW.with(|now| now.set(now.get() + Duration::from_millis(42)));
+//! }
+//! ```
+//!
+//! # Function bodies
+//!
+//! The measurement ends once the body is done, after the locals declared in it are dropped, so the
+//! time spent in their destructors is included. The arguments are only dropped when the function
+//! returns, after the measurement. To include them too, e.g. for a function consuming a large
+//! collection, pass the `include_drops` flag, which moves them into the body. Arguments taken by
+//! reference or bound through patterns, as well as `self`, are still dropped after the
+//! measurement. This cannot be combined with `future`, and is not supported by `time_block!`:
+//! ```
+//! # use std::collections::HashMap;
+//! # use time_it::time_it;
+//! #[time_it(include_drops)]
+//! fn consume(index: HashMap<String, Vec<u64>>) -> usize {
+//!     index.len()
+//! }
+//! ```
+//!
+//! When the body has to be wrapped to inspect its value, e.g. for `log_result`, it runs in a
+//! closure or an async block. Pass the `inner_fn` flag to move it into a nested function taking
+//! the same generics, lifetimes and arguments instead, which is called while timing. The body is
+//! then type checked exactly as if it was the function's own, at the cost of a call that is
+//! inlined in optimized builds. As a nested function can't receive `self` or refer to `Self`, it
+//! is limited to free functions and associated functions not mentioning `Self`. The arguments are
+//! moved into the call, so this implies `include_drops`, and cannot be combined with it or
+//! `future`. Arguments bound through patterns aren't logged by `log_args`, as in other modes:
+//! ```
+//! # use std::{fmt::Debug, str::FromStr};
+//! # use time_it::time_it;
+//! #[time_it(inner_fn, log_result)]
+//! fn parse<T: FromStr + Debug>(input: &str) -> Option<T> {
+//!     input.parse().ok()
+//! }
+//! ```
+//!
+//! # Statistics
+//!
+//! To keep running totals instead of emitting an event per call, pass the `aggregate` flag. Every
+//! call then updates a `static` call counter and total duration, which are returned by a generated
+//! `<function name>_stats()` accessor with the same visibility as the function. For periodic
+//! reporting, a generated `<function name>_reset_stats()` returns the totals and sets them back to
+//! zero, so that each report covers the calls since the previous one. The counters are updated,
+//! read and reset atomically but independently, so a concurrent call may be counted without its
+//! duration, or have its duration reported in the next period. The total wraps around on
+//! overflow, after roughly 584 years of cumulated time.
+//! As the statics and the accessor are generated next to the function, this only supports free
+//! functions, and all instantiations of a generic function share the same totals. This can only
+//! be combined with `clock`, `metric`, `otel`, `on_complete`, `collector`, `env_gate`, `first`
+//! and `debug_only`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(aggregate)]
+//! fn handle_request() {
+//!    println!("Some slow work");
+//! }
+//!
+//! # #[cfg(not(feature = "disabled"))]
+//! let (calls, total) = handle_request_stats();
+//! // Every minute:
+//! # #[cfg(not(feature = "disabled"))]
+//! let (calls, total) = handle_request_reset_stats();
+//! ```
+//!
+//! To keep the durations of the most recent calls instead, pass `ring` with the number of calls to
+//! keep. Every call then stores its duration in a `static` ring buffer of `AtomicU64`s, 8 bytes per
+//! call kept, overwriting the oldest once it's full. A generated `<function name>_recent()`
+//! accessor with the same visibility as the function copies them into a `Vec`, from the oldest to
+//! the most recent. Recording and reading are lock-free, but a read concurrent with a call may see
+//! the previous duration of the slot it's writing. Like `aggregate`, this only supports free
+//! functions and doesn't emit events. `threshold`, `min_report`, `first` and `sample` select the
+//! calls that are recorded:
+//! ```
+//! # use std::time::Duration;
+//! # use time_it::time_it;
+//! #[time_it(ring = 256)]
+//! fn render_frame() {
+//!    println!("Some slow work");
+//! }
+//!
+//! # #[cfg(not(feature = "disabled"))]
+//! let frame_times: Vec<Duration> = render_frame_recent();
+//! ```
+//!
+//! For percentiles without an external metrics backend, pass the `quantiles` flag. Every call then
+//! adds its duration to a `static` histogram of 976 `AtomicU64` buckets, 7.6KiB per function, and
+//! a generated `<function name>_quantiles()` accessor with the same visibility as the function
+//! returns the estimated median, 90th and 99th percentile, or `None` before the first call.
+//! Durations below 16ns are counted exactly, longer ones in buckets spanning 1/16th of their
+//! duration, and the estimates are the middle of a bucket, so they are within about 3% of the
+//! actual percentile. Recording is lock-free and never allocates, while the accessor reads every
+//! bucket. Events are still emitted, and durations are recorded regardless of any `threshold`. As
+//! the histogram is a `static` next to the function, this only supports free functions:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(quantiles)]
+//! fn handle_request() {
+//!    println!("Some slow work");
+//! }
+//!
+//! # #[cfg(not(feature = "disabled"))]
+//! if let Some((p50, p90, p99)) = handle_request_quantiles() {
+//!     println!("p50: {p50:?}, p90: {p90:?}, p99: {p99:?}");
+//! }
+//! ```
+//!
+//! To only collect statistics, pass the `silent` flag next to them: every call is still measured
+//! and recorded by `quantiles`, `metric`, `otel`, `on_complete` or `collector`, but emits no
+//! event, so the data is only exposed by them. `aggregate` and `ring` emit no events already, and
+//! accept `silent` to say so. It requires one of these arguments, and can't be combined with those
+//! shaping the event, such as `level`, `structured` or `threshold`:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(quantiles, silent)]
+//! fn handle_request() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! With the `metrics` feature enabled, pass `metric` to also record every duration, in seconds,
+//! into a histogram of the `metrics` crate. The histogram is named after the function unless a
+//! name is given with `metric = "..."`. Durations are recorded regardless of any `threshold`:
+//! ```
+//! # use time_it::time_it;
+//! # #[cfg(feature = "metrics")]
+//! #[time_it(metric = "fetch_users_seconds")]
+//! fn fetch_users() {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! With the `alloc_stats` feature enabled and the counting allocator installed with
+//! [`count_allocations!`](crate::count_allocations!), pass `allocs` to also report the number of
+//! allocations and bytes allocated by the current thread during the call. They are appended to the
+//! message, e.g. `, allocations: 12 (4096 bytes)`, or added as `allocations` and `allocated_bytes`
+//! fields in `structured` and `span` mode. Reallocations count as allocations of their new size.
+//! The counters are per thread, so `allocs` is rejected on async functions and with `future`, and
+//! allocations made by other threads on behalf of the call are not included:
+//! ```
+//! # use time_it::time_it;
+//! # #[cfg(feature = "alloc_stats")]
+//! time_it::count_allocations!();
+//!
+//! # #[cfg(feature = "alloc_stats")]
+//! #[time_it(allocs)]
+//! fn build_index(words: &[&str]) -> Vec<String> {
+//!     words.iter().map(|word| word.to_lowercase()).collect()
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! With the `opentelemetry` feature enabled, pass `otel` to also set every duration, in
+//! milliseconds, as the `duration_ms` attribute of the OpenTelemetry span backing the current
+//! `tracing` span. This goes through the `tracing-opentelemetry` bridge, so the subscriber must
+//! include its `OpenTelemetryLayer`. Like histograms, attributes are set regardless of any
+//! `threshold`. `span` mode already exports its `duration_ms` field, so `otel` cannot be combined
+//! with it:
+//! ```
+//! # use time_it::time_it;
+//! # #[cfg(feature = "opentelemetry")]
+//! #[time_it(otel)]
+//! #[tracing::instrument]
+//! async fn handle(id: u32) {
+//!    println!("Some slow work");
+//! }
+//! ```
+//!
+//! # Impl blocks and modules
+//!
+//! To time every method of an impl block, annotate the block itself. Each method is timed as if it
+//! was annotated with the same arguments, except for methods with their own `#[time_it]`
+//! attribute, whose arguments take precedence, and `const fn`s, which are left unchanged. `label`
+//! and `aggregate` are not supported on impl blocks:
+//! ```
+//! # use time_it::time_it;
+//! # struct Client;
+//! #[time_it("info")]
+//! impl Client {
+//!     fn connect(&mut self) {
+//!         println!("Some slow work");
+//!     }
+//!
+//!     async fn fetch(&self, id: u32) -> String {
+//!         format!("Entry {id}")
+//!     }
+//! }
+//! ```
+//!
+//! Trait impls can be annotated too. Every signature is emitted exactly as written, including
+//! generics, lifetimes, `where` clauses and `impl Trait` types, so the methods still match the
+//! trait, and the futures of `async fn`s and `future` methods stay `Send` when what they hold is.
+//! Arguments are applied to every method, so those with requirements, such as `err_level` on
+//! methods returning `Result` or `poll_count` on futures, belong on the methods meeting them.
+//!
+//! To leave a method of such an impl block out, such as a trivial getter, mark it with
+//! `#[time_it(skip)]`. The marker is removed by the impl level attribute and the method is left
+//! unchanged. The most specific attribute always wins: `skip` or a method's own `#[time_it]`
+//! arguments take precedence over the impl block's. `skip` must be the only argument, and a
+//! function marked with it outside of an impl block is left unchanged as well:
+//! ```
+//! # use time_it::time_it;
+//! # struct Client {
+//! #     id: u32,
+//! # }
+//! #[time_it]
+//! impl Client {
+//!     fn connect(&mut self) {
+//!         println!("Some slow work");
+//!     }
+//!
+//!     #[time_it(skip)]
+//!     fn id(&self) -> u32 {
+//!         self.id
+//!     }
+//! }
+//! ```
+//!
+//! Inline modules can be annotated the same way, timing every free function directly inside them.
+//! Nested modules, impl blocks and other items are left unchanged, so they are only timed when
+//! annotated themselves. Paths given to the arguments, such as `on_complete`, are resolved inside
+//! the module, and so is the own `#[time_it]` of a function, which needs a `use` of the macro in
+//! the module or its full path. `label` is not supported on modules:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it("info")]
+//! mod storage {
+//!     pub fn load() {
+//!         println!("Some slow work");
+//!     }
+//!
+//!     #[time_it::time_it("trace")]
+//!     pub fn flush() {
+//!         println!("Some slow work");
+//!     }
+//!
+//!     #[time_it(skip)]
+//!     pub fn path() -> &'static str {
+//!         "data"
+//!     }
+//! }
+//! ```
+//!
+//! A function can only have one `#[time_it]` attribute, as stacking them would time the inner
+//! attribute's reporting too. Stacked attributes are rejected, so merge their arguments instead.
+//!
+//! # Debug builds
+//!
+//! To only time a function in builds with debug assertions enabled, pass the `debug_only` flag.
+//! In other builds the function is emitted unchanged:
+//! ```
+//! # use time_it::time_it;
+//! #[time_it(debug_only)]
+//! fn dev_only() {
+//!    println!("Some slow work");
+//! }
+//! ```
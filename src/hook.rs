@@ -0,0 +1,67 @@
+//! The hook every measurement is passed to, with the `global_hook` feature.
+
+use core::time::Duration;
+use std::sync::{Arc, Mutex, PoisonError};
+
+#[cfg(not(feature = "tracing"))]
+use log::Level;
+#[cfg(feature = "tracing")]
+use tracing::Level;
+
+/// A hook, called with the name of the timed code, the duration and the level of each
+/// measurement.
+pub type Hook = dyn Fn(&str, Duration, Level) + Send + Sync;
+
+static HOOK: Mutex<Option<Arc<Hook>>> = Mutex::new(None);
+
+/// Installs `hook`, replacing the previous one.
+///
+/// The hook is called once per call, on the thread that made it, before the call is reported,
+/// whatever the arguments of the timed code, so it can route every timing to a profiler without
+/// changing the annotations. That includes calls reported without an event, e.g. with `span`,
+/// `aggregate`, `ring`, `handler` or `sink`, and calls below a `threshold`. The level is the one
+/// the call's event is emitted at, or would be. Only calls left out by `sample`, `first`,
+/// `min_report`, `top_only`, `env_gate` or a paused runtime switch aren't passed to it.
+///
+/// As timed code can run on any thread, the hook must be `Send + Sync`. It's kept behind a
+/// `Mutex`, but the lock is released before calling it, so concurrent calls run it in parallel,
+/// and it can call timed code or replace itself without deadlocking:
+/// ```
+/// use time_it::time_it;
+///
+/// #[time_it("info")]
+/// fn handle_request() {
+///     println!("Some slow work");
+/// }
+///
+/// time_it::set_global_hook(|name, duration, level| {
+///     println!("{name} took {duration:?} at {level}");
+/// });
+/// handle_request(); // Passed to the hook, then logged
+/// time_it::clear_global_hook();
+/// ```
+///
+/// The hook is shared by every crate using `#[time_it]` in the process. With the `disabled`
+/// feature, it can still be installed, so code installing it keeps compiling, but nothing is
+/// passed to it.
+pub fn set_global_hook<F>(hook: F)
+where
+    F: Fn(&str, Duration, Level) + Send + Sync + 'static,
+{
+    *HOOK.lock().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(hook));
+}
+
+/// Removes the hook, if any.
+pub fn clear_global_hook() {
+    *HOOK.lock().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Passes a measurement to the hook, if any.
+pub fn call(name: &str, duration: Duration, level: Level) {
+    // Released before the call, so the hook can replace itself, and timed code it calls reports
+    // to it without deadlocking.
+    let hook = HOOK.lock().unwrap_or_else(PoisonError::into_inner).clone();
+    if let Some(hook) = hook {
+        hook(name, duration, level);
+    }
+}
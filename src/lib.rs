@@ -1,129 +1,372 @@
-//! This proc_macro library exposes the `#[time_it]` attribute macro.
+//! This library exposes the `#[time_it]` attribute macro.
 //! Annotating a function with this macro will generate a corresponding tracing event with the
 //! function's execution time. By default the macro will emit DEBUG level events. This can be
 //! customized by passing the desired log level as a macro argument: `#[time_it("trace")]`.
-//! Works with both regular `fn`s and `async` `fn`s.
+//! The function name used in the event can be replaced with a custom label:
+//! `#[time_it(label = "db query")]`. Events can be restricted to slow calls with a minimum
+//! duration: `#[time_it(threshold = "50ms")]`, and rendered in a rounded form with
+//! `#[time_it(format = "human")]` or converted to a fixed unit with `#[time_it(unit = "ms")]`.
+//! Works with both regular `fn`s and `async` `fn`s, independently of the async runtime. Every
+//! argument is described in the [`docs`] module.
 //!
+//! To time a region inside a larger function, the `time_block!` macro accepts the same arguments
+//! and times a single block: `let users = time_block!("db query", { fetch_users() });`.
+//!
+//! Events are emitted through `tracing` by default. Disabling default features and enabling the
+//! `log` feature makes the macro emit them through the `log` crate instead. When both features
+//! are enabled, `tracing` takes precedence. For embedded targets, the `defmt` feature emits them
+//! through `defmt` instead, with durations in microseconds.
+//!
+//! The generated code refers to `tracing` through a re-export of this crate, so crates using the
+//! macros don't need to depend on it themselves. `log` and `defmt` are used by path, and have to
+//! be direct dependencies of those crates with their features.
+//!
+//! Enabling the `metrics` feature allows recording durations into `metrics` histograms with
+//! `#[time_it(metric)]`, and the `opentelemetry` feature allows setting them as attributes of
+//! OpenTelemetry spans with `#[time_it(otel)]`.
+//!
+//! Enabling the `alloc_stats` feature allows reporting the allocations made during each call with
+//! `#[time_it(allocs)]`, once the counting allocator is installed with `count_allocations!()`.
+//!
+//! Enabling the `runtime_switch` feature allows pausing the reporting of every timed function at
+//! runtime, with `set_enabled`.
+//!
+//! Enabling the `global_hook` feature allows intercepting every measurement in one place, with a
+//! hook installed by `set_global_hook`.
+//!
+//! Enabling the `testing` feature adds the `testing` module, capturing the timing events emitted
+//! by timed code so tests can assert on them.
+//!
+//! Enabling the `no_std` feature makes the generated code only rely on `core`, for `#![no_std]`
+//! crates. Every timed function then needs a `clock_fn`, such as a hardware timer, and the
+//! arguments relying on `std` or allocations, like `log_args` or `thread`, are rejected. Events
+//! can still be emitted through `tracing` or `log` without their `std` features, or through
+//! `defmt` with the `defmt` feature.
+//!
+//! Enabling the `disabled` feature turns the macro into a no-op: annotated functions are emitted
+//! unchanged, without any timing or logging code.
+//!
+//! The `max_level_off`, `max_level_error`, `max_level_warn`, `max_level_info`, `max_level_debug`
+//! and `max_level_trace` features do the same for functions whose events are all below the given
+//! level, so with `max_level_info` a function annotated with `#[time_it("debug")]` is emitted
+//! unchanged, while `#[time_it("debug", err_level = "error")]` is still timed. The most
+//! restrictive level applies when several of them are enabled. Functions also reporting through
+//! `aggregate`, `ring`, `quantiles`, `metric`, `otel`, `on_complete`, `handler`, `sink`,
+//! `collector`, `output` or `in_span` are always timed.
+
+#![no_std]
 
-use proc_macro::TokenStream;
-use quote::quote;
-use strum::EnumString;
-use syn::{LitStr, Token, punctuated::Punctuated};
+use core::time::Duration;
 
-/// Attribute function used to annotate functions that should output their execution time using the
-/// `tracing` library. Works with both async and non-async functions. By default, this macro will use the "DEBUG" log level.
+#[cfg(not(feature = "no_std"))]
+extern crate std;
+
+// The generated code refers to this crate as `::time_it`, including in the `Timer` defined here.
+extern crate self as time_it;
+
+#[cfg(not(feature = "no_std"))]
+pub mod channel;
+pub mod docs;
+#[cfg(all(
+    feature = "global_hook",
+    not(feature = "no_std"),
+    any(feature = "tracing", feature = "log")
+))]
+mod hook;
+#[cfg(all(feature = "scopes", not(feature = "no_std")))]
+mod scopes;
+#[cfg(not(feature = "no_std"))]
+mod start;
+#[cfg(feature = "runtime_switch")]
+mod switch;
+#[cfg(all(feature = "testing", feature = "tracing", not(feature = "no_std")))]
+pub mod testing;
+#[cfg(all(not(feature = "no_std"), any(feature = "tracing", feature = "log")))]
+mod timer;
+
+#[cfg(all(
+    feature = "global_hook",
+    not(feature = "no_std"),
+    any(feature = "tracing", feature = "log")
+))]
+pub use hook::{Hook, clear_global_hook, set_global_hook};
+#[cfg(all(feature = "scopes", not(feature = "no_std")))]
+pub use scopes::in_scope;
+#[cfg(feature = "runtime_switch")]
+pub use switch::{is_enabled, set_enabled};
+#[cfg(all(not(feature = "no_std"), any(feature = "tracing", feature = "log")))]
+pub use timer::Timer;
+
+/// Attribute macro used to annotate functions that should output their execution time, through
+/// `tracing` or the backend enabled in its place. Works with both async and non-async functions.
+/// By default, this macro will use the "DEBUG" log level.
 ///
 /// # Example
 ///
-/// ```rust,ignore
+/// ```
 /// use time_it::time_it;
 ///
-/// #[tokio::main]
-/// async fn main() {
-///    tracing_subscriber::fmt()
-///       .with_max_level(Level::DEBUG)
-///       .init();
-///     test_function().await;
-///     println!("Hello, world!");
+/// #[time_it]
+/// fn test_function() {
+///    println!("Some slow work");
 /// }
 ///
 /// #[time_it]
-/// async fn test_function() {
-///    println!("Some slow work");
+/// async fn test_async_function() {
+///    println!("Some slow async work");
 /// }
 ///
+/// fn main() {
+///     test_function();
+/// }
 /// ```
 ///
-/// If you want to use a different log level, pass the level to to attribtue macro:
-/// ```rust,ignore
+/// If you want to use a different log level, pass the level to the attribute macro:
+/// ```
+/// # use time_it::time_it;
 /// #[time_it("trace")]
 /// async fn test_function() {
 ///    println!("Some slow work");
 /// }
 /// ```
-#[proc_macro_attribute]
-pub fn time_it(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = syn::parse_macro_input!(item as syn::ItemFn);
-    let fn_name = &input.sig.ident;
-    let fn_block = &input.block;
-    let fn_vis = &input.vis;
-    let fn_sig = &input.sig;
-    let fn_attrs = &input.attrs;
-    let asyncness = &input.sig.asyncness;
-
-    let timed_fn_block = if asyncness.is_some() {
-        quote! {
-            let __start = tokio::time::Instant::now();
-            let result = async move { #fn_block }.await;
-            let __duration = __start.elapsed();
-        }
-    } else {
-        quote! {
-            let __start = std::time::Instant::now();
-            let result = (|| #fn_block)();
-            let __duration = __start.elapsed();
-        }
-    };
-
-    let log_level = syn::parse_macro_input!(attr as LogLevel);
-    let log_line = match log_level {
-        LogLevel::Trace => {
-            quote! {tracing::trace!("[{}]: Execution time: {:?}", stringify!(#fn_name), __duration);}
-        }
-        LogLevel::Debug => {
-            quote! {tracing::debug!("[{}]: Execution time: {:?}", stringify!(#fn_name), __duration);}
-        }
-        LogLevel::Info => {
-            quote! {tracing::info!("[{}]: Execution time: {:?}", stringify!(#fn_name), __duration);}
-        }
-        LogLevel::Warn => {
-            quote! {tracing::warn!("[{}]: Execution time: {:?}", stringify!(#fn_name), __duration);}
-        }
-        LogLevel::Error => {
-            quote! {tracing::error!("[{}]: Execution time: {:?}", stringify!(#fn_name), __duration);}
-        }
-    };
-
-    quote::quote! {
-        #(#fn_attrs)*
-        #fn_vis #fn_sig {
-            #timed_fn_block
-            #log_line
-            result
-        }
-    }
-    .into()
-}
+///
+/// The other arguments are passed as comma separated `key = "value"` pairs and flags, as in
+/// `#[time_it("info", label = "db query", threshold = "50ms")]`. Impl blocks and inline modules
+/// can be annotated too, timing each of their functions. Every argument, and how the annotated
+/// items are timed, is described in the [`docs`] module.
+pub use time_it_macros::time_it;
 
-#[derive(Default, EnumString)]
-#[strum(ascii_case_insensitive)]
-enum LogLevel {
-    Trace,
-    #[default]
-    Debug,
-    Info,
-    Warn,
-    Error,
+/// Function-like macro timing a single block, for regions smaller than a whole function. The
+/// label naming the block is passed first and the block last, and the macro evaluates to the
+/// value of the block:
+/// ```
+/// use time_it::time_block;
+///
+/// fn handle(request: &str) -> usize {
+///     let parsed = time_block!("parsing", { request.split(',').count() });
+///     parsed * 2
+/// }
+/// ```
+///
+/// The block runs in place, so `.await`, `?` and `return` behave as if the macro wasn't there,
+/// and it can be used in both sync and async contexts. The arguments of `#[time_it]` can be passed
+/// between the label and the block, except for `label`, `debug_only`, `span`, `err_level`,
+/// `err_template`, `skip_err`, `log_result`, `log_args`, `aggregate` and `kind`, which only apply
+/// to functions:
+/// ```
+/// # use time_it::time_block;
+/// # async fn fetch(url: &str) -> std::io::Result<String> {
+/// #     Ok(url.to_owned())
+/// # }
+/// # async fn download(url: &str) -> std::io::Result<String> {
+/// let body = time_block!("download", "info", threshold = "100ms", {
+///     fetch(url).await?
+/// });
+/// # Ok(body)
+/// # }
+/// ```
+///
+/// This macro is called `time_block!` rather than `time_it!`, as a crate cannot export an attribute
+/// macro and a function-like macro under the same name.
+pub use time_it_macros::time_block;
+
+/// Function-like macro timing a block at a given level, taking only the name, the level and the
+/// block. It produces the same measurement and event as `#[time_it]` on a function of that name,
+/// so inline and attribute timing read the same in the logs:
+/// ```
+/// use time_it::time_scope;
+///
+/// fn handle(request: &str) -> usize {
+///     let parsed = time_scope!("parsing", info, { request.split(',').count() });
+///     parsed * 2
+/// }
+/// ```
+///
+/// The level is given as for `#[time_it]`, as `info`, `"info"` or `Level::INFO`. The block runs in
+/// place as with `time_block!`, which takes the other arguments when more control is needed.
+pub use time_it_macros::time_scope;
+
+/// Function-like macro timing every call of a closure, as the attribute cannot be placed on
+/// closures. The label naming the closure is passed first and the closure last, and the macro
+/// evaluates to the timed closure:
+/// ```
+/// use time_it::time_closure;
+///
+/// let double = time_closure!("double", |x: u32| x * 2);
+/// let doubled: Vec<u32> = (0..10).map(double).collect();
+/// ```
+///
+/// Only the body of the closure is changed, so it captures the same values and implements the
+/// same `Fn`, `FnMut` or `FnOnce` traits as written, and `move` and `async` closures are supported.
+/// The timed closure is `Send` and `Sync` whenever the original closure is, so it can be passed to
+/// the parallel iterators of Rayon, e.g. `.par_iter().map(time_closure!("item", |x| work(x)))`.
+/// All closures created by the same invocation share the counters of `seq`, `first` and `sample`.
+/// The arguments are those of `time_block!`, with the same exceptions:
+/// ```
+/// # use time_it::time_closure;
+/// let mut total = 0;
+/// let mut add = time_closure!("add", "info", threshold = "1ms", |x: u32| total += x);
+/// add(1);
+/// add(2);
+/// ```
+pub use time_it_macros::time_closure;
+
+/// Function-like macro grouping the timed calls made while a block runs, and reporting them
+/// together as a single event once it completes. It takes a name, an optional level and the
+/// block, and evaluates to the value of the block:
+/// ```
+/// # use time_it::time_it;
+/// #[time_it]
+/// fn query(id: u32) {}
+///
+/// # #[cfg(feature = "scopes")]
+/// fn handle(ids: &[u32]) {
+///     time_it::scope!("request", info, {
+///         for &id in ids {
+///             query(id);
+///         }
+///     });
+/// } // Logs e.g. "[request]: Execution time: 3.1ms, breakdown: query: 2.9ms (3 calls)"
+/// ```
+///
+/// The event holds the duration of the block, followed by the total time and number of calls of
+/// each name timed in it, in the order they were first called, or `none`. The timed functions
+/// still report their own calls as usual, the scope only adds them up. This requires the `scopes`
+/// feature, which also makes every timed function, block and timer add its measurement to the
+/// innermost open scope of the thread it completes on, at the cost of a thread local lookup per
+/// call. `time_it::in_scope()` tells whether the current thread is inside a scope.
+///
+/// Scopes nest: calls are only added to the innermost scope, and a nested scope reports its own
+/// breakdown when it completes, then is added to the enclosing scope as a single entry under its
+/// name. Timed functions calling each other are each added, so the entries can overlap and add
+/// up to more than the scope. Scopes are kept per thread, so calls made on other threads are not
+/// added, and a block awaiting futures that resume on another thread is not supported. The level
+/// defaults to DEBUG, and the `defmt` backend and the `no_std` feature are not supported.
+pub use time_it_macros::scope;
+
+/// Defines the registry of the time left out of measurements by `exclude!`.
+///
+/// Invoke it once, at the root of the crate whose functions are timed with `exclude`. It generates
+/// a public `time_it_exclusions` module there, holding the time excluded under each name per
+/// thread. Each call timed with `exclude = "name"` starts from zero and subtracts the time
+/// registered under its name once it completes, and `excluded(name)` returns the time registered
+/// so far in the innermost such call on the current thread. A nested call's excluded time is
+/// added back to the calls it runs in, as it's part of their duration too.
+///
+/// With the `disabled` feature, the registry is still generated, so code referring to it keeps
+/// compiling, but nothing is added to it. The `no_std` feature is not supported.
+pub use time_it_macros::exclusions;
+
+/// Function-like macro leaving the time an expression takes out of the calls timed with
+/// `exclude`. It takes the name of the exclusion and the expression, and evaluates to its value:
+/// ```
+/// # use std::sync::Mutex;
+/// # use time_it::time_it;
+/// time_it::exclusions!();
+///
+/// #[time_it(exclude = "lock")]
+/// fn update(counter: &Mutex<u64>) {
+///     let mut counter = time_it::exclude!("lock", counter.lock().unwrap());
+///     *counter += 1;
+/// }
+/// # fn main() {}
+/// ```
+///
+/// The expression is timed with `std::time::Instant`, and its time is registered under the name in
+/// the module defined by `exclusions!`, on the current thread. Only the calls timed with the same
+/// name leave it out: a function timed with `exclude = "io"` still counts the time spent waiting
+/// for a lock registered as `"lock"`. Outside of such calls the time is registered, but subtracted
+/// from nothing. With the `disabled` feature, the expression is left as written.
+pub use time_it_macros::exclude;
+
+/// Installs the global allocator counting the allocations reported by `allocs`. Requires the
+/// `alloc_stats` feature.
+///
+/// Invoke it once, at the root of the crate whose functions use `allocs`. It generates a hidden
+/// `__time_it_allocs` module there, wrapping `std::alloc::System` with per thread counters, and
+/// registers it as the `#[global_allocator]`, so the program must not install another one:
+/// ```
+/// # use time_it::time_it;
+/// # #[cfg(feature = "alloc_stats")]
+/// time_it::count_allocations!();
+///
+/// # #[cfg(feature = "alloc_stats")]
+/// #[time_it(allocs)]
+/// fn build_index(words: &[&str]) -> Vec<String> {
+///     words.iter().map(|word| word.to_lowercase()).collect()
+/// }
+/// # fn main() {}
+/// ```
+///
+/// With the `disabled` feature, nothing is reported, so nothing is installed either.
+pub use time_it_macros::count_allocations;
+
+#[cfg(all(feature = "testing", not(feature = "tracing")))]
+compile_error!("the `testing` feature requires the `tracing` backend, whose events it captures");
+
+/// A value accumulating the measurements of the code timed with `collector`, such as a `static`
+/// summarized at shutdown.
+///
+/// `#[time_it(collector = "TIMINGS")]` calls `TimeItCollector::record(&TIMINGS, name, duration)`
+/// each time the timed code completes, so `TIMINGS` must implement the trait itself. The name is
+/// a `&'static str`, as for `on_complete` handlers, so it can be kept without copying it:
+/// ```
+/// use std::sync::Mutex;
+/// use std::time::Duration;
+///
+/// use time_it::{TimeItCollector, time_it};
+///
+/// struct Timings(Mutex<Vec<(&'static str, Duration)>>);
+///
+/// impl TimeItCollector for Timings {
+///     fn record(&self, name: &'static str, duration: Duration) {
+///         self.0.lock().unwrap().push((name, duration));
+///     }
+/// }
+///
+/// static TIMINGS: Timings = Timings(Mutex::new(Vec::new()));
+///
+/// #[time_it(collector = "TIMINGS")]
+/// fn observed() {
+///     println!("Some slow work");
+/// }
+///
+/// observed();
+/// # #[cfg(not(feature = "disabled"))]
+/// assert_eq!(TIMINGS.0.lock().unwrap().len(), 1);
+/// ```
+pub trait TimeItCollector {
+    /// Adds the measurement of a call to the code called `name`.
+    fn record(&self, name: &'static str, duration: Duration);
 }
 
-impl syn::parse::Parse for LogLevel {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let punctuated: Punctuated<LitStr, Token![,]> = Punctuated::parse_terminated(input)?;
-        if punctuated.len() > 1 {
-            return Err(syn::Error::new(
-                input.span(),
-                "Unexpected multiple macro arguments",
-            ));
-        }
-        let mut iter = punctuated.into_iter();
-
-        let Some(first) = iter.next() else {
-            return Ok(Self::default());
-        };
-
-        first
-            .value()
-            .parse()
-            .map_err(|e| syn::Error::new(input.span(), format!("{e:?}")))
+#[cfg(not(feature = "no_std"))]
+impl<T: TimeItCollector, F: FnOnce() -> T> TimeItCollector for std::sync::LazyLock<T, F> {
+    fn record(&self, name: &'static str, duration: Duration) {
+        T::record(self, name, duration);
     }
 }
+
+/// The `tracing` the generated code calls, which isn't part of the public API.
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+pub use tracing as __tracing;
+
+/// Items the generated code calls, which aren't part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(not(feature = "no_std"))]
+    pub use crate::channel::send;
+    #[cfg(all(
+        feature = "global_hook",
+        not(feature = "no_std"),
+        any(feature = "tracing", feature = "log")
+    ))]
+    pub use crate::hook::call as hook_call;
+    #[cfg(all(feature = "scopes", not(feature = "no_std")))]
+    pub use crate::scopes::{enter as enter_scope, exit as exit_scope, record as record_in_scope};
+    #[cfg(not(feature = "no_std"))]
+    pub use crate::start::elapsed as since_start;
+    #[cfg(feature = "runtime_switch")]
+    pub use crate::switch::is_enabled;
+}
@@ -0,0 +1,63 @@
+//! The registry of the scopes opened by `scope!`, with the `scopes` feature.
+
+use core::cell::RefCell;
+use core::time::Duration;
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+/// Total time and number of calls of each name measured in a scope, in the order of their first
+/// call.
+type Breakdown = Vec<(String, Duration, u64)>;
+
+std::thread_local! {
+    static OPEN: RefCell<Vec<Breakdown>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Whether the current thread is inside a `scope!`.
+///
+/// With the `scopes` feature, every timed function, block and timer adds its measurement to the
+/// innermost open scope of the thread it completes on, at the cost of a thread local lookup per
+/// call. With the `disabled` feature, nothing is added, and no scope is ever open.
+pub fn in_scope() -> bool {
+    OPEN.with_borrow(|open| !open.is_empty())
+}
+
+/// Opens a scope on the current thread.
+pub fn enter() {
+    OPEN.with_borrow_mut(|open| open.push(Vec::new()));
+}
+
+/// Closes the innermost scope, adding it to the enclosing one as `name`, and returns its formatted
+/// breakdown.
+pub fn exit(name: &str, duration: Duration) -> String {
+    let breakdown = OPEN.with_borrow_mut(Vec::pop).unwrap_or_default();
+    record(name, duration);
+    if breakdown.is_empty() {
+        return String::from("none");
+    }
+    let entries: Vec<String> = breakdown
+        .iter()
+        .map(|(name, total, calls)| {
+            let plural = if *calls == 1 { "call" } else { "calls" };
+            format!("{name}: {total:?} ({calls} {plural})")
+        })
+        .collect();
+    entries.join(", ")
+}
+
+/// Adds a call to the innermost scope, if any.
+pub fn record(name: &str, duration: Duration) {
+    OPEN.with_borrow_mut(|open| {
+        let Some(breakdown) = open.last_mut() else {
+            return;
+        };
+        match breakdown.iter_mut().find(|(recorded, ..)| recorded == name) {
+            Some((_, total, calls)) => {
+                *total += duration;
+                *calls += 1;
+            }
+            None => breakdown.push((String::from(name), duration, 1)),
+        }
+    });
+}
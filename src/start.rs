@@ -0,0 +1,12 @@
+//! The baseline the timestamps of `since_start` are relative to.
+
+use core::time::Duration;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Time elapsed since the baseline, which is set by the first call.
+pub fn elapsed() -> Duration {
+    START.get_or_init(Instant::now).elapsed()
+}
@@ -0,0 +1,35 @@
+//! The switch pausing the reporting of every timed function, with the `runtime_switch` feature.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Resumes reporting with `true`, or pauses it with `false`.
+///
+/// Pausing skips every event, aggregate, histogram and handler of the timed code in the process, at
+/// the cost of a relaxed load per call. The clock still runs while paused, and spans of `span` mode
+/// are still created, only the reporting is skipped:
+/// ```
+/// use time_it::time_it;
+///
+/// #[time_it]
+/// fn handle_request() {
+///     println!("Some slow work");
+/// }
+///
+/// time_it::set_enabled(false);
+/// handle_request(); // Not reported
+/// time_it::set_enabled(true);
+/// handle_request(); // Reported
+/// ```
+///
+/// With the `disabled` feature, the switch can still be toggled, so code toggling it keeps
+/// compiling, but nothing is reported either way.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether timed code is reported, which is the case until paused.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
@@ -0,0 +1,178 @@
+//! Capturing the timing events of timed code in tests, with the `testing` feature.
+//!
+//! [`capture`] sets a subscriber collecting the timing events emitted on the current thread as its
+//! default, until the returned [`Capture`] is dropped, and [`Capture::timings`] or
+//! [`Capture::named`] return the events collected so far:
+//! ```
+//! use time_it::time_it;
+//!
+//! #[time_it]
+//! fn handle() {}
+//!
+//! let capture = time_it::testing::capture();
+//! handle();
+//! assert_eq!(capture.named("handle").len(), 1);
+//! ```
+//!
+//! Each [`Timing`] has the `name`, `level` and `message` of the event, the `duration_ms` of
+//! structured events, and its other `fields`. The name is read from the first field of structured
+//! events, or the `[name]` prefix of messages, and events carrying neither are left out. Spans of
+//! `span` mode are not captured. This requires the `tracing` backend.
+
+use core::fmt::Debug;
+use std::format;
+use std::string::{String, ToString};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::vec::Vec;
+
+use tracing::field::{Field, Visit};
+use tracing::subscriber::DefaultGuard;
+use tracing::{Event, Level, Metadata, Subscriber, span};
+
+/// A timing event emitted while a `Capture` was active.
+#[derive(Clone, Debug)]
+pub struct Timing {
+    /// Name of the timed code, or its label.
+    pub name: String,
+    pub level: Level,
+    /// The message, e.g. `[name]: Execution time: 1.2ms` or `execution timed`.
+    pub message: String,
+    /// The duration in milliseconds, carried by structured events.
+    pub duration_ms: Option<f64>,
+    /// The other fields, with strings as written and other values formatted with `Debug`.
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl Timing {
+    /// The value of the field called `name`, if the event has one.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Collects the timing events emitted on the current thread, until dropped.
+#[must_use = "events are only captured while the capture is alive"]
+pub struct Capture {
+    timings: Arc<Mutex<Vec<Timing>>>,
+    _default: DefaultGuard,
+}
+
+impl Capture {
+    /// The timing events captured so far, in the order they were emitted.
+    pub fn timings(&self) -> Vec<Timing> {
+        self.timings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// The timing events captured so far for the code called `name`.
+    pub fn named(&self, name: &str) -> Vec<Timing> {
+        let mut timings = self.timings();
+        timings.retain(|timing| timing.name == name);
+        timings
+    }
+}
+
+/// Sets a subscriber capturing timing events as the default of the current thread, until the
+/// returned `Capture` is dropped. Other events are ignored.
+pub fn capture() -> Capture {
+    let timings = Arc::new(Mutex::new(Vec::new()));
+    let collector = Collector(Arc::clone(&timings));
+    Capture {
+        timings,
+        _default: tracing::subscriber::set_default(collector),
+    }
+}
+
+struct Collector(Arc<Mutex<Vec<Timing>>>);
+
+impl Subscriber for Collector {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        // Message events name the code in a prefix, structured events in their first field,
+        // whatever `name_field` calls it.
+        let name = match fields
+            .message
+            .strip_prefix('[')
+            .and_then(|message| message.split_once("]: "))
+        {
+            Some((name, _)) => name.to_string(),
+            None if STRUCTURED_MESSAGES.contains(&fields.message.as_str())
+                && !fields.fields.is_empty() =>
+            {
+                fields.fields.remove(0).1
+            }
+            None => return,
+        };
+        let timing = Timing {
+            name,
+            level: *event.metadata().level(),
+            message: fields.message,
+            duration_ms: fields.duration_ms,
+            fields: fields.fields,
+        };
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(timing);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+/// The messages of the structured events of `time_it`.
+const STRUCTURED_MESSAGES: [&str; 3] = [
+    "execution timed",
+    "execution started",
+    "fast calls summarized",
+];
+
+#[derive(Default)]
+struct Fields {
+    message: String,
+    duration_ms: Option<f64>,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Visit for Fields {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "duration_ms" {
+            self.duration_ms = Some(value);
+        }
+        self.fields.push((field.name(), value.to_string()));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            name => self.fields.push((name, value.to_string())),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        let value = format!("{value:?}");
+        match field.name() {
+            "message" => self.message = value,
+            name => self.fields.push((name, value)),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! The `Timer` guard, timing the scope it lives in without a macro at the call site.
+
+use core::time::Duration;
+use std::time::Instant;
+
+#[cfg(not(feature = "tracing"))]
+use log::Level;
+#[cfg(feature = "tracing")]
+use tracing::Level;
+
+/// Times the scope it lives in, emitting an event with the elapsed time when dropped.
+///
+/// `Timer::new(name, level)` starts the clock, and dropping the timer emits the event
+/// `#[time_it]` emits for a function called `name` at `level`, with the same message, target and
+/// `(panicked)` marker. The level is chosen at runtime, as a `tracing::Level`, or a `log::Level`
+/// with the `log` backend. `elapsed()` reads the time so far without stopping the timer:
+/// ```
+/// use time_it::Timer;
+///
+/// fn handle(request: &str) {
+///     let _timer = Timer::new("handle", tracing::Level::INFO);
+///     println!("Handling {request}");
+/// } // Logs e.g. "[handle]: Execution time: 1.2ms" at INFO
+/// # handle("request");
+/// ```
+///
+/// Assigning the timer to `_` drops it right away, so bind it to a named variable such as
+/// `_timer`. Timers are silenced by `set_enabled(false)` like the timed functions, but take none
+/// of their arguments, which `time_scope!` and `time_block!` offer for scopes known at compile
+/// time. The `defmt` backend and the `no_std` feature are not supported.
+#[must_use = "the timer reports when dropped, so an unused timer measures nothing"]
+pub struct Timer {
+    name: &'static str,
+    level: Level,
+    start: Instant,
+}
+
+impl Timer {
+    /// Starts timing, reporting as `name` at `level` once dropped.
+    pub fn new(name: &'static str, level: Level) -> Self {
+        Self {
+            name,
+            level,
+            start: Instant::now(),
+        }
+    }
+
+    /// Time elapsed since the timer was created.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        time_it_macros::__report_timer!();
+    }
+}
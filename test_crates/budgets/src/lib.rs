@@ -0,0 +1,15 @@
+//! Budgeted code defined in a library, whose budgets its integration tests check.
+
+use std::{thread, time::Duration};
+
+use time_it::time_it;
+
+#[time_it(budget = "5ms")]
+pub fn slow() {
+    thread::sleep(Duration::from_millis(20));
+}
+
+#[time_it(budget = "1s")]
+pub fn fast() -> u32 {
+    1
+}
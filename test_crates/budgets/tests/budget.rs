@@ -0,0 +1,13 @@
+//! `enforce_budgets` checks the budgets of a library in the tests of other crates, where it isn't
+//! compiled with `cfg(test)`.
+
+#[test]
+#[should_panic(expected = "[slow]: Execution time")]
+fn slow_calls_fail_the_test() {
+    budgets::slow();
+}
+
+#[test]
+fn calls_within_the_budget_pass() {
+    assert_eq!(budgets::fast(), 1);
+}
@@ -0,0 +1,16 @@
+//! Code timed with `time_it` in a crate that doesn't depend on `tracing` itself, which only builds
+//! if the generated code calls `tracing` through `time_it`.
+
+use time_it::{time_block, time_it};
+
+#[time_it("info")]
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[time_it("info", span, span_level = "trace")]
+pub fn spanned() {}
+
+pub fn blocks() -> u32 {
+    time_block!("block", "warn", { 1 })
+}
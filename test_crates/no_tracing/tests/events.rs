@@ -0,0 +1,32 @@
+//! Timed code emits its events without `tracing` among the dependencies of its crate.
+
+use time_it::testing::capture;
+
+#[test]
+fn functions_are_timed() {
+    let capture = capture();
+    assert_eq!(no_tracing::add(1, 2), 3);
+    let timings = capture.named("add");
+    assert_eq!(timings.len(), 1);
+    assert_eq!(timings[0].level.as_str(), "INFO");
+    assert!(timings[0].message.starts_with("[add]: Execution time: "));
+}
+
+#[test]
+fn spans_are_timed() {
+    let capture = capture();
+    no_tracing::spanned();
+    // Only the completion event is captured, not the span.
+    let timings = capture.named("spanned");
+    assert_eq!(timings.len(), 1);
+    assert_eq!(timings[0].level.as_str(), "INFO");
+}
+
+#[test]
+fn blocks_are_timed() {
+    let capture = capture();
+    assert_eq!(no_tracing::blocks(), 1);
+    let timings = capture.named("block");
+    assert_eq!(timings.len(), 1);
+    assert_eq!(timings[0].level.as_str(), "WARN");
+}
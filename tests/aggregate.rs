@@ -0,0 +1,47 @@
+//! `aggregate` counts calls and sums their durations, which can be read and reset at runtime.
+#![cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+
+mod common;
+
+use std::{cell::Cell, time::Duration};
+
+use time_it::time_it;
+
+thread_local! {
+    /// Time of the fake clock, only advanced by `handle`.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+#[time_it(aggregate, clock_fn = "FakeInstant::now")]
+fn handle(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+#[test]
+fn totals_are_snapshotted_and_reset() {
+    let ((), events) = common::capture(|| {
+        handle(10);
+        handle(20);
+    });
+    assert!(events.is_empty());
+    assert_eq!(handle_stats(), (2, Duration::from_millis(30)));
+    // Reading doesn't reset the totals, resetting returns them.
+    assert_eq!(handle_reset_stats(), (2, Duration::from_millis(30)));
+    assert_eq!(handle_stats(), (0, Duration::ZERO));
+
+    handle(5);
+    assert_eq!(handle_stats(), (1, Duration::from_millis(5)));
+}
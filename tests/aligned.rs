@@ -0,0 +1,95 @@
+//! `format = "aligned"` renders durations as right-justified milliseconds of a fixed width, so the
+//! durations of consecutive lines line up.
+
+mod common;
+
+use std::{cell::Cell, time::Duration};
+
+use time_it::{time_block, time_it};
+
+thread_local! {
+    /// Duration measured by every call on the fake clock.
+    static ELAPSED: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock, which always measures `ELAPSED`.
+struct FakeInstant;
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self
+    }
+
+    #[allow(clippy::unused_self)]
+    fn elapsed(&self) -> Duration {
+        ELAPSED.with(Cell::get)
+    }
+}
+
+#[time_it(format = "aligned", clock_fn = "FakeInstant::now")]
+fn aligned() {}
+
+#[time_it(
+    format = "aligned",
+    width = 6,
+    precision = 1,
+    clock_fn = "FakeInstant::now"
+)]
+fn narrow() {}
+
+#[time_it("repr=aligned;width=12;clock_fn=FakeInstant::now")]
+fn packed() {}
+
+#[time_it(
+    format = "aligned",
+    template = "{duration} | {name}",
+    clock_fn = "FakeInstant::now"
+)]
+fn leading() {}
+
+/// The duration `timed` renders once the fake clock measures `nanos`.
+fn rendered(timed: fn(), nanos: u64) -> String {
+    ELAPSED.with(|elapsed| elapsed.set(Duration::from_nanos(nanos)));
+    let ((), events) = common::capture(timed);
+    let (_, duration) = events[0].split_once("Execution time: ").unwrap();
+    duration.trim_end().to_owned()
+}
+
+#[test]
+fn durations_have_a_fixed_width() {
+    for nanos in [0, 1_234, 1_234_567, 12_345_678, 1_234_567_890] {
+        let duration = rendered(aligned, nanos);
+        assert_eq!(duration.len(), "99999.999 ms".len(), "{duration:?}");
+    }
+    assert_eq!(rendered(aligned, 1_234_567), "    1.235 ms");
+    assert_eq!(rendered(aligned, 12_345_678), "   12.346 ms");
+    assert_eq!(rendered(aligned, 0), "    0.000 ms");
+}
+
+#[test]
+fn width_and_precision_are_configurable() {
+    assert_eq!(rendered(narrow, 1_234_567), "   1.2 ms");
+    assert_eq!(rendered(narrow, 1_234_567_890), "1234.6 ms");
+    assert_eq!(rendered(packed, 1_234_567), "       1.235 ms");
+}
+
+#[test]
+fn longer_durations_widen_the_field() {
+    assert_eq!(rendered(narrow, 123_456_789_000), "123456.8 ms");
+}
+
+#[test]
+fn templates_can_start_with_the_aligned_column() {
+    ELAPSED.with(|elapsed| elapsed.set(Duration::from_micros(2_500)));
+    let ((), events) = common::capture(leading);
+    assert_eq!(events, ["message=    2.500 ms | leading "]);
+}
+
+#[test]
+fn blocks_are_aligned() {
+    let ((), events) = common::capture(|| {
+        time_block!("block", format = "aligned", width = 10, {});
+    });
+    let (_, duration) = events[0].split_once("Execution time: ").unwrap();
+    assert_eq!(duration.trim_end().len(), 13, "{duration:?}");
+}
@@ -0,0 +1,41 @@
+//! `allocs` reports the allocations made during the call, counted by `count_allocations!`.
+#![cfg(feature = "alloc_stats")]
+
+mod common;
+
+use time_it::time_it;
+
+time_it::count_allocations!();
+
+#[time_it(allocs)]
+fn allocating() -> Vec<u64> {
+    Vec::with_capacity(16)
+}
+
+#[time_it(allocs, structured)]
+fn allocating_structured() -> Vec<u64> {
+    Vec::with_capacity(16)
+}
+
+#[time_it(allocs)]
+fn not_allocating() -> u64 {
+    1
+}
+
+#[test]
+fn allocations_are_reported() {
+    let (_, events) = common::capture(|| {
+        allocating();
+        not_allocating();
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[0].ends_with(", allocations: 1 (128 bytes) "));
+    assert!(events[1].ends_with(", allocations: 0 (0 bytes) "));
+}
+
+#[test]
+fn structured_events_carry_allocation_fields() {
+    let (_, events) = common::capture(allocating_structured);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains("allocations=1 allocated_bytes=128 "));
+}
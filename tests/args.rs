@@ -0,0 +1,35 @@
+//! Argument lists without any argument in them behave like the bare attribute, and durations
+//! accept every unit.
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it()]
+fn empty_parens() {}
+
+#[time_it("info")]
+fn trailing_comma() {}
+
+#[time_it(threshold = "1m", min_report = "0µs")]
+fn minutes() {}
+
+#[test]
+fn empty_parens_use_the_defaults() {
+    let ((), events) = common::capture(empty_parens);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[empty_parens]: Execution time: "));
+}
+
+#[test]
+fn trailing_comma_is_accepted() {
+    let ((), events) = common::capture(trailing_comma);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[trailing_comma]: Execution time: "));
+}
+
+#[test]
+fn durations_accept_minutes_and_micro_signs() {
+    let ((), events) = common::capture(minutes);
+    assert!(events.is_empty());
+}
@@ -0,0 +1,178 @@
+//! `async fn`s and methods returning `impl Future` in traits are timed like their free function
+//! counterparts, both as default methods and in implementations.
+
+mod common;
+
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+/// Polls `future` to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            return output;
+        }
+    }
+}
+
+/// A future that is pending on its first poll, so the awaiting future suspends.
+async fn yield_once() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
+trait Store {
+    fn key(&self) -> u32;
+
+    #[time_it(log_result)]
+    async fn load(&self) -> u32 {
+        yield_once().await;
+        self.key() * 2
+    }
+
+    async fn save(&self, value: u32) -> u32;
+
+    #[time_it(future)]
+    fn fetch(&self) -> impl Future<Output = u32> + Send {
+        let key = self.key();
+        async move {
+            yield_once().await;
+            key
+        }
+    }
+
+    fn flush(&self) -> impl Future<Output = ()>;
+
+    /// Implemented by an `async fn`, whose future must be `Send` once timed.
+    fn ping(&self) -> impl Future<Output = u32> + Send;
+}
+
+struct Memory;
+
+#[time_it("info")]
+impl Store for Memory {
+    #[time_it(skip)]
+    fn key(&self) -> u32 {
+        3
+    }
+
+    async fn save(&self, value: u32) -> u32 {
+        yield_once().await;
+        value + self.key()
+    }
+
+    #[time_it(future, label = "Memory::flush")]
+    fn flush(&self) -> impl Future<Output = ()> {
+        yield_once()
+    }
+
+    async fn ping(&self) -> u32 {
+        yield_once().await;
+        1
+    }
+}
+
+struct Disk;
+
+impl Store for Disk {
+    fn key(&self) -> u32 {
+        5
+    }
+
+    #[time_it(label = "Disk::save")]
+    async fn save(&self, value: u32) -> u32 {
+        yield_once().await;
+        value
+    }
+
+    #[time_it(future, on_cancel)]
+    fn flush(&self) -> impl Future<Output = ()> {
+        yield_once()
+    }
+
+    #[time_it(structured)]
+    async fn ping(&self) -> u32 {
+        yield_once().await;
+        2
+    }
+}
+
+/// Asserts that a returned future is `Send`, as the trait requires.
+fn assert_send<T: Send>(value: T) -> T {
+    value
+}
+
+#[test]
+fn default_async_methods_are_timed() {
+    let (value, events) = common::capture(|| block_on(Memory.load()));
+    assert_eq!(value, 6);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[load]: Execution time: "));
+    assert!(events[0].contains(", returned: 6"));
+}
+
+#[test]
+fn implemented_async_methods_are_timed() {
+    let (value, events) = common::capture(|| block_on(Memory.save(1)) + block_on(Disk.save(1)));
+    assert_eq!(value, 5);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[save]: Execution time: "));
+    assert!(events[1].starts_with("message=[Disk::save]: Execution time: "));
+}
+
+#[test]
+fn async_methods_satisfy_send_bounds() {
+    let (value, events) =
+        common::capture(|| block_on(assert_send(Memory.ping())) + block_on(Disk.ping()));
+    assert_eq!(value, 3);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[ping]: Execution time: "));
+    assert!(events[1].contains("function=\"ping\""));
+}
+
+#[test]
+fn returned_futures_are_timed() {
+    let (value, events) = common::capture(|| block_on(assert_send(Disk.fetch())));
+    assert_eq!(value, 5);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[fetch]: Execution time: "));
+
+    let ((), events) = common::capture(|| {
+        block_on(Memory.flush());
+        block_on(Disk.flush());
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[Memory::flush]: Execution time: "));
+    assert!(events[1].starts_with("message=[flush]: Execution time: "));
+}
+
+#[test]
+fn dropped_futures_are_reported_as_cancelled() {
+    let ((), events) = common::capture(|| {
+        let mut future = pin!(Disk.flush());
+        let poll = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()));
+        assert!(poll.is_pending());
+    });
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains("(cancelled)"));
+}
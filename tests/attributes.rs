@@ -0,0 +1,66 @@
+//! Attributes stacked with `#[time_it]`, above and below it.
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it]
+#[tracing::instrument]
+fn instrumented_below(id: u32) -> u32 {
+    id
+}
+
+#[tracing::instrument]
+#[time_it]
+fn instrumented_above(id: u32) -> u32 {
+    id
+}
+
+// Both definitions would generate the same `counted_*` items, which would clash if the `#[cfg]`
+// only applied to the function.
+#[time_it(aggregate)]
+#[cfg(test)]
+fn counted() {}
+
+#[time_it(aggregate)]
+#[cfg(not(test))]
+fn counted() {}
+
+#[time_it]
+#[inline(always)]
+#[deprecated]
+fn inlined() -> u32 {
+    1
+}
+
+#[time_it(inner_fn, log_result)]
+#[cold]
+#[inline(never)]
+fn cold() -> u32 {
+    2
+}
+
+#[test]
+fn inline_and_cold_functions_are_timed() {
+    #[allow(deprecated)]
+    let (sum, events) = common::capture(|| inlined() + cold());
+    assert_eq!(sum, 3);
+    assert_eq!(events.len(), 2);
+    assert!(events[1].contains(", returned: 2"));
+}
+
+#[test]
+fn attribute_macros_below_and_above_time_it_are_applied() {
+    let (id, events) = common::capture(|| instrumented_below(1) + instrumented_above(2));
+    assert_eq!(id, 3);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[instrumented_below]: Execution time: "));
+    assert!(events[1].starts_with("message=[instrumented_above]: Execution time: "));
+}
+
+#[cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+#[test]
+fn cfg_below_time_it_applies_to_the_generated_items() {
+    counted();
+    assert_eq!(counted_stats().0, 1);
+}
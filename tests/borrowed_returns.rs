@@ -0,0 +1,199 @@
+//! Functions returning references borrowed from their arguments or from `self` keep compiling
+//! once timed, as the body runs in place rather than in a closure the borrows would be tied to.
+
+mod common;
+
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+/// Polls a future that never suspends to completion.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future suspended"),
+    }
+}
+
+#[time_it]
+fn longest<'a>(a: &'a str, b: &'a str) -> &'a str {
+    if a.len() >= b.len() { a } else { b }
+}
+
+#[time_it]
+fn first_word(text: &str) -> &str {
+    text.split(' ').next().unwrap_or(text)
+}
+
+#[time_it(log_result)]
+fn choose<'a, T: Debug>(items: &'a [T], index: usize) -> Option<&'a T> {
+    items.get(index)
+}
+
+#[time_it(err_level = "warn", log_err_value)]
+fn non_empty(text: &str) -> Result<&str, &str> {
+    if text.is_empty() { Err(text) } else { Ok(text) }
+}
+
+#[time_it(inner_fn)]
+fn smallest<'a>(a: &'a str, b: &'a str) -> &'a str {
+    if a < b { a } else { b }
+}
+
+#[time_it(include_drops)]
+fn kept(text: &str, _dropped: String) -> &str {
+    text
+}
+
+#[time_it(span)]
+fn spanned(text: &str) -> &str {
+    text
+}
+
+#[time_it(log_result)]
+fn tail(items: &mut [u32]) -> Option<&mut u32> {
+    items.last_mut()
+}
+
+#[time_it]
+async fn trimmed(text: &str) -> &str {
+    text.trim()
+}
+
+#[time_it(future)]
+fn later<'a>(text: &'a str) -> impl Future<Output = &'a str> + 'a {
+    async move { text }
+}
+
+struct Store {
+    values: Vec<String>,
+}
+
+impl Store {
+    #[time_it]
+    fn get(&self, index: usize) -> &str {
+        &self.values[index]
+    }
+
+    #[time_it]
+    fn last_mut(&mut self) -> Option<&mut String> {
+        self.values.last_mut()
+    }
+
+    #[time_it]
+    async fn first(&self) -> Option<&String> {
+        self.values.first()
+    }
+}
+
+#[time_it]
+impl Store {
+    fn all(&self) -> &[String] {
+        &self.values
+    }
+}
+
+/// Like `Store`, with methods whose returned value is inspected.
+struct Cache {
+    values: Vec<String>,
+}
+
+impl Cache {
+    #[time_it(log_result)]
+    fn get(&self, index: usize) -> &str {
+        &self.values[index]
+    }
+
+    #[time_it(log_result)]
+    fn last_mut(&mut self) -> Option<&mut String> {
+        self.values.last_mut()
+    }
+
+    #[time_it(err_level = "warn")]
+    fn first_mut(&mut self) -> Result<&mut String, ()> {
+        self.values.first_mut().ok_or(())
+    }
+
+    #[time_it(log_result)]
+    async fn first_async(&mut self) -> Option<&mut String> {
+        self.values.first_mut()
+    }
+
+    #[time_it(future)]
+    fn later_mut(&mut self) -> impl Future<Output = usize> + '_ {
+        let values = &mut self.values;
+        async move { values.len() }
+    }
+}
+
+#[test]
+fn references_to_arguments_are_returned() {
+    let ((), events) = common::capture(|| {
+        let (a, b) = (String::from("long"), String::from("longer"));
+        assert_eq!(longest(&a, &b), "longer");
+        assert_eq!(first_word("hello world"), "hello");
+        assert_eq!(choose(&[1, 2], 1), Some(&2));
+        assert_eq!(non_empty(""), Err(""));
+        assert_eq!(smallest("b", "a"), "a");
+        assert_eq!(kept("kept", String::from("dropped")), "kept");
+        assert_eq!(spanned("spanned"), "spanned");
+    });
+    // `spanned` is reported through its span rather than an event.
+    assert_eq!(events.len(), 6);
+    assert!(events[0].starts_with("message=[longest]: Execution time: "));
+    assert!(events[2].contains(", returned: Some(2)"), "{}", events[2]);
+    assert!(events[3].contains(", error: \"\""), "{}", events[3]);
+}
+
+#[test]
+fn futures_return_references_to_arguments() {
+    let ((), events) = common::capture(|| {
+        assert_eq!(block_on(trimmed(" padded ")), "padded");
+        assert_eq!(block_on(later("later")), "later");
+    });
+    assert_eq!(events.len(), 2);
+}
+
+#[test]
+fn references_to_self_are_returned() {
+    let ((), events) = common::capture(|| {
+        let mut store = Store {
+            values: vec![String::from("a"), String::from("b")],
+        };
+        assert_eq!(store.get(1), "b");
+        store.last_mut().unwrap().push('c');
+        assert_eq!(block_on(store.first()).map(String::as_str), Some("a"));
+        assert_eq!(store.all(), ["a", "bc"]);
+    });
+    assert_eq!(events.len(), 4);
+    assert!(events[3].starts_with("message=[all]: Execution time: "));
+}
+
+#[test]
+fn inspected_values_can_borrow_mutably() {
+    let ((), events) = common::capture(|| {
+        let mut cache = Cache {
+            values: vec![String::from("a"), String::from("b")],
+        };
+        assert_eq!(cache.get(0), "a");
+        cache.last_mut().unwrap().push('c');
+        cache.first_mut().unwrap().push('d');
+        block_on(cache.first_async()).unwrap().push('e');
+        assert_eq!(block_on(cache.later_mut()), 2);
+        let mut items = [1, 2];
+        *tail(&mut items).unwrap() = 3;
+        assert_eq!(items, [1, 3]);
+        assert_eq!(cache.values, ["ade", "bc"]);
+    });
+    assert_eq!(events.len(), 6);
+    assert!(events[0].contains(", returned: \"a\""), "{}", events[0]);
+}
@@ -0,0 +1,92 @@
+//! `both_clocks` measures the wall-clock and the CPU time of every call, telling apart the time
+//! spent working from the time spent waiting.
+#![cfg(feature = "cpu_time")]
+
+mod common;
+
+use std::{cell::Cell, thread, time::Duration};
+
+use time_it::{time_block, time_it};
+
+// The expansion refers to `::cpu_time`, which this crate stands in for.
+extern crate self as cpu_time;
+
+thread_local! {
+    /// CPU time of the current thread, only advanced by `work`.
+    static CPU: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Stands in for `cpu_time::ThreadTime`, so the CPU time is exactly what `work` spends.
+pub struct ThreadTime(Duration);
+
+impl ThreadTime {
+    pub fn now() -> Self {
+        Self(CPU.with(Cell::get))
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        CPU.with(Cell::get) - self.0
+    }
+}
+
+/// Spends `millis` of CPU time.
+fn work(millis: u64) {
+    CPU.with(|cpu| cpu.set(cpu.get() + Duration::from_millis(millis)));
+}
+
+#[time_it(both_clocks)]
+fn blocked() {
+    work(1);
+    thread::sleep(Duration::from_millis(20));
+}
+
+#[time_it(both_clocks, structured)]
+fn structured() {
+    work(2);
+    thread::sleep(Duration::from_millis(20));
+}
+
+#[time_it(both_clocks, span, span_level = "info")]
+fn spanned() {
+    work(1);
+}
+
+#[test]
+fn waiting_makes_the_wall_time_exceed_the_cpu_time() {
+    let ((), events) = common::capture(blocked);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[blocked]: Execution time: "));
+    let (_, clocks) = events[0].split_once(", wall: ").unwrap();
+    let (wall, cpu) = clocks.trim_end().split_once(", cpu: ").unwrap();
+    assert_eq!(cpu, "1ms");
+    let wall = wall.strip_suffix("ms").unwrap().parse::<f64>().unwrap();
+    assert!(wall >= 20.0, "{wall}");
+}
+
+#[test]
+fn structured_events_have_wall_and_cpu_fields() {
+    let ((), events) = common::capture(structured);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains(" cpu_ms=2.0"), "{}", events[0]);
+    let (_, wall) = events[0].split_once(" wall_ms=").unwrap();
+    let wall = wall.split(' ').next().unwrap().parse::<f64>().unwrap();
+    assert!(wall >= 20.0, "{wall}");
+}
+
+#[test]
+fn blocks_measure_both_clocks() {
+    let ((), events) = common::capture(|| {
+        time_block!("block", both_clocks, {
+            work(3);
+        });
+    });
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains(", cpu: 3ms"), "{}", events[0]);
+}
+
+#[test]
+fn spans_keep_the_clocks_out_of_the_message() {
+    let ((), events) = common::capture(spanned);
+    assert_eq!(events.len(), 1);
+    assert!(!events[0].contains("cpu"), "{}", events[0]);
+}
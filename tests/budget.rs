@@ -0,0 +1,107 @@
+//! `budget` fails the tests in which a timed call takes longer than the given duration.
+
+mod common;
+
+use std::{cell::Cell, panic, thread, time::Duration};
+
+use time_it::{time_block, time_it};
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+/// Advances the fake clock by `millis`.
+fn advance(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+#[time_it(budget = "5ms")]
+fn slow() {
+    thread::sleep(Duration::from_millis(20));
+}
+
+#[time_it(budget = "10ms", clock_fn = "FakeInstant::now")]
+fn query(millis: u64) -> u64 {
+    advance(millis);
+    millis
+}
+
+#[time_it(budget = "10ms", clock_fn = "FakeInstant::now")]
+fn failing() {
+    advance(20);
+    panic!("failed on its own");
+}
+
+/// The message of the panic `f` raised.
+fn panic_message(f: impl FnOnce() + panic::UnwindSafe) -> String {
+    let payload = panic::catch_unwind(f).unwrap_err();
+    payload
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| {
+            payload
+                .downcast_ref::<&str>()
+                .map(|message| (*message).to_owned())
+        })
+        .unwrap()
+}
+
+#[test]
+#[should_panic(expected = "exceeded the budget of 5ms")]
+fn slow_calls_fail_the_test() {
+    slow();
+}
+
+#[test]
+fn calls_within_the_budget_pass() {
+    let (value, events) = common::capture(|| query(10));
+    assert_eq!(value, 10);
+    assert_eq!(events.len(), 1);
+}
+
+#[test]
+fn over_budget_calls_are_logged_before_failing() {
+    let (message, events) = common::capture(|| {
+        panic_message(|| {
+            query(11);
+        })
+    });
+    assert_eq!(
+        message,
+        "[query]: Execution time 11ms exceeded the budget of 10ms"
+    );
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[query]: Execution time: 11ms"));
+}
+
+#[test]
+fn panicking_calls_keep_their_own_panic() {
+    assert_eq!(panic_message(failing), "failed on its own");
+}
+
+#[test]
+fn blocks_have_budgets() {
+    let message = panic_message(|| {
+        time_block!("block", budget = "1ms", clock_fn = "FakeInstant::now", {
+            advance(2);
+        });
+    });
+    assert_eq!(
+        message,
+        "[block]: Execution time 2ms exceeded the budget of 1ms"
+    );
+}
@@ -0,0 +1,104 @@
+//! `TIME_IT_DEFAULT_LEVEL`, `TIME_IT_DEFAULT_UNIT` and `TIME_IT_DEFAULT_TARGET` set defaults when
+//! building, which the arguments of each function override.
+//!
+//! The variables are read when `time_it` is compiled, so these tests expect whichever defaults
+//! they were built with, e.g. with
+//! `TIME_IT_DEFAULT_LEVEL=info TIME_IT_DEFAULT_UNIT=us TIME_IT_DEFAULT_TARGET=app cargo test`.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use time_it::time_it;
+use tracing::{Event, Level, Metadata, Subscriber, span};
+
+/// Subscriber keeping the target of every event.
+#[derive(Clone, Default)]
+struct Targets(Arc<Mutex<Vec<&'static str>>>);
+
+impl Subscriber for Targets {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        self.0.lock().unwrap().push(event.metadata().target());
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+/// Runs `f` with a [`Targets`] subscriber, returning the targets of the emitted events.
+fn capture_targets(f: impl FnOnce()) -> Vec<&'static str> {
+    let subscriber = Targets::default();
+    tracing::subscriber::with_default(subscriber.clone(), f);
+    subscriber.0.lock().unwrap().clone()
+}
+
+#[time_it]
+fn defaults() {}
+
+#[time_it("warn", unit = "ns", target = "explicit")]
+fn overridden() {}
+
+#[time_it(format = "human")]
+fn formatted() {}
+
+#[test]
+fn defaults_come_from_the_build_environment() {
+    let level = option_env!("TIME_IT_DEFAULT_LEVEL")
+        .map_or(Level::DEBUG, |level| level.parse().expect("valid level"));
+    let ((), levels) = common::capture_levels(defaults);
+    assert_eq!(levels, [level]);
+
+    let ((), events) = common::capture(defaults);
+    let suffix = match option_env!("TIME_IT_DEFAULT_UNIT") {
+        Some("s") => "s",
+        Some("ms") => "ms",
+        Some("us") => "us",
+        Some("ns") => "ns",
+        Some(other) => panic!("unexpected unit {other}"),
+        None => "",
+    };
+    let duration = events[0]
+        .trim_end()
+        .strip_prefix("message=[defaults]: Execution time: ")
+        .unwrap();
+    if suffix.is_empty() {
+        // `Debug` formatting, with a unit suffix of its own.
+        assert!(duration.ends_with('s'), "{duration}");
+    } else {
+        let amount = duration.strip_suffix(suffix).unwrap();
+        assert!(amount.parse::<u128>().is_ok(), "{duration}");
+    }
+
+    let target = option_env!("TIME_IT_DEFAULT_TARGET").unwrap_or("time_it::measurement");
+    assert_eq!(capture_targets(defaults), [target]);
+}
+
+#[test]
+fn arguments_override_the_build_environment() {
+    let ((), levels) = common::capture_levels(overridden);
+    assert_eq!(levels, [Level::WARN]);
+    let ((), events) = common::capture(overridden);
+    let duration = events[0]
+        .trim_end()
+        .strip_prefix("message=[overridden]: Execution time: ")
+        .unwrap();
+    assert!(duration.strip_suffix("ns").unwrap().parse::<u128>().is_ok());
+    assert_eq!(capture_targets(overridden), ["explicit"]);
+
+    // A `format` also replaces the default unit, `human` spelling microseconds as `µs`.
+    let ((), events) = common::capture(formatted);
+    assert!(!events[0].trim_end().ends_with("us"), "{}", events[0]);
+}
@@ -0,0 +1,93 @@
+//! `on_cancel` reports futures dropped before completing as cancelled.
+
+mod common;
+
+use std::{
+    future::{Future, poll_fn},
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+/// Future suspending once before completing.
+async fn yield_once() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
+/// Polls `future` once, returning whether it completed, before dropping it.
+fn poll_once(future: impl Future) -> bool {
+    let future = pin!(future);
+    future
+        .poll(&mut Context::from_waker(Waker::noop()))
+        .is_ready()
+}
+
+/// Polls `future` until it completes.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            return output;
+        }
+    }
+}
+
+#[time_it(on_cancel)]
+async fn fetch() -> u32 {
+    yield_once().await;
+    1
+}
+
+#[time_it(on_cancel, structured)]
+async fn fetch_structured() {
+    yield_once().await;
+}
+
+#[time_it(on_cancel, future)]
+fn fetch_future() -> impl Future<Output = ()> {
+    yield_once()
+}
+
+#[test]
+fn dropped_futures_are_reported_as_cancelled() {
+    let (completed, events) = common::capture(|| [poll_once(fetch()), poll_once(fetch_future())]);
+    assert_eq!(completed, [false; 2]);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[fetch]: Execution time: "));
+    for event in events {
+        assert!(event.ends_with(" (cancelled) "), "{event}");
+    }
+}
+
+#[test]
+fn completed_futures_are_not_cancelled() {
+    let (output, events) = common::capture(|| block_on(fetch()));
+    assert_eq!(output, 1);
+    assert_eq!(events.len(), 1);
+    assert!(!events[0].contains("cancelled"), "{}", events[0]);
+}
+
+#[test]
+fn structured_events_carry_a_cancelled_field() {
+    let ((), events) = common::capture(|| {
+        poll_once(fetch_structured());
+        block_on(fetch_structured());
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains("cancelled=true"), "{}", events[0]);
+    assert!(!events[1].contains("cancelled"), "{}", events[1]);
+}
@@ -0,0 +1,56 @@
+//! `category` tags every event with the subsystem the timed code belongs to, for grouping.
+
+mod common;
+
+use time_it::{time_block, time_it};
+
+#[time_it(category = "network")]
+fn fetch() {}
+
+#[time_it(structured, category = "storage", fields(table = "users"))]
+fn query() {}
+
+#[time_it("level=info;category=cache")]
+fn packed() {}
+
+#[test]
+fn message_events_carry_the_category() {
+    let ((), events) = common::capture(fetch);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[fetch]: Execution time: "));
+    assert!(
+        events[0].ends_with(" category=\"network\" "),
+        "{}",
+        events[0]
+    );
+}
+
+#[test]
+fn the_category_comes_before_the_other_fields() {
+    let ((), events) = common::capture(query);
+    assert_eq!(events.len(), 1);
+    assert!(
+        events[0].contains(" category=\"storage\" table=\"users\" "),
+        "{}",
+        events[0]
+    );
+}
+
+#[test]
+fn the_category_can_be_packed() {
+    let ((), events) = common::capture(packed);
+    assert!(events[0].ends_with(" category=\"cache\" "), "{}", events[0]);
+}
+
+#[test]
+fn blocks_carry_the_category() {
+    let ((), events) = common::capture(|| {
+        time_block!("block", category = "parsing", {});
+    });
+    assert_eq!(events.len(), 1);
+    assert!(
+        events[0].ends_with(" category=\"parsing\" "),
+        "{}",
+        events[0]
+    );
+}
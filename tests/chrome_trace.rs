@@ -0,0 +1,71 @@
+//! `chrome_trace` adds the start time and duration of chrome trace events to the event.
+
+mod common;
+
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use time_it::{time_block, time_it};
+
+static MEASURED: Mutex<Vec<Duration>> = Mutex::new(Vec::new());
+
+fn measured(_name: &'static str, duration: Duration) {
+    MEASURED.lock().unwrap().push(duration);
+}
+
+#[time_it(chrome_trace, on_complete = "measured")]
+fn work() {
+    thread::sleep(Duration::from_millis(1));
+}
+
+#[time_it(chrome_trace, structured, on_complete = "measured")]
+fn structured_work() {
+    thread::sleep(Duration::from_millis(1));
+}
+
+fn now_micros() -> u64 {
+    let since = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    u64::try_from(since.as_micros()).unwrap()
+}
+
+/// Value of the integer field `name` of `event`.
+fn field(event: &str, name: &str) -> u64 {
+    let prefix = format!("{name}=");
+    event
+        .split(' ')
+        .find_map(|field| field.strip_prefix(&prefix))
+        .unwrap_or_else(|| panic!("{event} has no `{name}` field"))
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn events_carry_the_start_time_and_duration() {
+    let before = now_micros();
+    let ((), events) = common::capture(|| {
+        work();
+        structured_work();
+    });
+    let after = now_micros();
+    let measured = MEASURED.lock().unwrap().clone();
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains("[work]: Execution time: "));
+    assert!(events[1].contains("function=\"structured_work\" "));
+    for (event, duration) in events.iter().zip(measured) {
+        let (ts, dur) = (field(event, "ts"), field(event, "dur"));
+        assert!(before <= ts && ts <= after, "{event}");
+        assert_eq!(u128::from(dur), duration.as_micros(), "{event}");
+        assert!(dur >= 1000, "{event}");
+    }
+}
+
+#[test]
+fn blocks_carry_the_start_time_and_duration() {
+    let ((), events) = common::capture(|| time_block!("block", chrome_trace, {}));
+    assert_eq!(events.len(), 1);
+    assert!(field(&events[0], "ts") > 0);
+    field(&events[0], "dur");
+}
@@ -0,0 +1,58 @@
+//! `clock_fn` measures durations with a clock provided by the caller.
+
+mod common;
+
+use std::{cell::Cell, time::Duration};
+
+use time_it::{time_block, time_it};
+
+thread_local! {
+    /// Time of the fake clock, only advanced by `advance`.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+fn advance(by: Duration) {
+    NOW.with(|now| now.set(now.get() + by));
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+#[time_it(clock_fn = "FakeInstant::now")]
+fn fixed() {
+    advance(Duration::from_millis(42));
+}
+
+#[time_it(clock_fn = "FakeInstant::now", log_result, unit = "ms")]
+fn fixed_with_result() -> u32 {
+    advance(Duration::from_millis(7));
+    1
+}
+
+#[test]
+fn durations_come_from_the_clock() {
+    let ((), events) = common::capture(|| {
+        fixed();
+        fixed_with_result();
+        time_block!("block", clock_fn = "FakeInstant::now", {
+            advance(Duration::from_secs(1));
+        });
+    });
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0], "message=[fixed]: Execution time: 42ms ");
+    assert_eq!(
+        events[1],
+        "message=[fixed_with_result]: Execution time: 7ms, returned: 1 "
+    );
+    assert_eq!(events[2], "message=[block]: Execution time: 1s ");
+}
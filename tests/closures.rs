@@ -0,0 +1,85 @@
+//! `time_closure!` times every call of a closure, including from several threads.
+
+mod common;
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+    time::Duration,
+};
+
+use time_it::time_closure;
+
+/// Number of calls completed by `square`, across all threads.
+static SQUARED: AtomicU64 = AtomicU64::new(0);
+
+fn record(name: &'static str, _: Duration) {
+    assert_eq!(name, "square");
+    SQUARED.fetch_add(1, Ordering::Relaxed);
+}
+
+fn assert_send_sync<T: Send + Sync>(value: T) -> T {
+    value
+}
+
+#[test]
+fn every_call_is_timed() {
+    let ((), events) = common::capture(|| {
+        let double = time_closure!("double", |x: u32| x * 2);
+        let doubled: Vec<u32> = (0..3).map(double).collect();
+        assert_eq!(doubled, [0, 2, 4]);
+    });
+    assert_eq!(events.len(), 3);
+    for event in events {
+        assert!(event.starts_with("message=[double]: Execution time: "));
+    }
+}
+
+#[test]
+fn fn_mut_closures_keep_their_captures() {
+    let mut total = 0;
+    let ((), events) = common::capture(|| {
+        let mut add = time_closure!("add", "info", seq, |x: u32| total += x);
+        add(1);
+        add(2);
+    });
+    assert_eq!(total, 3);
+    assert_eq!(events.len(), 2);
+    assert!(events[1].ends_with(", seq: 1 "));
+}
+
+#[test]
+fn returns_and_move_closures_behave_as_written() {
+    let limit = 10;
+    let ((), events) = common::capture(|| {
+        let clamp = time_closure!("clamp", move |x: u32| -> u32 {
+            if x > limit {
+                return limit;
+            }
+            x
+        });
+        assert_eq!(clamp(5), 5);
+        assert_eq!(clamp(50), 10);
+    });
+    assert_eq!(events.len(), 2);
+}
+
+#[test]
+fn closures_are_shared_across_threads() {
+    // Parallel iterators such as Rayon's require `Send + Sync` closures, which stay so once timed.
+    let square = assert_send_sync(time_closure!("square", on_complete = "record", |x: u64| x * x));
+    let total: u64 = thread::scope(|scope| {
+        let workers: Vec<_> = (0..4)
+            .map(|worker: u64| {
+                let square = &square;
+                scope.spawn(move || (worker * 10..worker * 10 + 10).map(square).sum::<u64>())
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .sum()
+    });
+    assert_eq!(total, (0..40).map(|x| x * x).sum());
+    assert_eq!(SQUARED.load(Ordering::Relaxed), 40);
+}
@@ -0,0 +1,44 @@
+//! `collector` records every call into a user provided value.
+
+use std::{
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use time_it::{TimeItCollector, time_block, time_it};
+
+struct Timings(Mutex<Vec<&'static str>>);
+
+impl TimeItCollector for Timings {
+    fn record(&self, name: &'static str, _duration: Duration) {
+        self.0.lock().unwrap().push(name);
+    }
+}
+
+static TIMINGS: Timings = Timings(Mutex::new(Vec::new()));
+
+static LAZY: LazyLock<Timings> = LazyLock::new(|| Timings(Mutex::new(Vec::new())));
+
+#[time_it(collector = "TIMINGS")]
+fn collected() {}
+
+#[time_it(collector = "TIMINGS", aggregate)]
+fn collected_without_events() {}
+
+#[time_it(collector = "LAZY")]
+fn collected_lazily() {}
+
+#[test]
+fn every_call_is_recorded() {
+    collected();
+    collected_without_events();
+    time_block!("block", collector = "TIMINGS", {});
+    let names = TIMINGS.0.lock().unwrap();
+    assert_eq!(*names, ["collected", "collected_without_events", "block"]);
+}
+
+#[test]
+fn lazily_initialized_collectors_record_calls() {
+    collected_lazily();
+    assert_eq!(*LAZY.0.lock().unwrap(), ["collected_lazily"]);
+}
@@ -0,0 +1,77 @@
+//! Helpers shared by the integration tests.
+
+use std::{
+    fmt::{Debug, Write},
+    sync::{Arc, Mutex},
+};
+
+use tracing::{
+    Event, Level, Metadata, Subscriber,
+    field::{Field, Visit},
+    span,
+};
+
+/// Subscriber keeping the level and fields of every event, formatted as `name=value` pairs.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<(Level, String)>>>);
+
+impl Capture {
+    fn events(&self) -> Vec<String> {
+        let events = self.0.lock().unwrap();
+        events.iter().map(|(_, fields)| fields.clone()).collect()
+    }
+
+    fn levels(&self) -> Vec<Level> {
+        let events = self.0.lock().unwrap();
+        events.iter().map(|&(level, _)| level).collect()
+    }
+}
+
+impl Subscriber for Capture {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields(String::new());
+        event.record(&mut fields);
+        let level = *event.metadata().level();
+        self.0.lock().unwrap().push((level, fields.0));
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+struct Fields(String);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        write!(self.0, "{}={:?} ", field.name(), value).unwrap();
+    }
+}
+
+/// Runs `f` with a [`Capture`] as the default subscriber, returning the events it emitted.
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    let capture = Capture::default();
+    let result = tracing::subscriber::with_default(capture.clone(), f);
+    (result, capture.events())
+}
+
+/// Runs `f` with a [`Capture`] as the default subscriber, returning the levels of the events it
+/// emitted.
+#[allow(dead_code)]
+pub fn capture_levels<R>(f: impl FnOnce() -> R) -> (R, Vec<Level>) {
+    let capture = Capture::default();
+    let result = tracing::subscriber::with_default(capture.clone(), f);
+    (result, capture.levels())
+}
@@ -0,0 +1,108 @@
+//! Function bodies are spliced into the function as written, so any statement and control flow
+//! works unchanged.
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it]
+fn parse_or_zero(input: &str) -> u32 {
+    let Ok(value) = input.parse() else {
+        return 0;
+    };
+    value
+}
+
+#[time_it]
+fn first_even(values: &[u32]) -> Option<u32> {
+    let found = 'search: {
+        for &value in values {
+            if value % 2 == 0 {
+                break 'search Some(value);
+            }
+        }
+        None
+    };
+    found.filter(|&value| value > 0)
+}
+
+#[time_it]
+fn statement_macros() -> u32 {
+    macro_rules! double {
+        ($value:expr) => {
+            $value * 2
+        };
+    }
+    let value = double!(LATER);
+    // Items declared after their use are visible in the whole body.
+    const LATER: u32 = 21;
+    value
+}
+
+#[time_it(log_result)]
+fn parse_logged(input: &str) -> u32 {
+    let Ok(value) = input.parse() else {
+        return 0;
+    };
+    value
+}
+
+#[time_it(log_result)]
+fn clamped(value: u32) -> u32 {
+    if value > 10 {
+        return 10;
+    }
+    value
+}
+
+#[time_it(log_result, err_level = "warn")]
+fn sum(left: &str, right: &str) -> Result<u32, std::num::ParseIntError> {
+    let left: u32 = left.parse()?;
+    let right: u32 = right.parse()?;
+    Ok(left + right)
+}
+
+#[test]
+fn let_else_returns_from_the_function() {
+    let (values, events) = common::capture(|| (parse_or_zero("7"), parse_or_zero("x")));
+    assert_eq!(values, (7, 0));
+    assert_eq!(events.len(), 2);
+}
+
+#[test]
+fn labeled_blocks_break_with_a_value() {
+    assert_eq!(first_even(&[1, 3, 4, 6]), Some(4));
+    assert_eq!(first_even(&[1]), None);
+}
+
+#[test]
+fn macros_and_items_are_kept() {
+    assert_eq!(statement_macros(), 42);
+}
+
+#[test]
+fn returns_are_inspected_in_log_result_mode() {
+    let (value, events) = common::capture(|| parse_logged("x"));
+    assert_eq!(value, 0);
+    assert!(events[0].contains(", returned: 0"));
+}
+
+#[test]
+fn early_returns_are_reported_with_their_value() {
+    let (values, events) = common::capture(|| (clamped(42), clamped(3)));
+    assert_eq!(values, (10, 3));
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[clamped]: Execution time: "));
+    assert!(events[0].contains(", returned: 10"));
+    assert!(events[1].contains(", returned: 3"));
+}
+
+#[test]
+fn question_marks_are_reported_with_the_error() {
+    let ((ok, err), events) = common::capture(|| (sum("1", "2"), sum("1", "x")));
+    assert_eq!(ok, Ok(3));
+    assert!(err.is_err());
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains(", returned: Ok(3)"));
+    assert!(events[1].contains(", returned: Err(ParseIntError { kind: InvalidDigit })"));
+}
@@ -0,0 +1,66 @@
+//! `corr_id` tags the events of each call with the same random id.
+
+mod common;
+
+use time_it::{time_block, time_it};
+
+#[time_it(corr_id, trace_entry)]
+fn correlated() {}
+
+#[time_it(corr_id, trace_entry, structured)]
+fn correlated_structured() {}
+
+#[time_it(corr_id, span)]
+fn correlated_span() {}
+
+/// The 16 hexadecimal digits following `corr_id: ` or `corr_id=` in `event`.
+fn corr_id(event: &str) -> &str {
+    let start = ["corr_id: ", "corr_id="]
+        .iter()
+        .find_map(|prefix| event.find(prefix).map(|index| index + prefix.len()))
+        .expect("event without a correlation id");
+    let id = &event[start..start + 16];
+    assert!(id.bytes().all(|b| b.is_ascii_hexdigit()), "invalid id {id}");
+    id
+}
+
+#[test]
+fn entry_and_completion_share_the_id() {
+    let ((), events) = common::capture(|| {
+        correlated();
+        correlated();
+    });
+    assert_eq!(events.len(), 4);
+    assert!(events[0].starts_with("message=[correlated]: Started, corr_id: "));
+    assert!(events[1].starts_with("message=[correlated]: Execution time: "));
+    assert_eq!(corr_id(&events[0]), corr_id(&events[1]));
+    assert_eq!(corr_id(&events[2]), corr_id(&events[3]));
+    assert_ne!(corr_id(&events[0]), corr_id(&events[2]));
+}
+
+#[test]
+fn structured_events_carry_the_id_as_a_field() {
+    let ((), events) = common::capture(correlated_structured);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains(" corr_id="));
+    assert_eq!(corr_id(&events[0]), corr_id(&events[1]));
+}
+
+#[test]
+fn spans_carry_the_id_as_a_field() {
+    // The id is a field of the span, which emits no event of its own.
+    let ((), events) = common::capture(correlated_span);
+    assert!(events.is_empty());
+}
+
+#[test]
+fn blocks_are_correlated_per_run() {
+    let ((), events) = common::capture(|| {
+        for _ in 0..2 {
+            time_block!("block", corr_id, trace_entry, {});
+        }
+    });
+    assert_eq!(events.len(), 4);
+    assert_eq!(corr_id(&events[0]), corr_id(&events[1]));
+    assert_ne!(corr_id(&events[1]), corr_id(&events[3]));
+}
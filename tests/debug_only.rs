@@ -0,0 +1,36 @@
+//! `debug_only` times calls in builds with debug assertions, and leaves the functions untimed in
+//! the others, e.g. with `cargo test --release`.
+#![cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it("info", debug_only)]
+fn doubled(value: u32) -> u32 {
+    value * 2
+}
+
+#[time_it(debug_only, aggregate)]
+fn counted() {}
+
+#[cfg(debug_assertions)]
+#[test]
+fn calls_are_timed_with_debug_assertions() {
+    let (value, events) = common::capture(|| doubled(2));
+    assert_eq!(value, 4);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[doubled]: Execution time: "));
+    counted();
+    assert_eq!(counted_stats().0, 1);
+}
+
+#[cfg(not(debug_assertions))]
+#[test]
+fn calls_are_untimed_without_debug_assertions() {
+    let (value, events) = common::capture(|| doubled(2));
+    assert_eq!(value, 4);
+    assert!(events.is_empty(), "{events:?}");
+    counted();
+    assert_eq!(counted_stats().0, 0);
+}
@@ -0,0 +1,87 @@
+//! The `defmt` backend logs durations as integers, through format strings `defmt` accepts.
+#![cfg(all(feature = "defmt", not(feature = "tracing"), not(feature = "log")))]
+
+use time_it::{time_block, time_it};
+
+// The expansion refers to `::defmt`, which this crate stands in for.
+extern crate self as defmt;
+
+use std::cell::RefCell;
+
+thread_local! {
+    /// The level and format string of every call, next to the formatted message. This crate
+    /// stands in for the `defmt` crate, whose macros only link with a global logger for the
+    /// target.
+    pub static LOGGED: RefCell<Vec<(&'static str, &'static str, String)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+macro_rules! log {
+    ($level:literal, $format:literal $(, $arg:expr)* $(,)?) => {
+        crate::LOGGED.with_borrow_mut(|logged| {
+            logged.push(($level, $format, format!($format $(, $arg)*)))
+        })
+    };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { crate::log!("debug", $($arg)*) };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { crate::log!("info", $($arg)*) };
+}
+
+macro_rules! error {
+    ($($arg:tt)*) => { crate::log!("error", $($arg)*) };
+}
+
+pub(crate) use {debug, error, info, log};
+
+pub fn take() -> Vec<(&'static str, &'static str, String)> {
+    LOGGED.take()
+}
+
+#[time_it]
+fn work() {}
+
+#[time_it("info", unit = "ms", seq, location)]
+fn detailed() {}
+
+#[time_it(err_level = "error", trace_entry)]
+fn fallible(fail: bool) -> Result<(), ()> {
+    if fail { Err(()) } else { Ok(()) }
+}
+
+#[test]
+fn durations_are_logged_in_microseconds() {
+    work();
+    time_block!("block", {});
+    let logged = defmt::take();
+    assert_eq!(logged.len(), 2);
+    assert_eq!(logged[0].0, "debug");
+    assert_eq!(logged[0].1, "[{}]: Execution time: {}us{}");
+    assert!(logged[0].2.starts_with("[work]: Execution time: "));
+    assert!(logged[0].2.ends_with("us"));
+    assert!(logged[1].2.starts_with("[block]: Execution time: "));
+}
+
+#[test]
+fn levels_units_and_additions_are_kept() {
+    detailed();
+    let _ = fallible(true);
+    let logged = defmt::take();
+    assert_eq!(logged.len(), 3);
+    assert_eq!(logged[0].0, "info");
+    assert_eq!(
+        logged[0].1,
+        "[{}]: Execution time: {}ms{}, seq: {} at {}:{}"
+    );
+    assert!(logged[0].2.contains("ms, seq: 0 at tests/defmt.rs:"));
+    assert_eq!(
+        logged[1],
+        ("debug", "[{}]: Started", "[fallible]: Started".to_owned())
+    );
+    assert_eq!(logged[2].0, "error");
+    assert!(logged[2].2.ends_with("us (failed)"));
+}
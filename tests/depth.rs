@@ -0,0 +1,70 @@
+//! `depth` counts how deep recursive calls go, and `top_only` only reports the outermost call.
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it(depth)]
+fn factorial(n: u64) -> u64 {
+    if n <= 1 { 1 } else { n * factorial(n - 1) }
+}
+
+#[time_it(depth, top_only, structured)]
+fn fibonacci(n: u64) -> u64 {
+    if n < 2 {
+        n
+    } else {
+        fibonacci(n - 1) + fibonacci(n - 2)
+    }
+}
+
+#[time_it(depth)]
+fn descend(n: u64, fail: bool) {
+    if n > 0 {
+        descend(n - 1, fail);
+    } else if fail {
+        panic!("bottom reached");
+    }
+}
+
+#[test]
+fn every_call_reports_its_depth() {
+    let (result, events) = common::capture(|| factorial(3));
+    assert_eq!(result, 6);
+    // The innermost call completes first.
+    let depths: Vec<_> = events
+        .iter()
+        .map(|event| {
+            event
+                .split(", depth: ")
+                .nth(1)
+                .unwrap()
+                .split(' ')
+                .next()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(depths, ["2", "1", "0"]);
+}
+
+#[test]
+fn top_only_reports_the_outermost_call() {
+    let (result, events) = common::capture(|| {
+        let first = fibonacci(5);
+        first + fibonacci(3)
+    });
+    assert_eq!(result, 7);
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event.contains("depth=0 ")));
+}
+
+#[test]
+fn unwinding_restores_the_depth() {
+    let ((), events) = common::capture(|| {
+        assert!(std::panic::catch_unwind(|| descend(2, true)).is_err());
+        descend(0, false);
+    });
+    assert_eq!(events.len(), 4);
+    assert!(events[2].contains(" (panicked), depth: 0 "));
+    assert!(events[3].contains(", depth: 0 "));
+}
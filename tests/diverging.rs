@@ -0,0 +1,50 @@
+//! Functions that never return keep compiling without warnings once annotated.
+
+#![deny(unreachable_code)]
+
+mod common;
+
+use std::panic::catch_unwind;
+
+use time_it::time_it;
+
+#[time_it]
+fn diverging() -> ! {
+    panic!("diverged")
+}
+
+#[time_it(log_result)]
+fn diverging_with_result() -> ! {
+    panic!("diverged")
+}
+
+#[time_it(log_result, err_level = "error")]
+fn never_returning() -> Result<u32, String> {
+    panic!("diverged")
+}
+
+#[time_it(log_result)]
+fn looping(stop: bool) -> u32 {
+    loop {
+        if stop {
+            panic!("stopped");
+        }
+    }
+}
+
+#[test]
+fn diverging_functions_are_reported_as_panicked() {
+    let (results, events) = common::capture(|| {
+        [
+            catch_unwind(|| diverging()).is_err(),
+            catch_unwind(|| diverging_with_result()).is_err(),
+            catch_unwind(never_returning).is_err(),
+            catch_unwind(|| looping(true)).is_err(),
+        ]
+    });
+    assert_eq!(results, [true; 4]);
+    assert_eq!(events.len(), 4);
+    for event in events {
+        assert!(event.contains(" (panicked)"), "{event}");
+    }
+}
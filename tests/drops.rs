@@ -0,0 +1,104 @@
+//! Destructors of the locals of the timed code are part of the measurement, and those of the
+//! arguments are with `include_drops`.
+
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+    thread,
+    time::Duration,
+};
+
+use time_it::{time_block, time_it};
+
+const DROP_TIME: Duration = Duration::from_millis(20);
+
+thread_local! {
+    static MEASURED: RefCell<Vec<Duration>> = const { RefCell::new(Vec::new()) };
+}
+
+fn measured(_name: &'static str, duration: Duration) {
+    MEASURED.with_borrow_mut(|measured| measured.push(duration));
+}
+
+/// Durations measured on the current thread so far.
+fn take_measured() -> Vec<Duration> {
+    MEASURED.take()
+}
+
+/// Sleeps for [`DROP_TIME`] when dropped.
+struct SlowDrop;
+
+impl Drop for SlowDrop {
+    fn drop(&mut self) {
+        thread::sleep(DROP_TIME);
+    }
+}
+
+#[time_it(on_complete = "measured")]
+fn local() {
+    let _slow = SlowDrop;
+}
+
+#[time_it(on_complete = "measured", log_result)]
+fn local_with_result() -> u32 {
+    let _slow = SlowDrop;
+    1
+}
+
+#[time_it(on_complete = "measured")]
+fn argument(_slow: SlowDrop) {}
+
+#[time_it(on_complete = "measured", include_drops)]
+fn included_argument(_slow: SlowDrop) {}
+
+#[time_it(on_complete = "measured", include_drops, log_result)]
+fn included_mutable_argument(mut slow: Vec<SlowDrop>, count: &mut usize) -> usize {
+    slow.push(SlowDrop);
+    *count = slow.len();
+    *count
+}
+
+#[time_it(on_complete = "measured", include_drops)]
+async fn included_async_argument(_slow: SlowDrop) {}
+
+#[test]
+fn locals_are_dropped_before_the_measurement_ends() {
+    local();
+    local_with_result();
+    let () = time_block!("block", on_complete = "measured", {
+        let _slow = SlowDrop;
+    });
+    let measured = take_measured();
+    assert_eq!(measured.len(), 3);
+    assert!(
+        measured.iter().all(|&duration| duration >= DROP_TIME),
+        "{measured:?}"
+    );
+}
+
+#[test]
+fn arguments_are_dropped_after_the_measurement_ends() {
+    argument(SlowDrop);
+    let measured = take_measured();
+    assert_eq!(measured.len(), 1);
+    assert!(measured[0] < DROP_TIME, "{measured:?}");
+}
+
+#[test]
+fn included_arguments_are_dropped_before_the_measurement_ends() {
+    included_argument(SlowDrop);
+    let mut count = 0;
+    assert_eq!(included_mutable_argument(Vec::new(), &mut count), 1);
+    assert_eq!(count, 1);
+    let future = pin!(included_async_argument(SlowDrop));
+    let ready = future.poll(&mut Context::from_waker(Waker::noop()));
+    assert!(matches!(ready, Poll::Ready(())));
+    let measured = take_measured();
+    assert_eq!(measured.len(), 3);
+    assert!(
+        measured.iter().all(|&duration| duration >= DROP_TIME),
+        "{measured:?}"
+    );
+}
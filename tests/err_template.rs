@@ -0,0 +1,42 @@
+//! `err_template` words the message of calls returning an `Err` differently.
+
+mod common;
+
+use time_it::time_it;
+use tracing::Level;
+
+#[time_it(err_template = "{name} FAILED after {duration}")]
+fn fallible(fail: bool) -> Result<(), ()> {
+    if fail { Err(()) } else { Ok(()) }
+}
+
+#[time_it(
+    "info",
+    err_level = "error",
+    template = "{name} took {duration}",
+    err_template = "{name} failed in {duration}"
+)]
+fn leveled(fail: bool) -> Result<(), ()> {
+    if fail { Err(()) } else { Ok(()) }
+}
+
+#[test]
+fn failures_use_their_own_template() {
+    let (_, events) = common::capture(|| (fallible(false), fallible(true)));
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[fallible]: Execution time: "));
+    assert!(!events[0].contains("FAILED"));
+    assert!(events[1].starts_with("message=fallible FAILED after "));
+    // The template marks the failure, so `(failed)` isn't appended.
+    assert!(!events[1].contains("(failed)"));
+}
+
+#[test]
+fn templates_combine_with_levels() {
+    let (_, events) = common::capture(|| (leveled(false), leveled(true)));
+    assert!(events[0].starts_with("message=leveled took "));
+    assert!(events[1].starts_with("message=leveled failed in "));
+
+    let (_, levels) = common::capture_levels(|| (leveled(false), leveled(true), fallible(true)));
+    assert_eq!(levels, [Level::INFO, Level::ERROR, Level::DEBUG]);
+}
@@ -0,0 +1,107 @@
+//! `exclude` leaves the time of the expressions wrapped in `exclude!` under the same name out of
+//! the measurement, such as the time spent waiting for a lock.
+
+#![cfg(not(feature = "no_std"))]
+
+mod common;
+
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use time_it::{exclude, time_it};
+
+time_it::exclusions!();
+
+/// Duration in milliseconds reported by a structured event.
+fn duration_ms(event: &str) -> f64 {
+    let (_, duration) = event.split_once(" duration_ms=").unwrap();
+    duration.split(' ').next().unwrap().parse().unwrap()
+}
+
+#[time_it(structured, exclude = "lock")]
+fn sleeps_excluded() {
+    exclude!("lock", thread::sleep(Duration::from_millis(50)));
+    thread::sleep(Duration::from_millis(20));
+}
+
+#[time_it(structured, exclude = "io")]
+fn excludes_another_name() {
+    exclude!("lock", thread::sleep(Duration::from_millis(30)));
+}
+
+/// Waits for the lock without being timed itself.
+fn acquire(counter: &Mutex<u64>) -> std::sync::MutexGuard<'_, u64> {
+    exclude!("lock", counter.lock().unwrap())
+}
+
+#[time_it(structured, exclude = "lock")]
+fn increment(counter: &Mutex<u64>) -> Duration {
+    *acquire(counter) += 1;
+    crate::time_it_exclusions::excluded("lock")
+}
+
+#[time_it(structured, exclude = "lock")]
+fn inner() {
+    exclude!("lock", thread::sleep(Duration::from_millis(30)));
+}
+
+#[time_it(structured, exclude = "lock")]
+fn outer() {
+    inner();
+}
+
+#[test]
+fn excluded_time_is_subtracted() {
+    let ((), events) = common::capture(sleeps_excluded);
+    assert_eq!(events.len(), 1);
+    let duration = duration_ms(&events[0]);
+    assert!((20.0..50.0).contains(&duration), "{duration}");
+}
+
+#[test]
+fn other_names_are_not_subtracted() {
+    let ((), events) = common::capture(excludes_another_name);
+    assert_eq!(events.len(), 1);
+    let duration = duration_ms(&events[0]);
+    assert!(duration >= 30.0, "{duration}");
+}
+
+#[test]
+fn waiting_for_a_lock_in_a_callee_is_excluded() {
+    let counter = Arc::new(Mutex::new(0));
+    let held = counter.lock().unwrap();
+    let contender = {
+        let counter = Arc::clone(&counter);
+        thread::spawn(move || common::capture(|| increment(&counter)))
+    };
+    thread::sleep(Duration::from_millis(50));
+    drop(held);
+    let (excluded, events) = contender.join().unwrap();
+    assert_eq!(*counter.lock().unwrap(), 1);
+    assert!(excluded >= Duration::from_millis(40), "{excluded:?}");
+    assert_eq!(events.len(), 1);
+    let duration = duration_ms(&events[0]);
+    assert!(duration < 40.0, "{duration}");
+}
+
+#[test]
+fn nested_calls_exclude_their_time_from_the_outer_ones() {
+    let ((), events) = common::capture(outer);
+    assert_eq!(events.len(), 2);
+    for event in &events {
+        let duration = duration_ms(event);
+        assert!(duration < 30.0, "{event}");
+    }
+}
+
+#[test]
+fn exclusions_evaluate_to_the_expression() {
+    let value = exclude!("unused", {
+        let value = 20;
+        value + 1
+    });
+    assert_eq!(value, 21);
+}
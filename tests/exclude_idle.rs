@@ -0,0 +1,121 @@
+//! `exclude_idle` measures the time futures spend being polled, leaving out the time they wait to
+//! be polled again.
+
+mod common;
+
+use std::{
+    cell::Cell,
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use time_it::time_it;
+
+thread_local! {
+    /// Time of the fake clock, advanced by the futures while working and by `block_on` while idle.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+/// Advances the fake clock by `millis`.
+fn advance(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+/// Polls `future` to completion, idling for 100ms after every poll returning `Pending`, as a
+/// scheduler busy with other tasks would.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    loop {
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => return output,
+            Poll::Pending => advance(100),
+        }
+    }
+}
+
+/// A future that is pending on its first poll, so the awaiting future suspends.
+async fn yield_once() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
+#[time_it(clock_fn = "FakeInstant::now")]
+async fn wall() {
+    advance(1);
+    yield_once().await;
+    advance(2);
+}
+
+#[time_it(exclude_idle, clock_fn = "FakeInstant::now")]
+async fn busy() {
+    advance(1);
+    yield_once().await;
+    advance(2);
+}
+
+#[time_it(
+    future,
+    exclude_idle,
+    poll_count,
+    log_result,
+    clock_fn = "FakeInstant::now"
+)]
+fn returned() -> impl Future<Output = u32> {
+    async {
+        advance(3);
+        yield_once().await;
+        yield_once().await;
+        4
+    }
+}
+
+#[test]
+fn idle_time_is_left_out() {
+    let ((), events) = common::capture(|| {
+        block_on(wall());
+        block_on(busy());
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[wall]: Execution time: 103ms"));
+    assert!(events[1].starts_with("message=[busy]: Execution time: 3ms"));
+}
+
+#[test]
+fn returned_futures_are_measured_while_polled() {
+    let (value, events) = common::capture(|| block_on(returned()));
+    assert_eq!(value, 4);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[returned]: Execution time: 3ms"));
+    assert!(
+        events[0].contains(", returned: 4, polls: 3"),
+        "{}",
+        events[0]
+    );
+}
@@ -0,0 +1,94 @@
+//! Async functions are timed without relying on a runtime, so any executor can poll them. This
+//! crate has no `tokio` to resolve, unlike the `runtime` tests.
+
+mod common;
+
+use std::{
+    cell::Cell,
+    future::Future,
+    pin::{Pin, pin},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use time_it::time_it;
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+/// Advances the fake clock by `millis`.
+fn advance(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+/// Future returning `Pending` once before completing.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// The smallest executor: polls `future` on the current thread until it completes.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[time_it("info")]
+async fn ready(value: u32) -> u32 {
+    value + 1
+}
+
+#[time_it(clock_fn = "FakeInstant::now")]
+async fn suspending(millis: u64) -> u64 {
+    advance(millis);
+    YieldOnce(false).await;
+    advance(millis);
+    millis
+}
+
+#[test]
+fn async_functions_are_timed_on_any_executor() {
+    let (value, events) = common::capture(|| block_on(ready(1)));
+    assert_eq!(value, 2);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[ready]: Execution time: "));
+}
+
+#[test]
+fn measurements_span_the_await_points() {
+    let (value, events) = common::capture(|| block_on(suspending(3)));
+    assert_eq!(value, 3);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[suspending]: Execution time: 6ms"));
+}
@@ -0,0 +1,38 @@
+//! FFI exported functions keep their ABI and symbol when timed.
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it(log_result)]
+extern "C" fn exported(value: u32) -> u32 {
+    value * 2
+}
+
+#[time_it]
+#[unsafe(no_mangle)]
+pub extern "C" fn time_it_test_no_mangle(value: u32) -> u32 {
+    value + 1
+}
+
+unsafe extern "C" {
+    #[link_name = "time_it_test_no_mangle"]
+    fn no_mangle_by_symbol(value: u32) -> u32;
+}
+
+#[test]
+fn extern_functions_are_timed() {
+    let pointer: extern "C" fn(u32) -> u32 = exported;
+    let (value, events) = common::capture(|| pointer(2));
+    assert_eq!(value, 4);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[exported]: Execution time: "));
+}
+
+#[test]
+fn no_mangle_functions_keep_their_symbol() {
+    let (value, events) = common::capture(|| unsafe { no_mangle_by_symbol(1) });
+    assert_eq!(value, 2);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[time_it_test_no_mangle]: Execution time: "));
+}
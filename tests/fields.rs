@@ -0,0 +1,30 @@
+//! `fields` adds constant fields to every event.
+
+mod common;
+
+use time_it::{time_block, time_it};
+
+#[time_it(fields(component = "db", critical = true, shard = 3, ratio = 0.5))]
+fn tagged() {}
+
+#[time_it(structured, fields(component = "db"))]
+fn tagged_structured() {}
+
+#[test]
+fn message_events_carry_the_fields() {
+    let ((), events) = common::capture(tagged);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[tagged]: Execution time: "));
+    assert!(events[0].ends_with(" component=\"db\" critical=true shard=3 ratio=0.5 "));
+}
+
+#[test]
+fn structured_events_and_blocks_carry_the_fields() {
+    let ((), events) = common::capture(|| {
+        tagged_structured();
+        time_block!("block", fields(component = "cache"), {});
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains(" component=\"db\" "));
+    assert!(events[1].ends_with(" component=\"cache\" "));
+}
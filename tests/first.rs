@@ -0,0 +1,23 @@
+//! `first` stops reporting once the given number of calls completed.
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it(first = 3)]
+fn startup() {}
+
+#[test]
+fn only_the_first_calls_are_reported() {
+    let ((), events) = common::capture(|| {
+        for _ in 0..10 {
+            startup();
+        }
+    });
+    assert_eq!(events.len(), 3);
+    assert!(
+        events
+            .iter()
+            .all(|event| event.starts_with("message=[startup]: Execution time: "))
+    );
+}
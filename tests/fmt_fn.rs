@@ -0,0 +1,44 @@
+//! `fmt_fn` renders durations with a function of the caller's.
+
+mod common;
+
+use std::time::Duration;
+
+use time_it::{time_block, time_it};
+
+fn sentinel(_duration: Duration) -> String {
+    "<sentinel>".to_owned()
+}
+
+mod locale {
+    use std::time::Duration;
+
+    /// Milliseconds with a decimal comma.
+    pub fn french(duration: Duration) -> String {
+        format!("{:.1} ms", duration.as_secs_f64() * 1000.0).replace('.', ",")
+    }
+}
+
+#[time_it(fmt_fn = "sentinel")]
+fn work() {}
+
+#[time_it(fmt_fn = "locale::french", template = "{name} a pris {duration}")]
+fn travail() {}
+
+#[test]
+fn durations_are_rendered_by_the_function() {
+    let ((), events) = common::capture(|| {
+        work();
+        time_block!("block", fmt_fn = "sentinel", {});
+    });
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0], "message=[work]: Execution time: <sentinel> ");
+    assert_eq!(events[1], "message=[block]: Execution time: <sentinel> ");
+}
+
+#[test]
+fn templates_use_the_rendered_duration() {
+    let ((), events) = common::capture(travail);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0], "message=travail a pris 0,0 ms ");
+}
@@ -0,0 +1,78 @@
+//! Functions returning a future without being `async` are timed until the future completes.
+
+mod common;
+
+use std::{
+    future::Future,
+    pin::{Pin, pin},
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+/// Future returning `Pending` once before completing.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// Polls `future` to completion, returning its output and how often it was polled.
+fn block_on<F: Future>(future: F) -> (F::Output, u32) {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut polls = 1;
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return (output, polls),
+            Poll::Pending => polls += 1,
+        }
+    }
+}
+
+#[time_it(future, log_result)]
+fn opaque(value: u32) -> impl Future<Output = u32> {
+    async move {
+        YieldOnce(false).await;
+        value * 2
+    }
+}
+
+#[time_it(future)]
+fn pinned<'a>(name: &'a str) -> Pin<Box<dyn Future<Output = usize> + Send + 'a>> {
+    Box::pin(async move {
+        YieldOnce(false).await;
+        name.len()
+    })
+}
+
+#[test]
+fn opaque_futures_are_timed_on_completion() {
+    let (future, events) = common::capture(|| opaque(21));
+    assert!(events.is_empty(), "creating the future emitted {events:?}");
+    let ((output, polls), events) = common::capture(|| block_on(future));
+    assert_eq!((output, polls), (42, 2));
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains("[opaque]: Execution time: "));
+    assert!(events[0].contains(", returned: 42"));
+}
+
+#[test]
+fn pinned_futures_are_timed_on_completion() {
+    let (future, events) = common::capture(|| pinned("four"));
+    assert!(events.is_empty(), "creating the future emitted {events:?}");
+    let ((output, _), events) = common::capture(|| block_on(future));
+    assert_eq!(output, 4);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains("[pinned]: Execution time: "));
+}
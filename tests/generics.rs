@@ -0,0 +1,164 @@
+//! Generics, lifetimes and where-clauses pass through the expansion unchanged, including in async
+//! methods borrowing `self` and their arguments across `.await`s.
+
+use std::fmt::{Debug, Display};
+
+use time_it::time_it;
+
+#[time_it(log_args, log_result)]
+fn describe<T: Display + Debug, U>(value: T, suffix: U) -> String
+where
+    U: AsRef<str> + Debug,
+{
+    format!("{value}{}", suffix.as_ref())
+}
+
+#[time_it(log_args, log_result)]
+fn longest<'a, 'b: 'a>(left: &'a str, right: &'b str) -> &'a str {
+    if left.len() >= right.len() { left } else { right }
+}
+
+#[time_it(log_result)]
+fn first<const N: usize, T: Copy + Debug + Default>(values: [T; N]) -> T {
+    values.first().copied().unwrap_or_default()
+}
+
+#[time_it(err_level = "warn")]
+fn parse<T>(input: &str) -> Result<T, T::Err>
+where
+    T: std::str::FromStr,
+{
+    let value = input.trim().parse()?;
+    Ok(value)
+}
+
+struct Wrapper<T>(T);
+
+impl<T: Clone> Wrapper<T> {
+    #[time_it]
+    fn get(&self) -> T {
+        self.0.clone()
+    }
+
+    #[time_it(log_args)]
+    fn replace<U: Into<T> + Debug>(&mut self, value: U) -> T {
+        std::mem::replace(&mut self.0, value.into())
+    }
+}
+
+#[time_it]
+async fn describe_async<T>(value: T) -> String
+where
+    T: Display + Send,
+{
+    value.to_string()
+}
+
+struct Splitter<'s> {
+    separator: &'s str,
+}
+
+/// Future suspending once before completing, so borrows have to live across an `.await`.
+async fn yield_once() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if std::mem::replace(&mut yielded, true) {
+            std::task::Poll::Ready(())
+        } else {
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    })
+    .await;
+}
+
+impl<'s> Splitter<'s> {
+    #[time_it]
+    async fn split<'a, 'b>(&'a self, input: &'b str) -> Vec<&'b str> {
+        yield_once().await;
+        input.split(self.separator).collect()
+    }
+
+    #[time_it(log_args, log_result)]
+    async fn first<'a, 'b>(&'a self, input: &'b str) -> Option<&'b str> {
+        yield_once().await;
+        input.split(self.separator).next()
+    }
+
+    #[time_it(span, poll_count, on_cancel)]
+    async fn count<'a, 'b: 'a>(&'a self, input: &'b str, _: &'a mut Vec<&'b str>) -> usize {
+        yield_once().await;
+        input.matches(self.separator).count()
+    }
+
+    #[time_it(future, log_result)]
+    fn last<'a, 'b>(
+        &'a self,
+        input: &'b str,
+    ) -> impl std::future::Future<Output = Option<&'b str>> + 'a
+    where
+        'b: 'a,
+    {
+        async move {
+            yield_once().await;
+            input.rsplit(self.separator).next()
+        }
+    }
+}
+
+#[time_it(log_result)]
+async fn chunk<'a, const N: usize>(input: &'a [u8]) -> Option<&'a [u8; N]> {
+    yield_once().await;
+    input.first_chunk()
+}
+
+/// Polls `future` until it completes.
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    loop {
+        if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn generic_functions() {
+    assert_eq!(describe(5, "s"), "5s");
+    assert_eq!(longest("ab", "c"), "ab");
+    assert_eq!(first([3, 4]), 3);
+    assert_eq!(first::<0, u8>([]), 0);
+    assert_eq!(parse::<u32>(" 7 "), Ok(7));
+    assert!(parse::<u32>("x").is_err());
+}
+
+#[test]
+fn generic_methods() {
+    let mut wrapper = Wrapper(String::from("a"));
+    assert_eq!(wrapper.get(), "a");
+    assert_eq!(wrapper.replace("b"), "a");
+    assert_eq!(wrapper.get(), "b");
+}
+
+#[test]
+fn generic_async_functions() {
+    // Only checks that the future type is well formed, which is where generics could break.
+    fn assert_send<F: std::future::Future + Send>(_: F) {}
+    assert_send(describe_async(5));
+}
+
+#[test]
+fn async_methods_with_several_lifetimes() {
+    let separator = String::from(",");
+    let splitter = Splitter {
+        separator: &separator,
+    };
+    let input = String::from("a,b,c");
+    let mut seen = Vec::new();
+    assert_eq!(block_on(splitter.split(&input)), ["a", "b", "c"]);
+    assert_eq!(block_on(splitter.first(&input)), Some("a"));
+    assert_eq!(block_on(splitter.count(&input, &mut seen)), 2);
+    assert_eq!(block_on(splitter.last(&input)), Some("c"));
+    assert_eq!(block_on(chunk::<2>(input.as_bytes())), Some(b"a,"));
+}
@@ -0,0 +1,299 @@
+//! `set_global_hook` installs a hook every measurement is passed to, whatever the arguments of the
+//! timed code.
+#![cfg(all(feature = "global_hook", not(feature = "no_std")))]
+
+mod common;
+
+use std::{
+    cell::{Cell, RefCell},
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use time_it::{time_block, time_it};
+use tracing::Level;
+
+/// A timing passed to the hook.
+type Hooked = (String, Duration, Level);
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+    /// Timings passed to the hook on this thread.
+    static HOOKED: RefCell<Vec<Hooked>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Held by every test, as the hook is shared by all of them.
+static HOOK: Mutex<()> = Mutex::new(());
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+/// Advances the fake clock by `millis`.
+fn advance(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+/// Installs a hook keeping the timings it receives in `HOOKED`, which only works as it runs on
+/// the thread emitting the event.
+fn install() -> MutexGuard<'static, ()> {
+    let guard = HOOK.lock().unwrap_or_else(PoisonError::into_inner);
+    time_it::set_global_hook(|name, duration, level| {
+        HOOKED.with(|hooked| hooked.borrow_mut().push((name.to_owned(), duration, level)));
+    });
+    guard
+}
+
+/// The timings passed to the hook on this thread so far.
+fn hooked() -> Vec<Hooked> {
+    HOOKED.with(RefCell::take)
+}
+
+#[time_it("info", clock_fn = "FakeInstant::now")]
+fn handle(millis: u64) {
+    advance(millis);
+}
+
+#[time_it("debug", err_level = "warn", label = "parse")]
+fn fallible(fail: bool) -> Result<(), ()> {
+    if fail { Err(()) } else { Ok(()) }
+}
+
+#[time_it(structured, threshold = "10ms", clock_fn = "FakeInstant::now")]
+fn thresholded(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(aggregate, clock_fn = "FakeInstant::now")]
+fn aggregated(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(ring = 2)]
+fn recorded() {}
+
+#[time_it(silent, quantiles)]
+fn silenced() {}
+
+#[time_it(tiers = "1ms: warn, 1s: error", clock_fn = "FakeInstant::now")]
+fn tiered(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(span, "debug", clock_fn = "FakeInstant::now")]
+fn spanned(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(in_span)]
+fn recorded_in_span() {}
+
+#[time_it(handler = |_, _| {}, clock_fn = "FakeInstant::now")]
+fn handled(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(threshold = "10ms", summarize = 2, clock_fn = "FakeInstant::now")]
+fn summarized(millis: u64) {
+    advance(millis);
+}
+
+#[time_it("info")]
+fn nested() {}
+
+#[test]
+fn events_are_passed_to_the_hook() {
+    let _hook = install();
+    let ((), events) = common::capture(|| {
+        handle(5);
+        handle(12);
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[handle]: Execution time: 5ms"));
+    assert_eq!(
+        hooked(),
+        [
+            ("handle".to_owned(), Duration::from_millis(5), Level::INFO),
+            ("handle".to_owned(), Duration::from_millis(12), Level::INFO),
+        ]
+    );
+}
+
+#[test]
+fn the_hook_receives_the_level_events_are_emitted_at() {
+    let _hook = install();
+    let ((), levels) = common::capture_levels(|| {
+        fallible(false).unwrap();
+        fallible(true).unwrap_err();
+    });
+    assert_eq!(levels, [Level::DEBUG, Level::WARN]);
+    let hooked: Vec<_> = hooked()
+        .into_iter()
+        .map(|(name, _, level)| (name, level))
+        .collect();
+    assert_eq!(
+        hooked,
+        [
+            ("parse".to_owned(), Level::DEBUG),
+            ("parse".to_owned(), Level::WARN)
+        ]
+    );
+}
+
+#[test]
+fn tiered_calls_are_passed_to_the_hook_at_their_tier() {
+    let _hook = install();
+    let ((), levels) = common::capture_levels(|| {
+        tiered(0);
+        tiered(5);
+        tiered(1000);
+    });
+    assert_eq!(levels, [Level::DEBUG, Level::WARN, Level::ERROR]);
+    let hooked: Vec<_> = hooked().into_iter().map(|(_, _, level)| level).collect();
+    assert_eq!(hooked, levels);
+}
+
+#[test]
+fn calls_below_the_threshold_are_passed_to_the_hook() {
+    let _hook = install();
+    let ((), events) = common::capture(|| {
+        thresholded(3);
+        thresholded(15);
+        summarized(2);
+        summarized(4);
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[1].starts_with("message=[summarized]: 2 fast calls totaling 6ms"));
+    let hooked: Vec<_> = hooked()
+        .into_iter()
+        .map(|(name, duration, _)| (name, duration))
+        .collect();
+    assert_eq!(
+        hooked,
+        [
+            ("thresholded".to_owned(), Duration::from_millis(3)),
+            ("thresholded".to_owned(), Duration::from_millis(15)),
+            ("summarized".to_owned(), Duration::from_millis(2)),
+            ("summarized".to_owned(), Duration::from_millis(4)),
+        ]
+    );
+}
+
+#[test]
+fn calls_reported_without_an_event_are_passed_to_the_hook() {
+    let _hook = install();
+    let ((), events) = common::capture(|| {
+        aggregated(3);
+        recorded();
+        silenced();
+        handled(4);
+    });
+    assert!(events.is_empty(), "{events:?}");
+    assert_eq!(aggregated_stats(), (1, Duration::from_millis(3)));
+    let hooked: Vec<_> = hooked()
+        .into_iter()
+        .map(|(name, _, level)| (name, level))
+        .collect();
+    assert_eq!(
+        hooked,
+        [
+            ("aggregated".to_owned(), Level::DEBUG),
+            ("recorded".to_owned(), Level::DEBUG),
+            ("silenced".to_owned(), Level::DEBUG),
+            ("handled".to_owned(), Level::DEBUG),
+        ]
+    );
+}
+
+#[test]
+fn spans_are_passed_to_the_hook() {
+    let _hook = install();
+    let ((), events) = common::capture(|| {
+        spanned(7);
+        recorded_in_span();
+    });
+    assert!(events.is_empty(), "{events:?}");
+    let hooked = hooked();
+    assert_eq!(hooked.len(), 2);
+    assert_eq!(
+        hooked[0],
+        ("spanned".to_owned(), Duration::from_millis(7), Level::DEBUG)
+    );
+    assert_eq!(
+        (hooked[1].0.as_str(), hooked[1].2),
+        ("recorded_in_span", Level::DEBUG)
+    );
+}
+
+#[test]
+fn timers_are_passed_to_the_hook_at_their_level() {
+    let _hook = install();
+    let ((), _) = common::capture(|| {
+        let _timer = time_it::Timer::new("timer", Level::TRACE);
+    });
+    let hooked = hooked();
+    assert_eq!(hooked.len(), 1);
+    assert_eq!((hooked[0].0.as_str(), hooked[0].2), ("timer", Level::TRACE));
+}
+
+#[test]
+fn blocks_are_passed_to_the_hook() {
+    let _hook = install();
+    let ((), _) = common::capture(|| {
+        time_block!("block", "error", {});
+    });
+    let hooked = hooked();
+    assert_eq!(hooked.len(), 1);
+    assert_eq!((hooked[0].0.as_str(), hooked[0].2), ("block", Level::ERROR));
+}
+
+#[cfg(feature = "scopes")]
+#[test]
+fn scopes_are_passed_to_the_hook() {
+    let _hook = install();
+    let ((), _) = common::capture(|| time_it::scope!("request", warn, { handle(2) }));
+    let hooked: Vec<_> = hooked()
+        .into_iter()
+        .map(|(name, _, level)| (name, level))
+        .collect();
+    assert_eq!(
+        hooked,
+        [
+            ("handle".to_owned(), Level::INFO),
+            ("request".to_owned(), Level::WARN)
+        ]
+    );
+}
+
+#[test]
+fn the_hook_can_call_timed_code() {
+    let _hook = HOOK.lock().unwrap_or_else(PoisonError::into_inner);
+    time_it::set_global_hook(|name, _, _| {
+        // Only the outer call is passed on, or the hook would recurse forever.
+        if name == "handle" {
+            nested();
+        }
+    });
+    let ((), events) = common::capture(|| handle(1));
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[nested]: Execution time: "));
+}
+
+#[test]
+fn cleared_hooks_are_not_called() {
+    let _hook = install();
+    time_it::clear_global_hook();
+    let ((), events) = common::capture(|| handle(1));
+    assert_eq!(events.len(), 1);
+    assert!(hooked().is_empty());
+}
@@ -0,0 +1,67 @@
+//! `handler` calls an inline closure with the name and duration instead of emitting an event.
+
+mod common;
+
+use std::{cell::RefCell, time::Duration};
+
+use time_it::{time_block, time_it};
+
+thread_local! {
+    /// Calls reported to the handlers.
+    static HANDLED: RefCell<Vec<(&'static str, Duration)>> = const { RefCell::new(Vec::new()) };
+}
+
+#[time_it(handler = |name, duration| HANDLED.with(|handled| handled.borrow_mut().push((name, duration))))]
+fn handled(value: u32) -> u32 {
+    value + 1
+}
+
+#[time_it(handler = |name: &str, _: Duration| {
+    HANDLED.with(|handled| handled.borrow_mut().push((name, Duration::MAX)));
+})]
+fn annotated() {}
+
+#[time_it(threshold = "100s", handler = |name, duration| HANDLED.with(|handled| handled.borrow_mut().push((name, duration))))]
+fn fast() {}
+
+/// Takes the calls reported so far.
+fn take() -> Vec<(&'static str, Duration)> {
+    HANDLED.with(|handled| handled.take())
+}
+
+#[test]
+fn the_closure_replaces_the_event() {
+    let (value, events) = common::capture(|| {
+        handled(1);
+        handled(2)
+    });
+    assert_eq!(value, 3);
+    assert!(events.is_empty());
+    let handled = take();
+    assert_eq!(handled.len(), 2);
+    assert!(handled.iter().all(|&(name, _)| name == "handled"));
+}
+
+#[test]
+fn closures_can_annotate_their_arguments() {
+    annotated();
+    assert_eq!(take(), [("annotated", Duration::MAX)]);
+}
+
+#[test]
+fn filters_apply_before_the_closure() {
+    fast();
+    assert!(take().is_empty());
+}
+
+#[test]
+fn blocks_accept_handlers() {
+    let value = time_block!(
+        "block",
+        handler =
+            |name, duration| HANDLED.with(|handled| handled.borrow_mut().push((name, duration))),
+        { 1 }
+    );
+    assert_eq!(value, 1);
+    assert_eq!(take()[0].0, "block");
+}
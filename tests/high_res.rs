@@ -0,0 +1,78 @@
+//! `clock = "high_res"` measures with `quanta::Instant` instead of `std::time::Instant`.
+#![cfg(feature = "high_res")]
+
+mod common;
+
+use std::{
+    cell::Cell,
+    pin::pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use time_it::{time_block, time_it};
+
+// The expansion refers to `::quanta`, which this crate stands in for.
+extern crate self as quanta;
+
+thread_local! {
+    /// Time of the fake counter, only advanced by `tick`.
+    static COUNTER: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Stands in for `quanta::Instant`, so the measured time is exactly what `tick` advances.
+#[derive(Clone, Copy)]
+pub struct Instant(Duration);
+
+impl Instant {
+    pub fn now() -> Self {
+        Self(COUNTER.with(Cell::get))
+    }
+
+    pub fn duration_since(&self, earlier: Self) -> Duration {
+        self.0 - earlier.0
+    }
+}
+
+/// Advances the fake counter by `nanos`.
+fn tick(nanos: u64) {
+    COUNTER.with(|counter| counter.set(counter.get() + Duration::from_nanos(nanos)));
+}
+
+#[time_it(clock = "high_res")]
+fn precise() {
+    tick(42);
+}
+
+#[time_it(clock = "high_res", exclude_idle)]
+async fn polled() {
+    tick(7);
+}
+
+#[test]
+fn durations_are_read_from_the_high_resolution_clock() {
+    let ((), events) = common::capture(precise);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[precise]: Execution time: 42ns"));
+}
+
+#[test]
+fn blocks_can_use_the_high_resolution_clock() {
+    let ((), events) = common::capture(|| {
+        time_block!("block", clock = "high_res", {
+            tick(3);
+        });
+    });
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[block]: Execution time: 3ns"));
+}
+
+#[test]
+fn busy_time_uses_the_high_resolution_clock() {
+    let ((), events) = common::capture(|| {
+        let poll = pin!(polled()).poll(&mut Context::from_waker(Waker::noop()));
+        assert_eq!(poll, Poll::Ready(()));
+    });
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[polled]: Execution time: 7ns"));
+}
@@ -0,0 +1,47 @@
+//! Bodies using the names the expansion relies on behave as written.
+
+mod common;
+
+use std::{
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+#[time_it(log_result, err_level = "warn")]
+#[allow(clippy::let_and_return)]
+fn shadowing(fail: bool) -> Result<u32, String> {
+    let __start = 1;
+    let __duration = 2;
+    let result = if fail {
+        Err("failed".to_owned())
+    } else {
+        Ok(__start + __duration)
+    };
+    result
+}
+
+#[time_it(log_result)]
+#[allow(clippy::let_and_return)]
+async fn shadowing_async() -> u32 {
+    let result = 3;
+    let __duration = result;
+    __duration
+}
+
+#[test]
+fn body_locals_do_not_collide_with_the_expansion() {
+    let (value, events) = common::capture(|| shadowing(false));
+    assert_eq!(value, Ok(3));
+    assert!(events[0].contains(", returned: Ok(3)"));
+
+    let (value, events) = common::capture(|| shadowing(true));
+    assert_eq!(value, Err("failed".to_owned()));
+    assert!(events[0].contains(" (failed)"));
+
+    let (value, events) =
+        common::capture(|| pin!(shadowing_async()).poll(&mut Context::from_waker(Waker::noop())));
+    assert_eq!(value, Poll::Ready(3));
+    assert!(events[0].contains(", returned: 3"));
+}
@@ -0,0 +1,100 @@
+//! An impl level `#[time_it]` times every method of the block not marked with `skip`.
+
+mod common;
+
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+/// Polls a future that never suspends to completion.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future suspended"),
+    }
+}
+
+struct Store {
+    entries: Vec<u32>,
+}
+
+#[time_it("info")]
+impl Store {
+    const fn capacity() -> usize {
+        16
+    }
+
+    fn new() -> Self {
+        Self {
+            entries: Vec::with_capacity(Self::capacity()),
+        }
+    }
+
+    fn insert(&mut self, entry: u32) {
+        self.entries.push(entry);
+    }
+
+    async fn fetch(&self, index: usize) -> Option<u32> {
+        self.entries.get(index).copied()
+    }
+
+    #[time_it(label = "Store::len")]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[time_it]
+impl Point {
+    fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    #[time_it(skip)]
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn length_squared(&self) -> i32 {
+        self.x() * self.x() + self.y * self.y
+    }
+}
+
+#[test]
+fn sync_and_async_methods_are_timed() {
+    let ((entry, len), events) = common::capture(|| {
+        let mut store = Store::new();
+        store.insert(7);
+        (block_on(store.fetch(0)), store.len())
+    });
+    assert_eq!(entry, Some(7));
+    assert_eq!(len, 1);
+    assert_eq!(events.len(), 4);
+    assert!(events[0].starts_with("message=[new]: Execution time: "));
+    assert!(events[1].starts_with("message=[insert]: Execution time: "));
+    assert!(events[2].starts_with("message=[fetch]: Execution time: "));
+    // The method's own attribute takes precedence over the block's.
+    assert!(events[3].starts_with("message=[Store::len]: Execution time: "));
+}
+
+#[test]
+fn skipped_methods_are_not_timed() {
+    let (length, events) = common::capture(|| Point::new(3, 4).length_squared());
+    assert_eq!(length, 25);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[new]: Execution time: "));
+    assert!(events[1].starts_with("message=[length_squared]: Execution time: "));
+}
@@ -0,0 +1,108 @@
+//! `inner_fn` moves the body into a nested function, which keeps generics, lifetimes, patterns and
+//! opaque return types working without a closure.
+
+mod common;
+
+use std::{
+    fmt::{Debug, Display},
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+#[time_it(inner_fn, log_args, log_result)]
+fn describe<T: Display + Debug, U>(value: T, suffix: U) -> String
+where
+    U: AsRef<str> + Debug,
+{
+    format!("{value}{}", suffix.as_ref())
+}
+
+#[time_it(inner_fn)]
+fn longest<'a, 'b: 'a>(left: &'a str, right: &'b str) -> &'a str {
+    if left.len() >= right.len() {
+        return left;
+    }
+    right
+}
+
+// `T` only appears in the return type, so it has to be passed to the nested function.
+#[time_it(inner_fn, log_result)]
+fn parse<T: std::str::FromStr + Debug>(input: &str) -> Option<T> {
+    input.parse().ok()
+}
+
+#[time_it(inner_fn)]
+fn evens<const N: usize>(values: [u32; N]) -> impl Iterator<Item = u32> {
+    values.into_iter().filter(|value| value % 2 == 0)
+}
+
+#[time_it(inner_fn, log_args)]
+fn sum(
+    (left, right): (u32, u32),
+    mut extra: Vec<u32>,
+    more: impl IntoIterator<Item = u32> + Debug,
+) -> u32 {
+    extra.extend(more);
+    left + right + extra.iter().sum::<u32>()
+}
+
+#[time_it(inner_fn)]
+async fn fetch<'a>(values: &'a [u32], indices: &[usize]) -> Option<&'a u32> {
+    std::future::ready(()).await;
+    values.get(*indices.first()?)
+}
+
+#[time_it(inner_fn)]
+unsafe fn read(pointer: *const u32) -> u32 {
+    // SAFETY: the caller passes a valid pointer.
+    unsafe { *pointer }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn generics_and_lifetimes_are_forwarded() {
+    let ((), events) = common::capture(|| {
+        assert_eq!(describe(5, "s"), "5s");
+        assert_eq!(longest("a", "bc"), "bc");
+        assert_eq!(parse::<u8>("42"), Some(42));
+    });
+    assert_eq!(events.len(), 3);
+    assert!(events[0].contains(", args: value = 5, suffix = \"s\", returned: \"5s\""));
+    assert!(events[1].starts_with("message=[longest]: Execution time: "));
+    assert!(events[2].contains(", returned: Some(42)"));
+}
+
+#[test]
+fn opaque_returns_and_patterns_pass_through() {
+    let ((), events) = common::capture(|| {
+        assert_eq!(evens([1, 2, 3, 4]).collect::<Vec<_>>(), [2, 4]);
+        assert_eq!(sum((1, 2), vec![3], [4]), 10);
+    });
+    assert_eq!(events.len(), 2);
+    // Arguments bound by a pattern have no name to log.
+    assert!(events[1].contains(", args: extra = [3], more = [4]"));
+}
+
+#[test]
+fn async_and_unsafe_functions_are_supported() {
+    let values = [1, 2];
+    let ((), events) = common::capture(|| {
+        assert_eq!(block_on(fetch(&values, &[1])), Some(&2));
+        // SAFETY: the pointer comes from a reference.
+        assert_eq!(unsafe { read(&values[0]) }, 1);
+    });
+    assert_eq!(events.len(), 2);
+}
@@ -0,0 +1,34 @@
+//! `kind` marks events of async and sync functions.
+
+mod common;
+
+use std::{
+    pin::pin,
+    task::{Context, Waker},
+};
+
+use time_it::time_it;
+
+#[time_it(kind)]
+fn sync_work() {}
+
+#[time_it(kind)]
+async fn async_work() {}
+
+#[time_it(kind, structured)]
+async fn structured_async_work() {}
+
+#[test]
+fn events_carry_the_kind_of_function() {
+    let (_, events) = common::capture(|| {
+        sync_work();
+        let mut cx = Context::from_waker(Waker::noop());
+        let _ = pin!(async_work()).poll(&mut cx);
+        let _ = pin!(structured_async_work()).poll(&mut cx);
+    });
+    assert_eq!(events.len(), 3);
+    assert!(events[0].starts_with("message=[sync_work]: Execution time: "));
+    assert!(events[0].ends_with(" kind=\"sync\" "));
+    assert!(events[1].ends_with(" kind=\"async\" "));
+    assert!(events[2].contains(" kind=\"async\" "));
+}
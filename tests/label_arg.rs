@@ -0,0 +1,64 @@
+//! `label_arg` names each call after the value of one of the function's arguments.
+
+mod common;
+
+use std::fmt;
+
+use time_it::time_it;
+
+#[time_it(label_arg = "op")]
+fn dispatch(op: &str, value: u32) -> u32 {
+    match op {
+        "double" => value * 2,
+        "increment" => value + 1,
+        _ => value,
+    }
+}
+
+/// An operation displayed under its own name.
+struct Op(&'static str);
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "op.{}", self.0)
+    }
+}
+
+#[time_it(label_arg = "op", structured, trace_entry)]
+fn consume(op: Op) {
+    let Op(name) = op;
+    assert!(!name.is_empty());
+}
+
+#[time_it(label_arg = "op", template = "{name} took {duration}")]
+fn templated(op: String) -> String {
+    op
+}
+
+#[test]
+fn each_call_is_named_after_the_argument() {
+    let (value, events) = common::capture(|| dispatch("double", 2) + dispatch("increment", 1));
+    assert_eq!(value, 6);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[double]: Execution time: "));
+    assert!(events[1].starts_with("message=[increment]: Execution time: "));
+}
+
+#[test]
+fn the_argument_is_formatted_before_the_body_moves_it() {
+    let ((), events) = common::capture(|| consume(Op("flush")));
+    assert_eq!(events.len(), 2);
+    assert!(
+        events
+            .iter()
+            .all(|event| event.contains("function=\"op.flush\""))
+    );
+}
+
+#[test]
+fn the_argument_fills_the_template_placeholder() {
+    let (value, events) = common::capture(|| templated("rename".to_owned()));
+    assert_eq!(value, "rename");
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=rename took "));
+}
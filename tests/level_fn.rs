@@ -0,0 +1,64 @@
+//! `level_fn` picks the level of every event when it's emitted, instead of when compiling.
+
+mod common;
+
+use std::cell::Cell;
+
+use time_it::time_it;
+use tracing::Level;
+
+thread_local! {
+    /// Level returned by `current_level`, changed by the tests between calls.
+    static LEVEL: Cell<Level> = const { Cell::new(Level::INFO) };
+}
+
+fn current_level() -> Level {
+    LEVEL.with(Cell::get)
+}
+
+#[time_it(level_fn = current_level)]
+fn bare_path() {}
+
+#[time_it(level_fn = "current_level", trace_entry)]
+fn quoted_path() {}
+
+#[time_it(level_fn = current_level, tiers = "1s:error")]
+fn tiered() {}
+
+#[test]
+fn the_level_is_read_on_every_call() {
+    let ((), levels) = common::capture_levels(|| {
+        LEVEL.with(|level| level.set(Level::INFO));
+        bare_path();
+        LEVEL.with(|level| level.set(Level::WARN));
+        bare_path();
+    });
+    assert_eq!(levels, [Level::INFO, Level::WARN]);
+}
+
+#[test]
+fn entry_events_use_it_too() {
+    let ((), levels) = common::capture_levels(|| {
+        LEVEL.with(|level| level.set(Level::TRACE));
+        quoted_path();
+    });
+    assert_eq!(levels, [Level::TRACE, Level::TRACE]);
+}
+
+#[test]
+fn it_applies_below_the_first_tier() {
+    let ((), levels) = common::capture_levels(|| {
+        LEVEL.with(|level| level.set(Level::ERROR));
+        tiered();
+        LEVEL.with(|level| level.set(Level::DEBUG));
+        tiered();
+    });
+    assert_eq!(levels, [Level::ERROR, Level::DEBUG]);
+}
+
+#[test]
+fn the_message_is_unchanged() {
+    let ((), events) = common::capture(bare_path);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[bare_path]: Execution time: "));
+}
@@ -0,0 +1,78 @@
+//! The level can be given as a string literal, a bare identifier, a `level` argument or a
+//! `tracing::Level` constant.
+
+mod common;
+
+use time_it::{time_block, time_it};
+use tracing::Level;
+
+#[time_it("info")]
+fn quoted() {}
+
+#[time_it(info)]
+fn bare() {}
+
+#[time_it(WARN, label = "upper case")]
+fn upper_case() {}
+
+#[time_it(level = "error")]
+fn named() {}
+
+#[time_it]
+fn default() {}
+
+#[time_it(level = Level::WARN)]
+fn constant() {}
+
+#[time_it(level = tracing::Level::TRACE, err_level = Level::ERROR)]
+fn qualified_constants(fail: bool) -> Result<(), ()> {
+    if fail { Err(()) } else { Ok(()) }
+}
+
+#[test]
+fn levels_are_applied_in_every_form() {
+    let ((), levels) = common::capture_levels(|| {
+        quoted();
+        bare();
+        upper_case();
+        named();
+        default();
+    });
+    assert_eq!(
+        levels,
+        [
+            Level::INFO,
+            Level::INFO,
+            Level::WARN,
+            Level::ERROR,
+            Level::DEBUG
+        ]
+    );
+}
+
+#[test]
+fn levels_can_be_tracing_constants() {
+    let (_, levels) = common::capture_levels(|| {
+        constant();
+        time_block!("block", level = Level::INFO, {});
+        (qualified_constants(false), qualified_constants(true))
+    });
+    assert_eq!(
+        levels,
+        [Level::WARN, Level::INFO, Level::TRACE, Level::ERROR]
+    );
+}
+
+#[test]
+fn blocks_accept_bare_levels() {
+    let (value, levels) = common::capture_levels(|| time_block!("block", trace, { 1 }));
+    assert_eq!(value, 1);
+    assert_eq!(levels, [Level::TRACE]);
+}
+
+#[test]
+fn bare_levels_keep_the_usual_message() {
+    let ((), events) = common::capture(bare);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[bare]: Execution time: "));
+}
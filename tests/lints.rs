@@ -0,0 +1,201 @@
+//! Annotated functions don't introduce warnings in crates denying them, whatever the options.
+//! Also meant to be built with the `disabled` and `max_level_off` features, which pass the code
+//! through unchanged.
+
+#![deny(warnings, clippy::all, clippy::pedantic)]
+
+use std::time::Duration;
+
+use time_it::{time_block, time_closure, time_it};
+
+#[cfg(not(feature = "no_std"))]
+time_it::exclusions!();
+
+#[time_it]
+fn unit() {}
+
+#[time_it(log_result)]
+fn unit_result() {}
+
+#[time_it(log_result)]
+#[allow(clippy::unused_unit)]
+fn unit_result_explicit() -> () {}
+
+#[time_it(log_result, log_args)]
+fn value(input: u32) -> u32 {
+    input + 1
+}
+
+#[time_it(err_level = "error", log_result, log_err_value, structured)]
+fn fallible(fail: bool) -> Result<u32, String> {
+    if fail { Err("failed".into()) } else { Ok(1) }
+}
+
+#[time_it(threshold = "1ms", unit = "us", precision = 2, location, thread)]
+fn threshold() {}
+
+#[time_it(tiers = "1ms: info, 1s: warn")]
+fn tiered() -> Duration {
+    Duration::ZERO
+}
+
+#[time_it(span, log_args)]
+fn spanned(_input: &str) {}
+
+#[time_it(aggregate)]
+fn aggregated() {}
+
+#[time_it(quantiles)]
+fn percentiles() {}
+
+#[time_it(regression = 3.0)]
+fn regressed() {}
+
+#[time_it(warn_zero)]
+fn resolved() {}
+
+#[time_it(format = "human", sig_figs = 3)]
+fn significant() {}
+
+#[time_it(format = "human", min_unit = "us", sig_figs = 3)]
+fn clamped_significant() {}
+
+#[time_it(format = "human", min_unit = "ms")]
+fn clamped() {}
+
+#[time_it(budget = "1s")]
+fn budgeted() {}
+
+#[time_it(span, category = "network")]
+fn categorized() {}
+
+#[time_it(bytes_arg = "len")]
+fn throughput_arg(len: usize) -> usize {
+    len
+}
+
+#[time_it(bytes_ret, span)]
+fn throughput_ret(len: u32) -> u32 {
+    len
+}
+
+#[time_it(format = "aligned", width = 12)]
+fn columns() {}
+
+#[cfg(not(feature = "no_std"))]
+#[time_it(exclude = "lock")]
+fn excluding() -> u32 {
+    time_it::exclude!("lock", 1)
+}
+
+#[time_it(seq, trace_entry, kind, fields(team = "core", retries = 3))]
+fn numbered() {}
+
+#[time_it(corr_id, trace_entry, structured)]
+fn correlated() {}
+
+#[time_it(first = 2)]
+fn first_calls() {}
+
+#[time_it(sample = 2, ordering = "relaxed")]
+fn sampled() {}
+
+#[time_it(env_gate)]
+fn gated() {}
+
+#[time_it(raw_ns, format = "human")]
+fn raw() {}
+
+#[time_it(log_result)]
+async fn asynchronous(input: u32) -> u32 {
+    std::future::ready(input).await
+}
+
+#[time_it(exclude_idle)]
+async fn busy() {
+    std::future::ready(()).await;
+}
+
+#[time_it(future)]
+#[allow(clippy::manual_async_fn)]
+fn future() -> impl Future<Output = ()> {
+    async {}
+}
+
+fn tail_block() -> u32 {
+    time_block!("tail", { 1 })
+}
+
+fn tail_scope() -> u32 {
+    time_it::time_scope!("tail", info, { 1 })
+}
+
+#[cfg(all(feature = "scopes", not(feature = "no_std")))]
+fn tail_group() -> u32 {
+    time_it::scope!("tail", { 1 })
+}
+
+struct Worker(u32);
+
+impl Worker {
+    #[time_it(log_result, log_args)]
+    fn method(&self, input: u32) -> u32 {
+        self.0 + input
+    }
+
+    #[time_it]
+    fn consuming(self) -> u32 {
+        self.0
+    }
+}
+
+#[test]
+fn annotated_code_compiles_without_warnings() {
+    #[cfg(not(feature = "no_std"))]
+    let _timer = time_it::Timer::new("timer", tracing::Level::INFO);
+    unit();
+    unit_result();
+    unit_result_explicit();
+    assert_eq!(value(1), 2);
+    let _ = fallible(false);
+    threshold();
+    tiered();
+    spanned("input");
+    aggregated();
+    #[cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+    let _ = aggregated_stats();
+    percentiles();
+    #[cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+    let _ = percentiles_quantiles();
+    regressed();
+    resolved();
+    significant();
+    clamped_significant();
+    clamped();
+    columns();
+    budgeted();
+    categorized();
+    assert_eq!(throughput_arg(1), 1);
+    assert_eq!(throughput_ret(1), 1);
+    #[cfg(not(feature = "no_std"))]
+    assert_eq!(excluding(), 1);
+    numbered();
+    correlated();
+    first_calls();
+    sampled();
+    gated();
+    raw();
+    drop(asynchronous(1));
+    drop(future());
+    drop(busy());
+    assert_eq!(Worker(1).method(1), 2);
+    assert_eq!(Worker(1).consuming(), 1);
+    let total = time_block!("block", { 1 + 1 });
+    assert_eq!(total, 2);
+    assert_eq!(tail_block(), 1);
+    assert_eq!(tail_scope(), 1);
+    #[cfg(all(feature = "scopes", not(feature = "no_std")))]
+    assert_eq!(tail_group(), 1);
+    let double = time_closure!("closure", |x: u32| x * 2);
+    assert_eq!(double(2), 4);
+}
@@ -0,0 +1,75 @@
+//! `log_err_value` adds the error of calls returning an `Err` to their escalated event, leaving
+//! the events of successful calls unchanged.
+
+mod common;
+
+use time_it::time_it;
+use tracing::Level;
+
+#[derive(Debug)]
+enum FetchError {
+    NotFound,
+}
+
+#[time_it("info", err_level = "warn", log_err_value)]
+fn fetch(id: u32) -> Result<u32, FetchError> {
+    if id == 0 {
+        Err(FetchError::NotFound)
+    } else {
+        Ok(id)
+    }
+}
+
+#[time_it(err_level = "error", log_err_value, log_result)]
+fn parse(input: &str) -> Result<u8, std::num::ParseIntError> {
+    input.parse()
+}
+
+#[time_it(err_level = "warn", log_err_value, structured)]
+fn structured(fail: bool) -> Result<(), String> {
+    if fail {
+        Err("disk full".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
+#[test]
+fn failures_carry_the_error() {
+    let (_, events) = common::capture(|| (fetch(1), fetch(0)));
+    assert_eq!(events.len(), 2);
+    assert!(!events[0].contains("error"));
+    assert!(events[1].starts_with("message=[fetch]: Execution time: "));
+    assert!(
+        events[1].contains(" (failed), error: NotFound"),
+        "{}",
+        events[1]
+    );
+
+    let (_, levels) = common::capture_levels(|| (fetch(1), fetch(0)));
+    assert_eq!(levels, [Level::INFO, Level::WARN]);
+}
+
+#[test]
+fn errors_combine_with_results() {
+    let (_, events) = common::capture(|| parse("x"));
+    assert!(
+        events[0].contains(
+            ", returned: Err(ParseIntError { kind: InvalidDigit }), error: \
+                            ParseIntError { kind: InvalidDigit }"
+        ),
+        "{}",
+        events[0]
+    );
+}
+
+#[test]
+fn structured_failures_have_an_error_field() {
+    let (_, events) = common::capture(|| (structured(false), structured(true)));
+    assert!(!events[0].contains("error"));
+    assert!(
+        events[1].contains("error=\"\\\"disk full\\\"\""),
+        "{}",
+        events[1]
+    );
+}
@@ -0,0 +1,82 @@
+//! The `max_level_*` features compile out the timing of functions whose events are all below
+//! the selected level. Run with e.g. `--features max_level_info` to cover a given level.
+
+mod common;
+
+use time_it::{time_block, time_it};
+
+#[time_it("trace")]
+fn trace() {}
+
+#[time_it("debug")]
+fn debug() {}
+
+#[time_it("info")]
+fn info() {}
+
+#[time_it("warn")]
+fn warn() {}
+
+#[time_it("error")]
+fn error() {}
+
+#[time_it("debug", err_level = "error")]
+fn fallible() -> Result<(), ()> {
+    Err(())
+}
+
+#[time_it("trace", tiers = "1ns: warn")]
+fn tiered() {}
+
+#[time_it(aggregate)]
+fn aggregated() {}
+
+/// Whether events at `level` are kept by the enabled `max_level_*` features, the most
+/// restrictive of which applies.
+fn kept(level: usize) -> bool {
+    let max = if cfg!(feature = "max_level_off") {
+        0
+    } else if cfg!(feature = "max_level_error") {
+        1
+    } else if cfg!(feature = "max_level_warn") {
+        2
+    } else if cfg!(feature = "max_level_info") {
+        3
+    } else if cfg!(feature = "max_level_debug") {
+        4
+    } else {
+        5
+    };
+    level <= max
+}
+
+#[test]
+fn events_below_the_max_level_are_compiled_out() {
+    let functions: [(fn(), usize); 5] = [(error, 1), (warn, 2), (info, 3), (debug, 4), (trace, 5)];
+    for (function, level) in functions {
+        let ((), events) = common::capture(function);
+        assert_eq!(events.len(), usize::from(kept(level)), "level {level}");
+    }
+}
+
+#[test]
+fn functions_are_kept_while_any_of_their_levels_is() {
+    let (_, events) = common::capture(fallible);
+    assert_eq!(events.len(), usize::from(kept(1)));
+    let ((), events) = common::capture(tiered);
+    assert_eq!(events.len(), usize::from(kept(2)));
+}
+
+#[test]
+fn blocks_below_the_max_level_are_compiled_out() {
+    let (value, events) = common::capture(|| time_block!("block", "debug", { 1 }));
+    assert_eq!(value, 1);
+    assert_eq!(events.len(), usize::from(kept(4)));
+}
+
+#[cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+#[test]
+fn aggregates_are_always_kept() {
+    aggregated();
+    assert_eq!(aggregated_stats().0, 1);
+}
@@ -0,0 +1,131 @@
+//! Methods of traits and impl blocks are timed like free functions.
+
+mod common;
+
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+/// Polls a future that never suspends to completion.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future suspended"),
+    }
+}
+
+trait Greeter {
+    fn name(&self) -> String;
+
+    #[time_it]
+    fn greet(&self) -> String {
+        format!("Hello, {}!", self.name())
+    }
+
+    #[time_it(log_result)]
+    fn shout(&self) -> String {
+        self.greet().to_uppercase()
+    }
+}
+
+struct English;
+
+impl Greeter for English {
+    fn name(&self) -> String {
+        String::from("world")
+    }
+
+    #[time_it(label = "English::shout")]
+    fn shout(&self) -> String {
+        String::from("HELLO!")
+    }
+}
+
+struct Counter(u32);
+
+impl Counter {
+    #[time_it(log_args)]
+    pub fn add(&mut self, amount: u32) -> u32 {
+        self.0 += amount;
+        self.0
+    }
+
+    #[time_it]
+    fn new() -> Self {
+        Self(0)
+    }
+
+    #[time_it]
+    async fn current(&self) -> u32 {
+        self.0
+    }
+
+    #[time_it(log_result)]
+    async fn increment(&mut self) -> u32 {
+        self.0 += 1;
+        self.0
+    }
+
+    /// Borrows from both `self` and `values` in the returned reference.
+    #[time_it]
+    async fn largest<'a>(&'a self, values: &'a [u32]) -> &'a u32 {
+        values.iter().max().unwrap_or(&self.0)
+    }
+}
+
+#[test]
+fn trait_default_methods() {
+    let (greeting, events) = common::capture(|| English.greet());
+    assert_eq!(greeting, "Hello, world!");
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains("[greet]: Execution time: "));
+}
+
+#[test]
+fn trait_impl_methods() {
+    let (shout, events) = common::capture(|| English.shout());
+    assert_eq!(shout, "HELLO!");
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains("[English::shout]: Execution time: "));
+}
+
+#[test]
+fn inherent_methods() {
+    let (total, events) = common::capture(|| {
+        let mut counter = Counter::new();
+        counter.add(2);
+        counter.add(3)
+    });
+    assert_eq!(total, 5);
+    assert_eq!(events.len(), 3);
+    assert!(events[0].contains("[new]: Execution time: "));
+    assert!(events[1].contains("[add]: Execution time: "));
+    assert!(events[2].contains(", args: amount = 3"));
+}
+
+#[test]
+fn async_methods_borrowing_self() {
+    let (values, events) = common::capture(|| {
+        let mut counter = Counter(1);
+        let incremented = block_on(counter.increment());
+        let current = block_on(counter.current());
+        let values = [3, 7];
+        let largest = *block_on(counter.largest(&values));
+        let fallback = *block_on(counter.largest(&[]));
+        (incremented, current, largest, fallback)
+    });
+    assert_eq!(values, (2, 2, 7, 2));
+    assert_eq!(events.len(), 4);
+    assert!(events[0].starts_with("message=[increment]: Execution time: "));
+    assert!(events[0].contains(", returned: 2"));
+    assert!(events[1].starts_with("message=[current]: Execution time: "));
+    assert!(events[2].starts_with("message=[largest]: Execution time: "));
+}
@@ -0,0 +1,108 @@
+//! `min_unit` clamps durations rendered with the human format to a smallest unit, so shorter
+//! durations are rendered relative to it rather than in a finer unit.
+
+mod common;
+
+use std::{cell::Cell, time::Duration};
+
+use time_it::time_it;
+
+thread_local! {
+    /// Duration measured by every call on the fake clock.
+    static ELAPSED: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock, which always measures `ELAPSED`.
+struct FakeInstant;
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self
+    }
+
+    #[allow(clippy::unused_self)]
+    fn elapsed(&self) -> Duration {
+        ELAPSED.with(Cell::get)
+    }
+}
+
+#[time_it(format = "human", min_unit = "us", clock_fn = "FakeInstant::now")]
+fn micros() {}
+
+#[time_it(format = "human", min_unit = "ms", clock_fn = "FakeInstant::now")]
+fn millis() {}
+
+#[time_it(
+    format = "human",
+    min_unit = "us",
+    precision = 2,
+    clock_fn = "FakeInstant::now"
+)]
+fn fractional() {}
+
+#[time_it(
+    format = "human",
+    min_unit = "us",
+    sig_figs = 3,
+    clock_fn = "FakeInstant::now"
+)]
+fn significant() {}
+
+#[time_it(format = "human", min_unit = "ns", clock_fn = "FakeInstant::now")]
+fn nanos() {}
+
+#[time_it("format=human;min_unit=s;clock_fn=FakeInstant::now")]
+fn packed() {}
+
+/// The duration `timed` renders once the fake clock measures `nanos`.
+fn rendered(timed: fn(), nanos: u64) -> String {
+    ELAPSED.with(|elapsed| elapsed.set(Duration::from_nanos(nanos)));
+    let ((), events) = common::capture(timed);
+    let (_, duration) = events[0].split_once("Execution time: ").unwrap();
+    duration.trim_end().to_owned()
+}
+
+#[test]
+fn durations_below_the_unit_are_clamped_to_it() {
+    assert_eq!(rendered(micros, 999), "<1µs");
+    assert_eq!(rendered(micros, 1), "<1µs");
+    assert_eq!(rendered(micros, 0), "<1µs");
+    assert_eq!(rendered(millis, 999_999), "<1ms");
+    assert_eq!(rendered(millis, 850), "<1ms");
+}
+
+#[test]
+fn longer_durations_are_scaled_as_usual() {
+    assert_eq!(rendered(micros, 1_000), "1µs");
+    assert_eq!(rendered(micros, 12_345), "12µs");
+    assert_eq!(rendered(micros, 1_234_567), "1.2ms");
+    assert_eq!(rendered(micros, 1_234_567_890), "1.23s");
+    assert_eq!(rendered(millis, 1_234_567), "1.2ms");
+}
+
+#[test]
+fn precision_renders_fractions_of_the_unit() {
+    assert_eq!(rendered(fractional, 500), "0.50µs");
+    assert_eq!(rendered(fractional, 5), "0.01µs");
+    assert_eq!(rendered(fractional, 1_500), "1.50µs");
+}
+
+#[test]
+fn significant_figures_render_fractions_of_the_unit() {
+    assert_eq!(rendered(significant, 523), "0.523µs");
+    assert_eq!(rendered(significant, 5), "0.00500µs");
+    assert_eq!(rendered(significant, 999_600), "1.00ms");
+    assert_eq!(rendered(significant, 12_345), "12.3µs");
+}
+
+#[test]
+fn nanoseconds_leave_the_format_unchanged() {
+    assert_eq!(rendered(nanos, 5), "5ns");
+    assert_eq!(rendered(nanos, 12_345), "12µs");
+}
+
+#[test]
+fn units_can_be_packed() {
+    assert_eq!(rendered(packed, 999_999_999), "<1s");
+    assert_eq!(rendered(packed, 2_000_000_000), "2.00s");
+}
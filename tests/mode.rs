@@ -0,0 +1,111 @@
+//! `mode` selects between reporting calls as events, the default, and as spans.
+
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+
+use time_it::time_it;
+use tracing::{Event, Metadata, Subscriber, field::Field, field::Visit, span};
+
+/// Subscriber keeping what the timed functions emit, in order: the spans they create with the
+/// fields they declare, the fields recorded into spans, and the fields of events.
+#[derive(Clone, Default)]
+struct Shapes {
+    emitted: Arc<Mutex<Vec<String>>>,
+    next: Arc<AtomicU64>,
+}
+
+/// Collects the names of the visited fields.
+struct Names(Vec<&'static str>);
+
+impl Visit for Names {
+    fn record_debug(&mut self, field: &Field, _: &dyn std::fmt::Debug) {
+        self.0.push(field.name());
+    }
+}
+
+impl Subscriber for Shapes {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        let fields: Vec<_> = span.metadata().fields().iter().map(|f| f.name()).collect();
+        let shape = format!("span {} [{}]", span.metadata().name(), fields.join(", "));
+        self.emitted.lock().unwrap().push(shape);
+        span::Id::from_u64(self.next.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &span::Id, values: &span::Record<'_>) {
+        let mut names = Names(Vec::new());
+        values.record(&mut names);
+        let shape = format!("record [{}]", names.0.join(", "));
+        self.emitted.lock().unwrap().push(shape);
+    }
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut names = Names(Vec::new());
+        event.record(&mut names);
+        let shape = format!("event [{}]", names.0.join(", "));
+        self.emitted.lock().unwrap().push(shape);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+/// Runs `f` with a [`Shapes`] subscriber, returning what was emitted.
+fn capture_shapes(f: impl FnOnce()) -> Vec<String> {
+    let subscriber = Shapes::default();
+    tracing::subscriber::with_default(subscriber.clone(), f);
+    subscriber.emitted.lock().unwrap().clone()
+}
+
+#[time_it]
+fn default() {}
+
+#[time_it(mode = "event")]
+fn event() {}
+
+#[time_it(mode = "event", structured)]
+fn structured_event() {}
+
+#[time_it(mode = "span")]
+fn spanned() {}
+
+#[time_it(mode = "span", span_level = "info", level = "debug")]
+fn spanned_with_event() {}
+
+#[test]
+fn event_mode_emits_an_event() {
+    let expected = ["event [message]"];
+    assert_eq!(capture_shapes(default), expected);
+    assert_eq!(capture_shapes(event), expected);
+    assert_eq!(
+        capture_shapes(structured_event),
+        ["event [message, function, duration_ms]"]
+    );
+}
+
+#[test]
+fn span_mode_records_the_duration_into_a_span() {
+    assert_eq!(
+        capture_shapes(spanned),
+        [
+            "span spanned [duration_ms, panicked]",
+            "record [duration_ms]"
+        ]
+    );
+    assert_eq!(
+        capture_shapes(spanned_with_event),
+        [
+            "span spanned_with_event [duration_ms, panicked]",
+            "record [duration_ms]",
+            "event [message]"
+        ]
+    );
+}
@@ -0,0 +1,89 @@
+//! A module level `#[time_it]` times every function directly inside the module.
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it("info")]
+mod shapes {
+    pub struct Square(pub u32);
+
+    pub const fn sides() -> u32 {
+        4
+    }
+
+    pub fn area(square: &Square) -> u32 {
+        square.0 * square.0
+    }
+
+    pub fn perimeter(square: &Square) -> u32 {
+        square.0 * sides()
+    }
+
+    #[time_it::time_it(label = "shapes::diagonal", log_result)]
+    pub fn diagonal(square: &Square) -> f64 {
+        f64::from(square.0) * std::f64::consts::SQRT_2
+    }
+
+    #[time_it(skip)]
+    pub fn name() -> &'static str {
+        "square"
+    }
+
+    impl Square {
+        pub fn double(&self) -> Self {
+            Self(self.0 * 2)
+        }
+    }
+
+    pub mod nested {
+        pub fn untimed() -> u32 {
+            1
+        }
+    }
+}
+
+#[time_it(aggregate)]
+mod totals {
+    pub fn counted() {}
+}
+
+#[test]
+fn functions_in_the_module_are_timed() {
+    let square = shapes::Square(2);
+    let (results, events) = common::capture(|| (shapes::area(&square), shapes::perimeter(&square)));
+    assert_eq!(results, (4, 8));
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[area]: Execution time: "));
+    assert!(events[1].starts_with("message=[perimeter]: Execution time: "));
+}
+
+#[test]
+fn own_attributes_take_precedence() {
+    let (_, events) = common::capture(|| shapes::diagonal(&shapes::Square(1)));
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[shapes::diagonal]: Execution time: "));
+    assert!(events[0].contains(", returned: 1.41"));
+}
+
+#[test]
+fn skipped_functions_and_other_items_are_left_unchanged() {
+    let (results, events) = common::capture(|| {
+        (
+            shapes::name(),
+            shapes::sides(),
+            shapes::Square(1).double().0,
+            shapes::nested::untimed(),
+        )
+    });
+    assert_eq!(results, ("square", 4, 2, 1));
+    assert!(events.is_empty(), "{events:?}");
+}
+
+#[cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+#[test]
+fn aggregates_are_kept_per_function() {
+    totals::counted();
+    totals::counted();
+    assert_eq!(totals::counted_stats().0, 2);
+}
@@ -0,0 +1,25 @@
+//! `name` replaces the function name in events, like `label`.
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it(name = "cache.lookup")]
+fn lookup() {}
+
+#[time_it(name = "cache.lookup", template = "{name} done in {duration}")]
+fn templated() {}
+
+#[test]
+fn name_replaces_the_function_name() {
+    let ((), events) = common::capture(lookup);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[cache.lookup]: Execution time: "));
+}
+
+#[test]
+fn name_fills_the_template_placeholder() {
+    let ((), events) = common::capture(templated);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=cache.lookup done in "));
+}
@@ -0,0 +1,62 @@
+//! `name_field` sets the key of the field naming the function in structured events.
+
+mod common;
+
+use time_it::{time_block, time_it};
+
+#[time_it(structured, name_field = "operation")]
+fn renamed() {}
+
+#[time_it(structured, name_field = "fn", trace_entry)]
+fn keyword() {}
+
+#[time_it(structured)]
+fn default_key() {}
+
+#[time_it("structured;name_field=op")]
+fn packed() {}
+
+#[test]
+fn structured_events_use_the_chosen_key() {
+    let ((), events) = common::capture(renamed);
+    assert_eq!(events.len(), 1);
+    assert!(
+        events[0].contains(" operation=\"renamed\" duration_ms="),
+        "{}",
+        events[0]
+    );
+    assert!(!events[0].contains(" function="), "{}", events[0]);
+}
+
+#[test]
+fn entry_events_use_the_chosen_key() {
+    let ((), events) = common::capture(keyword);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains(" fn=\"keyword\" "), "{}", events[0]);
+    assert!(
+        events[1].contains(" fn=\"keyword\" duration_ms="),
+        "{}",
+        events[1]
+    );
+}
+
+#[test]
+fn the_key_defaults_to_function() {
+    let ((), events) = common::capture(default_key);
+    assert!(
+        events[0].contains(" function=\"default_key\" "),
+        "{}",
+        events[0]
+    );
+}
+
+#[test]
+fn keys_can_be_packed_and_given_to_blocks() {
+    let ((), events) = common::capture(|| {
+        packed();
+        time_block!("block", structured, name_field = "span_name", {});
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains(" op=\"packed\" "), "{}", events[0]);
+    assert!(events[1].contains(" span_name=\"block\" "), "{}", events[1]);
+}
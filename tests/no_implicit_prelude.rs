@@ -0,0 +1,268 @@
+//! Annotated code compiles and runs in crates without the implicit prelude, whatever the options.
+
+#![no_implicit_prelude]
+
+use ::core::result::Result::{self, Err, Ok};
+use ::core::time::Duration;
+use ::time_it::{time_block, time_closure, time_it, time_scope};
+
+#[cfg(not(feature = "no_std"))]
+::time_it::exclusions!();
+
+#[time_it]
+fn unit() {}
+
+#[time_it(log_result, log_args)]
+fn value(input: u32) -> u32 {
+    input + 1
+}
+
+#[time_it(
+    err_level = "error",
+    log_result,
+    log_err_value,
+    err_template = "{name} failed"
+)]
+fn fallible(fail: bool) -> Result<u32, ::std::string::String> {
+    if fail {
+        Err(::std::string::String::new())
+    } else {
+        Ok(1)
+    }
+}
+
+#[time_it(label_arg = "op")]
+fn dispatch(op: &str) {}
+
+#[time_it(skip_err)]
+fn skipped(fail: bool) -> Result<(), ()> {
+    if fail { Err(()) } else { Ok(()) }
+}
+
+#[time_it(threshold = "1ms", unit = "us", precision = 2, location, thread)]
+fn threshold() {}
+
+#[time_it("trace", tiers = "1ms:debug,1s:warn", format = "human")]
+fn tiered() {}
+
+#[time_it(template = "{name} took {duration}", repr = "nanos", raw_ns)]
+fn templated() {}
+
+#[time_it(span, log_args, span_level = "info")]
+fn spanned(_input: &str) {}
+
+#[time_it(in_span)]
+fn in_span() {}
+
+#[time_it(record = "elapsed_ms")]
+fn recorded_into() {}
+
+#[time_it(aggregate, ordering = "seqcst")]
+fn aggregated() {}
+
+#[time_it(ring = 4)]
+fn recorded() {}
+
+#[time_it(quantiles)]
+fn percentiles() {}
+
+#[time_it(seq, corr_id, trace_entry, kind, fields(team = "core", retries = 3))]
+fn numbered() {}
+
+#[time_it(first = 2, env_gate)]
+fn filtered() {}
+
+#[time_it(sample = 2, structured, name_field = "operation")]
+fn sampled() {}
+
+#[time_it(threshold = "1s", summarize = 2)]
+fn summarized() {}
+
+#[time_it(threshold = "1ns", slow_level = "warn", rate_limit = "1s")]
+fn limited() {}
+
+#[time_it(regression = 2.5)]
+fn regressed() {}
+
+#[time_it(warn_zero)]
+fn resolved() {}
+
+#[time_it(format = "human", sig_figs = 3)]
+fn significant() {}
+
+#[time_it(format = "human", min_unit = "us", sig_figs = 3)]
+fn clamped_significant() {}
+
+#[time_it(format = "human", min_unit = "ms")]
+fn clamped() {}
+
+#[time_it(budget = "1s")]
+fn budgeted() {}
+
+#[time_it(span, category = "network")]
+fn categorized() {}
+
+#[time_it(bytes_arg = "len")]
+fn throughput_arg(len: usize) -> usize {
+    len
+}
+
+#[time_it(bytes_ret, span)]
+fn throughput_ret(len: u32) -> u32 {
+    len
+}
+
+#[time_it(format = "aligned", width = 12)]
+fn columns() {}
+
+#[cfg(not(feature = "no_std"))]
+#[time_it(exclude = "lock")]
+fn excluding() -> u32 {
+    ::time_it::exclude!("lock", 1)
+}
+
+#[time_it(depth, top_only)]
+fn recursive(depth: u32) {
+    if depth > 0 {
+        recursive(depth - 1);
+    }
+}
+
+#[time_it(inner_fn, log_result)]
+fn hoisted<T: ::core::fmt::Debug>(value: T) -> T {
+    value
+}
+
+#[time_it(include_drops)]
+fn dropping(_values: ::std::vec::Vec<u32>) {}
+
+#[time_it(since_start, chrome_trace, structured)]
+fn timeline() {}
+
+#[time_it(output = "stdout")]
+fn printed() {}
+
+/// Receives the name and duration of `completed`.
+fn record(_name: &'static str, _duration: Duration) {}
+
+#[time_it(on_complete = "record")]
+fn completed() {}
+
+#[time_it(handler = |_, _| {})]
+fn handled() {}
+
+#[time_it(sink = "channel")]
+fn sent() {}
+
+/// Chooses the level of `leveled` at runtime.
+fn current_level() -> ::tracing::Level {
+    ::tracing::Level::INFO
+}
+
+#[time_it(level_fn = current_level)]
+fn leveled() {}
+
+#[time_it(parent = "::tracing::Span::current()")]
+fn parented() {}
+
+#[time_it]
+async fn asynchronous() {}
+
+#[time_it(exclude_idle)]
+async fn busy() {}
+
+#[time_it(future, on_cancel, poll_count, split_await)]
+fn future() -> impl ::core::future::Future<Output = u32> {
+    async { 1 }
+}
+
+struct Service;
+
+#[time_it(structured)]
+impl Service {
+    fn method(&self) -> u32 {
+        1
+    }
+}
+
+#[time_it]
+mod module {
+    pub fn free() {}
+}
+
+/// Polls `future` to completion on the current thread.
+fn block_on<F: ::core::future::Future>(future: F) -> F::Output {
+    let mut future = ::core::pin::pin!(future);
+    let mut context = ::core::task::Context::from_waker(::core::task::Waker::noop());
+    loop {
+        if let ::core::task::Poll::Ready(output) =
+            ::core::future::Future::poll(future.as_mut(), &mut context)
+        {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn annotated_code_runs() {
+    unit();
+    ::core::assert_eq!(value(1), 2);
+    ::core::assert!(fallible(true).is_err());
+    ::core::assert_eq!(fallible(false), Ok(1));
+    ::core::assert!(skipped(false).is_ok());
+    dispatch("op");
+    threshold();
+    tiered();
+    templated();
+    spanned("input");
+    in_span();
+    recorded_into();
+    aggregated();
+    #[cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+    ::core::assert_eq!(aggregated_stats().0, 1);
+    recorded();
+    #[cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+    ::core::assert_eq!(recorded_recent().len(), 1);
+    percentiles();
+    #[cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+    ::core::assert!(percentiles_quantiles().is_some());
+    numbered();
+    filtered();
+    sampled();
+    summarized();
+    summarized();
+    limited();
+    regressed();
+    resolved();
+    significant();
+    clamped_significant();
+    clamped();
+    columns();
+    budgeted();
+    categorized();
+    ::core::assert_eq!(throughput_arg(1), 1);
+    ::core::assert_eq!(throughput_ret(1), 1);
+    #[cfg(not(feature = "no_std"))]
+    ::core::assert_eq!(excluding(), 1);
+    recursive(2);
+    ::core::assert_eq!(hoisted(3), 3);
+    dropping(::std::vec::Vec::new());
+    timeline();
+    printed();
+    completed();
+    handled();
+    sent();
+    leveled();
+    parented();
+    block_on(asynchronous());
+    block_on(busy());
+    ::core::assert_eq!(block_on(future()), 1);
+    ::core::assert_eq!(Service.method(), 1);
+    module::free();
+    let block = time_block!("block", level = "info", threshold = "1ns", { 1 });
+    let scope = time_scope!("scope", info, { 2 });
+    #[cfg(not(feature = "no_std"))]
+    let _timer = ::time_it::Timer::new("timer", ::tracing::Level::INFO);
+    let closure = time_closure!("closure", |value: u32| value + 1);
+    ::core::assert_eq!(block + scope + closure(0), 4);
+}
@@ -0,0 +1,93 @@
+//! With the `no_std` feature, the expansion only relies on `core`, so it builds in `#![no_std]`
+//! crates given a `clock_fn`.
+#![cfg(feature = "no_std")]
+#![no_std]
+
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicU8, AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use time_it::{time_block, time_it};
+
+/// Microseconds counted by a fake hardware timer.
+static TICKS: AtomicU64 = AtomicU64::new(0);
+
+fn advance(micros: u64) {
+    TICKS.fetch_add(micros, Ordering::Relaxed);
+}
+
+struct Ticks(u64);
+
+impl Ticks {
+    fn now() -> Self {
+        Self(TICKS.load(Ordering::Relaxed))
+    }
+
+    fn elapsed(&self) -> Duration {
+        Duration::from_micros(TICKS.load(Ordering::Relaxed) - self.0)
+    }
+}
+
+/// Last message written by `sink!`, which stands in for e.g. `defmt::info!`.
+static MESSAGE: [AtomicU8; 64] = [const { AtomicU8::new(0) }; 64];
+static MESSAGE_LEN: AtomicUsize = AtomicUsize::new(0);
+
+/// Writer keeping the start of the message, which fits in a fixed size buffer without allocating.
+struct Recorder(usize);
+
+impl Write for Recorder {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for &byte in s.as_bytes() {
+            if let Some(slot) = MESSAGE.get(self.0) {
+                slot.store(byte, Ordering::Relaxed);
+                self.0 += 1;
+            }
+        }
+        MESSAGE_LEN.store(self.0, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+macro_rules! sink {
+    ($($arg:tt)*) => {
+        Recorder(0).write_fmt(format_args!($($arg)*)).unwrap()
+    };
+}
+
+fn assert_message(expected: &str) {
+    let len = MESSAGE_LEN.load(Ordering::Relaxed);
+    assert_eq!(len, expected.len());
+    for (slot, byte) in MESSAGE.iter().zip(expected.bytes()) {
+        assert_eq!(slot.load(Ordering::Relaxed), byte);
+    }
+}
+
+#[time_it(clock_fn = "Ticks::now", via = "sink", unit = "us")]
+fn measured() {
+    advance(42);
+}
+
+#[time_it(clock_fn = "Ticks::now", structured, seq, first = 3)]
+fn traced() {}
+
+#[test]
+fn events_are_emitted_without_std() {
+    measured();
+    assert_message("[measured]: Execution time: 42us");
+
+    time_block!(
+        "block",
+        clock_fn = "Ticks::now",
+        via = "sink",
+        unit = "us",
+        {
+            advance(7);
+        }
+    );
+    assert_message("[block]: Execution time: 7us");
+
+    // Without a subscriber, the `tracing` event is discarded.
+    traced();
+}
@@ -0,0 +1,29 @@
+//! `ordering` selects the memory ordering of the generated atomics.
+#![cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it(seq, ordering = "seqcst")]
+fn sequentially_consistent() {}
+
+#[time_it(aggregate, ordering = "acqrel")]
+fn acquire_release() {}
+
+#[time_it(first = 1, ordering = "AcqRel")]
+fn first_acquire_release() {}
+
+#[test]
+fn counters_work_with_any_ordering() {
+    let ((), events) = common::capture(|| {
+        sequentially_consistent();
+        sequentially_consistent();
+        acquire_release();
+        first_acquire_release();
+        first_acquire_release();
+    });
+    assert_eq!(events.len(), 3);
+    assert!(events[1].ends_with(", seq: 1 "));
+    assert_eq!(acquire_release_stats().0, 1);
+}
@@ -0,0 +1,49 @@
+//! Arguments packed in a single string literal behave like the same arguments written apart.
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it("level=info;label=packed;unit=ms;precision=0")]
+fn packed() {}
+
+#[time_it(" label = spaced ; location ; ")]
+fn spaced() {}
+
+#[time_it("label=mixed", log_result)]
+fn mixed() -> u32 {
+    1
+}
+
+#[time_it("threshold=3600s")]
+fn below_threshold() {}
+
+#[test]
+fn packed_arguments_are_applied() {
+    let ((), events) = common::capture(packed);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0], "message=[packed]: Execution time: 0ms ");
+}
+
+#[test]
+fn segments_and_flags_are_trimmed() {
+    let ((), events) = common::capture(spaced);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[spaced]: Execution time: "));
+    assert!(events[0].contains("tests/packed_args.rs:"), "{}", events[0]);
+}
+
+#[test]
+fn packed_arguments_combine_with_the_key_value_form() {
+    let (result, events) = common::capture(mixed);
+    assert_eq!(result, 1);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[mixed]: Execution time: "));
+    assert!(events[0].ends_with(", returned: 1 "));
+}
+
+#[test]
+fn packed_threshold_silences_fast_calls() {
+    let ((), events) = common::capture(below_threshold);
+    assert!(events.is_empty());
+}
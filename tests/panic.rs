@@ -0,0 +1,96 @@
+//! Calls that unwind are still reported, and marked as panicked.
+
+mod common;
+
+use std::{
+    future::Future,
+    panic,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+#[time_it]
+fn explode(panics: bool) {
+    assert!(!panics, "boom");
+}
+
+#[time_it(structured, log_result)]
+fn explode_structured(panics: bool) -> u32 {
+    assert!(!panics, "boom");
+    1
+}
+
+#[time_it]
+async fn explode_async(panics: bool) {
+    assert!(!panics, "boom");
+}
+
+#[time_it(on_cancel)]
+async fn explode_cancellable(panics: bool) {
+    assert!(!panics, "boom");
+}
+
+/// Polls `future` until it completes.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            return output;
+        }
+    }
+}
+
+/// Runs `f`, which must panic, returning the events emitted until it did.
+fn capture_panic(f: impl FnOnce() + panic::UnwindSafe) -> Vec<String> {
+    let (result, events) = common::capture(|| panic::catch_unwind(f));
+    assert!(result.is_err(), "expected a panic");
+    events
+}
+
+#[test]
+fn panics_are_reported() {
+    let events = capture_panic(|| explode(true));
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains("[explode]: Execution time: "));
+    assert!(events[0].contains(" (panicked)"));
+}
+
+#[test]
+fn panics_are_reported_as_field() {
+    let events = capture_panic(|| {
+        explode_structured(true);
+    });
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains("panicked=true"));
+}
+
+#[test]
+fn regular_calls_are_not_marked() {
+    let ((), events) = common::capture(|| {
+        explode(false);
+        explode_structured(false);
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| !event.contains("panicked")));
+}
+
+#[test]
+fn panics_while_polling_are_reported() {
+    let events = capture_panic(|| block_on(explode_async(true)));
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains("[explode_async]: Execution time: "));
+    assert!(events[0].ends_with(" (panicked) "));
+}
+
+#[test]
+fn panics_take_precedence_over_cancellation() {
+    // The future doesn't complete either, but it's reported as panicked rather than cancelled.
+    let events = capture_panic(|| block_on(explode_cancellable(true)));
+    assert_eq!(events.len(), 1);
+    assert!(events[0].ends_with(" (panicked) "));
+}
@@ -0,0 +1,82 @@
+//! `parent` emits the events under an explicitly given span, instead of the current one.
+
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+
+use time_it::{time_block, time_it};
+use tracing::{Event, Metadata, Span, Subscriber, span};
+
+/// Subscriber keeping the name of every span, and the explicit parent of every event.
+#[derive(Clone, Default)]
+struct Parents {
+    spans: Arc<Mutex<Vec<&'static str>>>,
+    parents: Arc<Mutex<Vec<Option<&'static str>>>>,
+    next: Arc<AtomicU64>,
+}
+
+impl Subscriber for Parents {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        self.spans.lock().unwrap().push(span.metadata().name());
+        span::Id::from_u64(self.next.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let spans = self.spans.lock().unwrap();
+        let parent = event
+            .parent()
+            .map(|id| spans[usize::try_from(id.into_u64()).unwrap() - 1]);
+        self.parents.lock().unwrap().push(parent);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+/// Runs `f` with a [`Parents`] subscriber, returning the explicit parents of the events.
+fn capture_parents(f: impl FnOnce()) -> Vec<Option<&'static str>> {
+    let subscriber = Parents::default();
+    tracing::subscriber::with_default(subscriber.clone(), f);
+    subscriber.parents.lock().unwrap().clone()
+}
+
+#[time_it(parent = "request")]
+fn handle(request: &Span) {}
+
+#[time_it(parent = "request.id()", trace_entry, structured)]
+fn handle_structured(request: &Span) {}
+
+#[test]
+fn events_are_emitted_under_the_given_span() {
+    let parents = capture_parents(|| {
+        let request = tracing::info_span!("request");
+        let other = tracing::info_span!("other");
+        let _entered = other.enter();
+        handle(&request);
+        handle_structured(&request);
+        tracing::info!("not timed");
+    });
+    assert_eq!(
+        parents,
+        [Some("request"), Some("request"), Some("request"), None]
+    );
+}
+
+#[test]
+fn blocks_accept_a_parent() {
+    let parents = capture_parents(|| {
+        let request = tracing::info_span!("request");
+        time_block!("block", parent = "&request", {});
+    });
+    assert_eq!(parents, [Some("request")]);
+}
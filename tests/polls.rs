@@ -0,0 +1,104 @@
+//! `poll_count` reports how many times the future was polled before completing.
+
+mod common;
+
+use std::{
+    future::{Future, poll_fn},
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+/// Future suspending once before completing.
+async fn yield_once() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
+/// Polls `future` until it completes.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            return output;
+        }
+    }
+}
+
+#[time_it(poll_count)]
+async fn yielding(times: u32) -> u32 {
+    for _ in 0..times {
+        yield_once().await;
+    }
+    times
+}
+
+#[time_it(poll_count, structured, log_result)]
+async fn yielding_structured() -> u32 {
+    yield_once().await;
+    yield_once().await;
+    2
+}
+
+#[time_it(poll_count, future)]
+fn yielding_future() -> impl Future<Output = ()> {
+    yield_once()
+}
+
+#[time_it(poll_count, span)]
+async fn yielding_spanned() {
+    yield_once().await;
+}
+
+/// Asserts the `Send` bound of the futures is kept.
+fn assert_send<F: Future + Send>(future: F) -> F {
+    future
+}
+
+#[test]
+fn polls_are_counted_until_completion() {
+    let (value, events) = common::capture(|| block_on(assert_send(yielding(3))));
+    assert_eq!(value, 3);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[yielding]: Execution time: "));
+    // Each yield takes a poll, and the last poll completes the future.
+    assert!(events[0].ends_with(", polls: 4 "));
+
+    let (_, events) = common::capture(|| block_on(yielding(0)));
+    assert!(events[0].ends_with(", polls: 1 "));
+}
+
+#[test]
+fn polls_are_a_field_in_structured_mode() {
+    let (value, events) = common::capture(|| block_on(yielding_structured()));
+    assert_eq!(value, 2);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains(" polls=3 "));
+}
+
+#[test]
+fn polls_are_recorded_on_spans() {
+    // The count is a field of the span, which emits no event of its own.
+    let ((), events) = common::capture(|| block_on(yielding_spanned()));
+    assert!(events.is_empty());
+}
+
+#[test]
+fn returned_futures_are_counted() {
+    let ((), events) = common::capture(|| block_on(yielding_future()));
+    assert_eq!(events.len(), 1);
+    assert!(events[0].ends_with(", polls: 2 "));
+}
@@ -0,0 +1,50 @@
+//! `precision` rounds durations rendered with `unit` or the human format.
+
+mod common;
+
+use std::{thread, time::Duration};
+
+use time_it::time_it;
+
+#[time_it(unit = "ms", precision = 2)]
+fn milliseconds() {
+    thread::sleep(Duration::from_millis(1));
+}
+
+#[time_it(format = "human", precision = 3)]
+fn human() {
+    thread::sleep(Duration::from_millis(1));
+}
+
+#[time_it(unit = "s", precision = 0)]
+fn seconds() {}
+
+/// Number of decimals of the duration ending the message, before its `suffix`.
+fn decimals(event: &str, suffix: &str) -> usize {
+    let duration = event
+        .strip_prefix("message=")
+        .and_then(|message| message.split(": ").last())
+        .and_then(|duration| duration.strip_suffix(' '))
+        .and_then(|duration| duration.strip_suffix(suffix))
+        .unwrap();
+    duration
+        .split_once('.')
+        .map_or(0, |(_, decimals)| decimals.len())
+}
+
+#[test]
+fn units_are_rounded_to_the_precision() {
+    let ((), events) = common::capture(milliseconds);
+    assert_eq!(decimals(&events[0], "ms"), 2);
+    let ((), events) = common::capture(seconds);
+    assert_eq!(
+        events[0].strip_suffix("s ").unwrap(),
+        "message=[seconds]: Execution time: 0"
+    );
+}
+
+#[test]
+fn human_durations_are_rounded_to_the_precision() {
+    let ((), events) = common::capture(human);
+    assert_eq!(decimals(&events[0], "ms"), 3);
+}
@@ -0,0 +1,95 @@
+//! `quantiles` records every duration into a histogram, from which percentiles are estimated.
+#![cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+
+mod common;
+
+use std::{cell::Cell, time::Duration};
+
+use time_it::time_it;
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+/// Advances the fake clock by `duration`.
+fn advance(duration: Duration) {
+    NOW.with(|now| now.set(now.get() + duration));
+}
+
+#[time_it(quantiles, clock_fn = "FakeInstant::now")]
+fn uniform(micros: u64) {
+    advance(Duration::from_micros(micros));
+}
+
+#[time_it(quantiles, clock_fn = "FakeInstant::now")]
+fn tiny(nanos: u64) {
+    advance(Duration::from_nanos(nanos));
+}
+
+#[time_it(quantiles, threshold = "1s", clock_fn = "FakeInstant::now")]
+fn fast() {
+    advance(Duration::from_millis(1));
+}
+
+/// Asserts that `estimate` is within 1/32nd of `expected`, half the width of the widest bucket.
+fn assert_close(estimate: Duration, expected: Duration) {
+    let error = estimate.abs_diff(expected);
+    assert!(
+        error <= expected / 32,
+        "{estimate:?} is too far from {expected:?}"
+    );
+}
+
+#[test]
+fn percentiles_of_a_known_distribution_are_estimated() {
+    assert_eq!(uniform_quantiles(), None);
+    let ((), events) = common::capture(|| {
+        for micros in 1..=1000 {
+            uniform(micros);
+        }
+    });
+    // The events are still emitted.
+    assert_eq!(events.len(), 1000);
+    let (p50, p90, p99) = uniform_quantiles().unwrap();
+    assert_close(p50, Duration::from_micros(500));
+    assert_close(p90, Duration::from_micros(900));
+    assert_close(p99, Duration::from_micros(990));
+}
+
+#[test]
+fn short_durations_are_exact() {
+    for nanos in [3, 3, 3, 3, 3, 7, 7, 7, 7, 15] {
+        tiny(nanos);
+    }
+    assert_eq!(
+        tiny_quantiles(),
+        Some((
+            Duration::from_nanos(3),
+            Duration::from_nanos(7),
+            Duration::from_nanos(15)
+        ))
+    );
+}
+
+#[test]
+fn durations_below_the_threshold_are_recorded() {
+    let ((), events) = common::capture(fast);
+    assert!(events.is_empty());
+    let (p50, _, p99) = fast_quantiles().unwrap();
+    assert_close(p50, Duration::from_millis(1));
+    assert_close(p99, Duration::from_millis(1));
+}
@@ -0,0 +1,120 @@
+//! Async functions returning `Result` convert errors with `?` exactly as written.
+
+use std::{
+    fmt::{Debug, Display},
+    future::Future,
+    num::{ParseFloatError, ParseIntError},
+    pin::pin,
+    str::FromStr,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+/// Polls a future that never suspends to completion.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future suspended"),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Error {
+    Int,
+    Float,
+    Other(String),
+}
+
+impl From<ParseIntError> for Error {
+    fn from(_: ParseIntError) -> Self {
+        Self::Int
+    }
+}
+
+impl From<ParseFloatError> for Error {
+    fn from(_: ParseFloatError) -> Self {
+        Self::Float
+    }
+}
+
+#[time_it]
+async fn plain<T: FromStr>(int: &str, float: &str, other: &str) -> Result<(u32, f64, T), Error>
+where
+    T::Err: Display,
+{
+    let int = int.parse::<u32>()?;
+    let float = float.parse::<f64>()?;
+    let other = other
+        .parse()
+        .map_err(|err: T::Err| Error::Other(err.to_string()))?;
+    Ok((int, float, other))
+}
+
+#[time_it(log_result, err_level = "warn")]
+async fn inspected<T: FromStr + Debug>(
+    int: &str,
+    float: &str,
+    other: &str,
+) -> Result<(u32, f64, T), Error>
+where
+    T::Err: Display,
+{
+    let int = int.parse::<u32>()?;
+    let float = float.parse::<f64>()?;
+    let other = other
+        .parse()
+        .map_err(|err: T::Err| Error::Other(err.to_string()))?;
+    Ok((int, float, other))
+}
+
+#[time_it(log_result, err_level = "warn")]
+async fn opaque(int: &str, float: &str) -> Result<impl Display + Debug, Error> {
+    let int = int.parse::<u32>()?;
+    let float = float.parse::<f64>()?;
+    Ok(f64::from(int) + float)
+}
+
+#[time_it(err_level = "warn")]
+async fn boxed(int: &str) -> Result<u32, Box<dyn std::error::Error>> {
+    let int = int.parse::<u32>()?;
+    let doubled = int.checked_mul(2).ok_or("overflow")?;
+    Ok(doubled)
+}
+
+#[test]
+fn question_marks_convert_errors() {
+    assert_eq!(
+        block_on(plain::<bool>("1", "2.5", "true")),
+        Ok((1, 2.5, true))
+    );
+    assert_eq!(block_on(plain::<bool>("x", "2.5", "true")), Err(Error::Int));
+    assert_eq!(block_on(plain::<bool>("1", "x", "true")), Err(Error::Float));
+    assert!(matches!(
+        block_on(plain::<bool>("1", "2.5", "x")),
+        Err(Error::Other(_))
+    ));
+}
+
+#[test]
+fn question_marks_convert_errors_when_inspected() {
+    assert_eq!(
+        block_on(inspected::<bool>("1", "2.5", "true")),
+        Ok((1, 2.5, true))
+    );
+    assert_eq!(
+        block_on(inspected::<bool>("1", "x", "true")),
+        Err(Error::Float)
+    );
+    assert_eq!(block_on(opaque("1", "2.5")).unwrap().to_string(), "3.5");
+    assert!(block_on(opaque("x", "2.5")).is_err());
+    assert_eq!(block_on(boxed("2")).unwrap(), 4);
+    assert_eq!(
+        block_on(boxed("4294967295")).unwrap_err().to_string(),
+        "overflow"
+    );
+}
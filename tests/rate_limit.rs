@@ -0,0 +1,89 @@
+//! `slow_level` raises the level of calls reaching the `threshold`, and `rate_limit` drops the
+//! events following another one within its window.
+
+mod common;
+
+use std::{cell::Cell, thread, time::Duration};
+
+use time_it::time_it;
+use tracing::Level;
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+fn advance(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+#[time_it(
+    threshold = "100ms",
+    slow_level = "error",
+    rate_limit = "1m",
+    clock_fn = "FakeInstant::now"
+)]
+fn alerting(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(
+    info,
+    threshold = "100ms",
+    slow_level = Level::WARN,
+    summarize = 2,
+    clock_fn = "FakeInstant::now"
+)]
+fn summarized(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(rate_limit = "20ms")]
+fn limited() {}
+
+#[test]
+fn a_flood_of_slow_calls_is_reported_once_per_window() {
+    let ((), levels) = common::capture_levels(|| {
+        alerting(10);
+        for _ in 0..50 {
+            alerting(200);
+        }
+    });
+    // The fast call is below the threshold, and the window started by the first slow call covers
+    // the others.
+    assert_eq!(levels, [Level::ERROR]);
+}
+
+#[test]
+fn other_events_keep_the_level() {
+    let ((), levels) = common::capture_levels(|| {
+        summarized(200);
+        summarized(10);
+        summarized(10);
+    });
+    assert_eq!(levels, [Level::WARN, Level::INFO]);
+}
+
+#[test]
+fn reporting_resumes_once_the_window_is_over() {
+    let ((), events) = common::capture(|| {
+        limited();
+        limited();
+        thread::sleep(Duration::from_millis(30));
+        limited();
+    });
+    assert_eq!(events.len(), 2);
+}
@@ -0,0 +1,37 @@
+//! `raw_ns` adds the exact duration in nanoseconds to the event.
+
+mod common;
+
+use std::{sync::Mutex, time::Duration};
+
+use time_it::time_it;
+
+static MEASURED: Mutex<Vec<Duration>> = Mutex::new(Vec::new());
+
+fn measured(_name: &'static str, duration: Duration) {
+    MEASURED.lock().unwrap().push(duration);
+}
+
+#[time_it(raw_ns, on_complete = "measured")]
+fn work() {}
+
+#[time_it(raw_ns, structured, on_complete = "measured")]
+fn structured_work() {}
+
+#[test]
+fn raw_ns_matches_the_measured_duration() {
+    let ((), events) = common::capture(|| {
+        work();
+        structured_work();
+    });
+    let measured = MEASURED.lock().unwrap().clone();
+    assert_eq!(events.len(), 2);
+    assert_eq!(measured.len(), 2);
+    assert!(events[0].contains("[work]: Execution time: "));
+    for (event, duration) in events.iter().zip(measured) {
+        assert!(
+            event.contains(&format!("duration_ns={} ", duration.as_nanos())),
+            "{event} doesn't contain {duration:?}"
+        );
+    }
+}
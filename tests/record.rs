@@ -0,0 +1,125 @@
+//! `record` records the duration into a field of the current span, such as one created by
+//! `#[tracing::instrument]`, instead of emitting an event. `in_span` does the same with the
+//! `duration_ms` field.
+
+use std::sync::{Arc, Mutex};
+
+use time_it::time_it;
+use tracing::{Event, Metadata, Subscriber, field::Field, field::Visit, span};
+
+/// Subscriber keeping what the timed functions emit: the spans they create with the fields they
+/// declare, the fields recorded into spans, and the fields of events. Only one span is created
+/// per test, which is current while entered.
+#[derive(Clone, Default)]
+struct Recorded {
+    emitted: Arc<Mutex<Vec<String>>>,
+    span: Arc<Mutex<Option<&'static Metadata<'static>>>>,
+    entered: Arc<Mutex<bool>>,
+}
+
+/// Collects the names of the visited fields, with the value of the ones holding a float.
+struct Fields(Vec<String>);
+
+impl Visit for Fields {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0
+            .push(format!("{}: f64 >= 0: {}", field.name(), value >= 0.0));
+    }
+
+    fn record_debug(&mut self, field: &Field, _: &dyn std::fmt::Debug) {
+        self.0.push(field.name().to_owned());
+    }
+}
+
+impl Subscriber for Recorded {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        let fields: Vec<_> = span.metadata().fields().iter().map(|f| f.name()).collect();
+        let shape = format!("span {} [{}]", span.metadata().name(), fields.join(", "));
+        self.emitted.lock().unwrap().push(shape);
+        *self.span.lock().unwrap() = Some(span.metadata());
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, values: &span::Record<'_>) {
+        let mut fields = Fields(Vec::new());
+        values.record(&mut fields);
+        let shape = format!("record [{}]", fields.0.join(", "));
+        self.emitted.lock().unwrap().push(shape);
+    }
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields(Vec::new());
+        event.record(&mut fields);
+        let shape = format!("event [{}]", fields.0.join(", "));
+        self.emitted.lock().unwrap().push(shape);
+    }
+
+    fn enter(&self, _: &span::Id) {
+        *self.entered.lock().unwrap() = true;
+    }
+
+    fn exit(&self, _: &span::Id) {
+        *self.entered.lock().unwrap() = false;
+    }
+
+    fn current_span(&self) -> tracing_core::span::Current {
+        match *self.span.lock().unwrap() {
+            Some(metadata) if *self.entered.lock().unwrap() => {
+                tracing_core::span::Current::new(span::Id::from_u64(1), metadata)
+            }
+            _ => tracing_core::span::Current::none(),
+        }
+    }
+}
+
+/// Runs `f` with a [`Recorded`] subscriber, returning what was emitted.
+fn capture_recorded(f: impl FnOnce()) -> Vec<String> {
+    let subscriber = Recorded::default();
+    tracing::subscriber::with_default(subscriber.clone(), f);
+    subscriber.emitted.lock().unwrap().clone()
+}
+
+#[time_it(record = "elapsed_ms")]
+#[tracing::instrument(skip_all, fields(request = id, elapsed_ms = tracing::field::Empty))]
+fn handle(id: u32, _payload: &[u8]) {}
+
+#[time_it(in_span)]
+#[tracing::instrument(fields(duration_ms = tracing::field::Empty))]
+fn default_field() {}
+
+#[time_it(record = "elapsed_ms")]
+#[tracing::instrument]
+fn undeclared() {}
+
+#[test]
+fn the_duration_is_recorded_into_the_instrumented_span() {
+    assert_eq!(
+        capture_recorded(|| handle(7, b"body")),
+        [
+            "span handle [request, elapsed_ms]",
+            "record [elapsed_ms: f64 >= 0: true]"
+        ]
+    );
+}
+
+#[test]
+fn in_span_records_duration_ms() {
+    assert_eq!(
+        capture_recorded(default_field),
+        [
+            "span default_field [duration_ms]",
+            "record [duration_ms: f64 >= 0: true]"
+        ]
+    );
+}
+
+#[test]
+fn undeclared_fields_are_not_recorded() {
+    assert_eq!(capture_recorded(undeclared), ["span undeclared []"]);
+}
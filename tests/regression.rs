@@ -0,0 +1,108 @@
+//! `regression` reports calls taking more than a factor times the fastest call so far at WARN,
+//! once the first calls have recorded the baseline.
+
+mod common;
+
+use std::{cell::Cell, time::Duration};
+
+use time_it::time_it;
+use tracing::Level;
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+/// Advances the fake clock by `millis`.
+fn advance(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+#[time_it("info", regression = 3.0, clock_fn = "FakeInstant::now")]
+fn query(millis: u64) {
+    advance(millis);
+}
+
+#[time_it("error", regression = 2, clock_fn = "FakeInstant::now")]
+fn critical(millis: u64) {
+    advance(millis);
+}
+
+#[time_it("level=info;regression=1.5;clock_fn=FakeInstant::now")]
+fn packed(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(regression = 2, clock_fn = "FakeInstant::now")]
+fn reported(millis: u64) {
+    advance(millis);
+}
+
+#[test]
+fn calls_slower_than_the_factor_are_reported_at_warn() {
+    let ((), levels) = common::capture_levels(|| {
+        // The warm-up only records the baseline, however slow the calls are.
+        query(100);
+        for _ in 0..9 {
+            query(10);
+        }
+        query(30);
+        query(31);
+        query(5);
+        // The baseline is now 5ms.
+        query(16);
+    });
+    let mut expected = vec![Level::INFO; 11];
+    expected.extend([Level::WARN, Level::INFO, Level::WARN]);
+    assert_eq!(levels, expected);
+}
+
+#[test]
+fn regressions_are_reported_at_warn_whatever_the_level() {
+    let ((), levels) = common::capture_levels(|| {
+        for _ in 0..10 {
+            critical(10);
+        }
+        critical(21);
+        critical(20);
+    });
+    let mut expected = vec![Level::ERROR; 10];
+    expected.extend([Level::WARN, Level::ERROR]);
+    assert_eq!(levels, expected);
+}
+
+#[test]
+fn factors_can_be_packed() {
+    let ((), levels) = common::capture_levels(|| {
+        for _ in 0..10 {
+            packed(10);
+        }
+        packed(16);
+    });
+    assert_eq!(levels.last(), Some(&Level::WARN));
+}
+
+#[test]
+fn regressed_calls_keep_their_message() {
+    let ((), events) = common::capture(|| {
+        for _ in 0..10 {
+            reported(10);
+        }
+        reported(40);
+    });
+    assert_eq!(events.len(), 11);
+    assert!(events[10].starts_with("message=[reported]: Execution time: 40ms"));
+}
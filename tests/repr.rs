@@ -0,0 +1,75 @@
+//! `repr` selects how the duration is rendered in the message.
+
+mod common;
+
+use std::{cell::Cell, time::Duration};
+
+use time_it::{time_block, time_it};
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+fn advance() {
+    NOW.with(|now| now.set(now.get() + Duration::from_micros(1_234_567)));
+}
+
+#[time_it(repr = "debug", clock_fn = "FakeInstant::now")]
+fn debug() {
+    advance();
+}
+
+#[time_it(repr = "secs_f64", clock_fn = "FakeInstant::now")]
+fn secs_f64() {
+    advance();
+}
+
+#[time_it(repr = "nanos", clock_fn = "FakeInstant::now")]
+fn nanos() {
+    advance();
+}
+
+#[time_it(repr = "human", clock_fn = "FakeInstant::now")]
+fn human() {
+    advance();
+}
+
+/// The duration rendered in the single event emitted by `f`.
+fn rendered(f: impl FnOnce()) -> String {
+    let ((), events) = common::capture(f);
+    assert_eq!(events.len(), 1);
+    let (_, duration) = events[0].split_once("Execution time: ").unwrap();
+    duration.trim_end().to_owned()
+}
+
+#[test]
+fn every_repr_renders_the_duration() {
+    assert_eq!(rendered(debug), "1.234567s");
+    assert_eq!(rendered(secs_f64), "1.234567s");
+    assert_eq!(rendered(nanos), "1234567000ns");
+    assert_eq!(rendered(human), "1.23s");
+}
+
+#[test]
+fn blocks_accept_a_repr() {
+    let rendered = rendered(|| {
+        time_block!("block", repr = "nanos", clock_fn = "FakeInstant::now", {
+            advance();
+        })
+    });
+    assert_eq!(rendered, "1234567000ns");
+}
@@ -0,0 +1,81 @@
+//! Functions whose return type depends on inference keep compiling once annotated.
+
+use std::{
+    fmt::{Debug, Display},
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+/// Polls a future that never suspends to completion.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future suspended"),
+    }
+}
+
+#[time_it]
+fn make_fn() -> impl Fn() -> u32 {
+    || 1
+}
+
+#[time_it]
+fn make_iter() -> impl Iterator<Item = u32> {
+    (0..3).map(|x| x * 2)
+}
+
+#[time_it(log_result)]
+fn make_debug_iter() -> impl Iterator<Item = u32> + Debug {
+    0..3
+}
+
+#[time_it(err_level = "warn")]
+fn make_display() -> Result<impl Display, ()> {
+    Ok(3)
+}
+
+#[time_it(err_level = "warn")]
+fn make_boxed() -> Result<Box<dyn Fn() -> u32>, String> {
+    let value: u32 = "3".parse().map_err(|_| "not a number")?;
+    Ok(Box::new(move || value))
+}
+
+#[time_it]
+async fn make_display_async() -> impl Display {
+    5
+}
+
+#[time_it(log_result)]
+async fn make_debug_async() -> impl Debug {
+    5
+}
+
+#[time_it(err_level = "warn")]
+async fn make_boxed_async() -> Result<Box<dyn Fn() -> u32>, String> {
+    let value: u32 = "3".parse().map_err(|_| "not a number")?;
+    Ok(Box::new(move || value))
+}
+
+#[test]
+fn impl_trait_returns() {
+    assert_eq!(make_fn()(), 1);
+    assert_eq!(make_iter().collect::<Vec<_>>(), [0, 2, 4]);
+    assert_eq!(make_debug_iter().sum::<u32>(), 3);
+    assert_eq!(make_display().unwrap().to_string(), "3");
+}
+
+#[test]
+fn impl_trait_returns_async() {
+    assert_eq!(block_on(make_display_async()).to_string(), "5");
+    assert_eq!(format!("{:?}", block_on(make_debug_async())), "5");
+}
+
+#[test]
+fn coerced_returns() {
+    assert_eq!(make_boxed().unwrap()(), 3);
+    assert_eq!(block_on(make_boxed_async()).unwrap()(), 3);
+}
@@ -0,0 +1,76 @@
+//! `ring` keeps the most recent durations in a ring buffer instead of emitting events.
+#![cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+
+mod common;
+
+use std::{cell::Cell, time::Duration};
+
+use time_it::time_it;
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+#[time_it(ring = 3, clock_fn = "FakeInstant::now")]
+fn recorded(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+#[time_it(ring = 4, threshold = "5ms", clock_fn = "FakeInstant::now")]
+fn slow_only(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+#[time_it(ring = 2, aggregate)]
+fn counted() {}
+
+#[test]
+fn the_last_durations_are_retained() {
+    let ((), events) = common::capture(|| {
+        recorded(1);
+        assert_eq!(recorded_recent(), [Duration::from_millis(1)]);
+        for millis in 2..=5 {
+            recorded(millis);
+        }
+    });
+    assert!(events.is_empty());
+    assert_eq!(
+        recorded_recent(),
+        [3, 4, 5].map(Duration::from_millis),
+        "the oldest durations are overwritten"
+    );
+}
+
+#[test]
+fn filters_apply_before_recording() {
+    let ((), events) = common::capture(|| {
+        for millis in [1, 10, 2, 20] {
+            slow_only(millis);
+        }
+    });
+    assert!(events.is_empty());
+    assert_eq!(slow_only_recent(), [10, 20].map(Duration::from_millis));
+}
+
+#[test]
+fn aggregates_are_kept_next_to_the_ring() {
+    for _ in 0..3 {
+        counted();
+    }
+    assert_eq!(counted_recent().len(), 2);
+    assert_eq!(counted_stats().0, 3);
+}
@@ -0,0 +1,134 @@
+//! `runtime` adds the flavor of the tokio runtime a future completes on to its timing.
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use std::{
+    cell::Cell,
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+use time_it::time_it;
+
+// The expansion refers to `::tokio`, which this crate stands in for.
+extern crate self as tokio;
+
+/// Stands in for `tokio::runtime`, with the runtime of the current thread set by `run_on`.
+pub mod runtime {
+    use std::cell::Cell;
+
+    thread_local! {
+        pub(crate) static CURRENT: Cell<Option<RuntimeFlavor>> = const { Cell::new(None) };
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    #[allow(dead_code)]
+    pub enum RuntimeFlavor {
+        CurrentThread,
+        MultiThread,
+        MultiThreadAlt,
+    }
+
+    #[derive(Debug)]
+    pub struct TryCurrentError;
+
+    pub struct Handle(RuntimeFlavor);
+
+    impl Handle {
+        pub fn try_current() -> Result<Self, TryCurrentError> {
+            CURRENT.with(Cell::get).map(Self).ok_or(TryCurrentError)
+        }
+
+        pub fn runtime_flavor(&self) -> RuntimeFlavor {
+            self.0
+        }
+    }
+}
+
+/// Polls `future` to completion on a worker thread of a runtime of the given `flavor`, as
+/// `tokio::spawn` would, returning its output and the events emitted on the thread.
+fn run_on<F>(flavor: runtime::RuntimeFlavor, future: F) -> (F::Output, Vec<String>)
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    thread::Builder::new()
+        .name("tokio-runtime-worker".to_owned())
+        .spawn(move || {
+            runtime::CURRENT.with(|current| current.set(Some(flavor)));
+            common::capture(|| block_on(future))
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+/// Polls a future yielding at most once to completion.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            return output;
+        }
+    }
+}
+
+#[time_it(runtime)]
+async fn handle() {}
+
+#[time_it(runtime, structured)]
+async fn structured() {}
+
+#[time_it(runtime, future)]
+fn spawned() -> impl Future<Output = u32> {
+    async { 1 }
+}
+
+#[test]
+fn events_name_the_runtime_flavor() {
+    let ((), events) = run_on(runtime::RuntimeFlavor::MultiThread, handle());
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[handle]: Execution time: "));
+    assert!(
+        events[0].ends_with(", runtime: tokio_multi_thread "),
+        "{}",
+        events[0]
+    );
+}
+
+#[test]
+fn structured_events_have_a_runtime_field() {
+    let ((), events) = run_on(runtime::RuntimeFlavor::CurrentThread, structured());
+    assert_eq!(events.len(), 1);
+    assert!(
+        events[0].contains(" runtime=\"tokio_current_thread\""),
+        "{}",
+        events[0]
+    );
+}
+
+#[test]
+fn futures_are_tagged_with_the_runtime_completing_them() {
+    let (value, events) = run_on(runtime::RuntimeFlavor::MultiThread, spawned());
+    assert_eq!(value, 1);
+    assert!(
+        events[0].ends_with(", runtime: tokio_multi_thread "),
+        "{}",
+        events[0]
+    );
+    let (_, events) = run_on(runtime::RuntimeFlavor::MultiThreadAlt, spawned());
+    assert!(events[0].ends_with(", runtime: tokio "), "{}", events[0]);
+}
+
+#[test]
+fn calls_outside_of_a_runtime_are_unknown() {
+    let ((), events) = common::capture(|| block_on(handle()));
+    assert!(events[0].ends_with(", runtime: unknown "), "{}", events[0]);
+    assert!(runtime::CURRENT.with(Cell::get).is_none());
+}
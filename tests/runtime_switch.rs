@@ -0,0 +1,27 @@
+//! `set_enabled` toggles a switch pausing the reporting of every timed function.
+#![cfg(feature = "runtime_switch")]
+
+mod common;
+
+use time_it::{time_block, time_it};
+
+#[time_it]
+fn switched() {}
+
+#[time_it(trace_entry)]
+fn switched_entry() {}
+
+#[test]
+fn paused_calls_are_not_reported() {
+    let ((), events) = common::capture(|| {
+        time_it::set_enabled(false);
+        assert!(!time_it::is_enabled());
+        switched();
+        switched_entry();
+        time_block!("block", {});
+        time_it::set_enabled(true);
+        switched();
+    });
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[switched]: Execution time: "));
+}
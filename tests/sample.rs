@@ -0,0 +1,19 @@
+//! `sample` reports the first call and then one in every given number of calls.
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it(sample = 10)]
+fn hot_path() {}
+
+#[test]
+fn one_call_in_every_interval_is_reported() {
+    let ((), events) = common::capture(|| {
+        for _ in 0..95 {
+            hot_path();
+        }
+    });
+    // Calls 0, 10, ..., 90.
+    assert_eq!(events.len(), 10);
+}
@@ -0,0 +1,60 @@
+//! `time_scope!` times a block at a given level, reporting it like `#[time_it]` reports a function.
+
+mod common;
+
+use time_it::{time_it, time_scope};
+use tracing::Level;
+
+#[time_it(info)]
+fn parsing(input: &str) -> usize {
+    input.split(',').count()
+}
+
+fn parsing_inline(input: &str) -> usize {
+    time_scope!("parsing", info, { input.split(',').count() })
+}
+
+fn checked(input: &str) -> Result<u32, std::num::ParseIntError> {
+    let value = time_scope!("checked", "warn", { input.parse::<u32>()? });
+    Ok(value * 2)
+}
+
+/// Removes the measured duration, which differs between runs, from `event`.
+fn without_duration(event: &str) -> &str {
+    event.split("Execution time: ").next().unwrap()
+}
+
+#[test]
+fn scopes_are_reported_like_functions() {
+    let ((), events) = common::capture(|| {
+        assert_eq!(parsing("a,b"), 2);
+        assert_eq!(parsing_inline("a,b"), 2);
+    });
+    assert_eq!(events.len(), 2);
+    assert_eq!(without_duration(&events[0]), without_duration(&events[1]));
+    assert!(events[1].starts_with("message=[parsing]: Execution time: "));
+
+    let ((), levels) = common::capture_levels(|| {
+        parsing("a");
+        parsing_inline("a");
+    });
+    assert_eq!(levels, [Level::INFO, Level::INFO]);
+}
+
+#[test]
+fn levels_can_be_quoted_or_constants() {
+    let ((), levels) = common::capture_levels(|| {
+        assert_eq!(checked("21"), Ok(42));
+        time_scope!("constant", Level::TRACE, {});
+    });
+    assert_eq!(levels, [Level::WARN, Level::TRACE]);
+}
+
+#[test]
+fn the_block_runs_in_place() {
+    let (result, events) = common::capture(|| checked("not a number"));
+    assert!(result.is_err());
+    // Leaving the block through `?` still reports it.
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[checked]: Execution time: "));
+}
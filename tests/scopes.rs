@@ -0,0 +1,142 @@
+//! `scope!` groups the timed calls made while a block runs, reporting their breakdown in a single
+//! event once it completes.
+#![cfg(all(feature = "scopes", not(feature = "no_std")))]
+
+mod common;
+
+use std::{cell::Cell, time::Duration};
+
+use time_it::{scope, time_block, time_it};
+use tracing::Level;
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+/// Advances the fake clock by `millis`.
+fn advance(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+#[time_it(clock_fn = "FakeInstant::now")]
+fn parse() {
+    advance(1);
+}
+
+#[time_it(clock_fn = "FakeInstant::now")]
+fn query(millis: u64) {
+    advance(millis);
+}
+
+/// Breakdown ending the event of a scope, leaving out the duration of the block, which is measured
+/// on the real clock.
+fn breakdown(event: &str) -> &str {
+    event.split_once(", breakdown: ").unwrap().1.trim_end()
+}
+
+#[test]
+fn scopes_report_the_breakdown_of_their_calls() {
+    let (value, events) = common::capture(|| {
+        scope!("request", {
+            parse();
+            query(3);
+            query(5);
+            7
+        })
+    });
+    assert_eq!(value, 7);
+    assert_eq!(events.len(), 4);
+    assert!(events[3].starts_with("message=[request]: Execution time: "));
+    assert_eq!(
+        breakdown(&events[3]),
+        "parse: 1ms (1 call), query: 8ms (2 calls)"
+    );
+}
+
+#[test]
+fn scopes_report_at_their_level() {
+    let ((), levels) = common::capture_levels(|| {
+        scope!("request", info, {
+            parse();
+        });
+    });
+    assert_eq!(levels, [Level::DEBUG, Level::INFO]);
+}
+
+#[test]
+fn empty_scopes_have_no_breakdown() {
+    let ((), events) = common::capture(|| scope!("request", {}));
+    assert_eq!(events.len(), 1);
+    assert_eq!(breakdown(&events[0]), "none");
+}
+
+#[test]
+fn nested_scopes_are_added_to_the_enclosing_scope() {
+    let ((), events) = common::capture(|| {
+        scope!("request", {
+            parse();
+            scope!("lookup", {
+                query(2);
+                query(2);
+            });
+            query(1);
+        });
+    });
+    assert_eq!(events.len(), 6);
+    assert!(events[3].starts_with("message=[lookup]: Execution time: "));
+    assert_eq!(breakdown(&events[3]), "query: 4ms (2 calls)");
+    let (outer, lookup) = breakdown(&events[5]).split_once(", lookup: ").unwrap();
+    assert_eq!(outer, "parse: 1ms (1 call)");
+    let (_, rest) = lookup.split_once(" (1 call), ").unwrap();
+    assert_eq!(rest, "query: 1ms (1 call)");
+}
+
+#[test]
+fn blocks_are_added_to_scopes() {
+    let ((), events) = common::capture(|| {
+        scope!("request", {
+            time_block!("block", clock_fn = "FakeInstant::now", {
+                advance(4);
+            });
+        });
+    });
+    assert_eq!(breakdown(&events[1]), "block: 4ms (1 call)");
+}
+
+#[test]
+fn calls_outside_scopes_are_not_recorded() {
+    assert!(!time_it::in_scope());
+    let ((), events) = common::capture(|| {
+        parse();
+        scope!("request", {
+            assert!(time_it::in_scope());
+        });
+    });
+    assert_eq!(breakdown(&events[1]), "none");
+    assert!(!time_it::in_scope());
+}
+
+#[test]
+fn panicking_blocks_close_their_scope() {
+    let result = std::panic::catch_unwind(|| {
+        scope!("request", {
+            panic!("failed");
+        })
+    });
+    assert!(result.is_err());
+    assert!(!time_it::in_scope());
+}
@@ -0,0 +1,33 @@
+//! `seq` numbers the calls of each function.
+
+mod common;
+
+use time_it::time_it;
+
+#[time_it(seq)]
+fn numbered() {}
+
+#[time_it(seq, structured)]
+fn numbered_structured() {}
+
+#[test]
+fn calls_are_numbered_in_order() {
+    let ((), events) = common::capture(|| {
+        numbered();
+        numbered();
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[0].ends_with(", seq: 0 "));
+    assert!(events[1].ends_with(", seq: 1 "));
+}
+
+#[test]
+fn structured_events_carry_a_seq_field() {
+    let ((), events) = common::capture(|| {
+        numbered_structured();
+        numbered_structured();
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains("seq=0 "));
+    assert!(events[1].contains("seq=1 "));
+}
@@ -0,0 +1,80 @@
+//! `sig_figs` rounds durations rendered with the human format to significant figures, whatever
+//! their magnitude.
+
+mod common;
+
+use std::{cell::Cell, time::Duration};
+
+use time_it::time_it;
+
+thread_local! {
+    /// Duration measured by every call on the fake clock.
+    static ELAPSED: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock, which always measures `ELAPSED`.
+struct FakeInstant;
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self
+    }
+
+    #[allow(clippy::unused_self)]
+    fn elapsed(&self) -> Duration {
+        ELAPSED.with(Cell::get)
+    }
+}
+
+#[time_it(format = "human", sig_figs = 3, clock_fn = "FakeInstant::now")]
+fn three() {}
+
+#[time_it(format = "human", sig_figs = 1, clock_fn = "FakeInstant::now")]
+fn one() {}
+
+#[time_it("format=human;sig_figs=5;clock_fn=FakeInstant::now")]
+fn five() {}
+
+/// The duration `timed` renders once the fake clock measures `nanos`.
+fn rendered(timed: fn(), nanos: u64) -> String {
+    ELAPSED.with(|elapsed| elapsed.set(Duration::from_nanos(nanos)));
+    let ((), events) = common::capture(timed);
+    let (_, duration) = events[0].split_once("Execution time: ").unwrap();
+    duration.trim_end().to_owned()
+}
+
+#[test]
+fn durations_are_rounded_to_significant_figures() {
+    assert_eq!(rendered(three, 1_234_567), "1.23ms");
+    assert_eq!(rendered(three, 123_456), "123µs");
+    assert_eq!(rendered(three, 12_345), "12.3µs");
+    assert_eq!(rendered(three, 1_234_567_890), "1.23s");
+    assert_eq!(rendered(three, 123_456_789_012), "123s");
+    assert_eq!(rendered(three, 1_555_000), "1.56ms");
+}
+
+#[test]
+fn rounding_up_moves_to_the_next_unit() {
+    assert_eq!(rendered(three, 999_600), "1.00ms");
+    assert_eq!(rendered(three, 999_999_999), "1.00s");
+    assert_eq!(rendered(one, 960), "1µs");
+}
+
+#[test]
+fn integer_digits_beyond_the_figures_are_rounded() {
+    assert_eq!(rendered(one, 456_000), "500µs");
+    assert_eq!(rendered(one, 34_000_000_000), "30s");
+}
+
+#[test]
+fn nanoseconds_have_no_decimals() {
+    assert_eq!(rendered(three, 5), "5ns");
+    assert_eq!(rendered(three, 0), "0ns");
+    assert_eq!(rendered(one, 87), "90ns");
+}
+
+#[test]
+fn figures_can_be_packed() {
+    assert_eq!(rendered(five, 1_234_567), "1.2346ms");
+    assert_eq!(rendered(five, 2_000_000_000), "2.0000s");
+}
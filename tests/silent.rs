@@ -0,0 +1,109 @@
+//! `silent` emits no event per call, leaving the measurements to statistics and callbacks.
+#![cfg(not(any(feature = "disabled", feature = "max_level_off")))]
+
+mod common;
+
+use std::{cell::Cell, cell::RefCell, time::Duration};
+
+use time_it::{time_block, time_it};
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+    /// Measurements passed to `measured` on the current thread.
+    static MEASURED: RefCell<Vec<(&'static str, Duration)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+/// Advances the fake clock by `millis`.
+fn advance(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+fn measured(name: &'static str, duration: Duration) {
+    MEASURED.with_borrow_mut(|measured| measured.push((name, duration)));
+}
+
+#[time_it(aggregate, silent, clock_fn = "FakeInstant::now")]
+fn counted(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(quantiles, silent, clock_fn = "FakeInstant::now")]
+fn distributed(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(quantiles, clock_fn = "FakeInstant::now")]
+fn logged(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(on_complete = "measured", silent, clock_fn = "FakeInstant::now")]
+fn handled(millis: u64) {
+    advance(millis);
+}
+
+#[test]
+fn statistics_are_kept_without_events() {
+    let ((), events) = common::capture(|| {
+        counted(10);
+        counted(20);
+        for millis in 1..=100 {
+            distributed(millis);
+        }
+    });
+    assert!(events.is_empty(), "{events:?}");
+    assert_eq!(counted_stats(), (2, Duration::from_millis(30)));
+    let (p50, _, p99) = distributed_quantiles().unwrap();
+    assert!(
+        p50 > Duration::from_millis(45) && p50 < Duration::from_millis(55),
+        "{p50:?}"
+    );
+    assert!(p99 > Duration::from_millis(95), "{p99:?}");
+}
+
+#[test]
+fn quantiles_emit_events_unless_silent() {
+    let ((), events) = common::capture(|| logged(5));
+    assert_eq!(events.len(), 1);
+    assert!(logged_quantiles().is_some());
+}
+
+#[test]
+fn callbacks_still_receive_every_measurement() {
+    let ((), events) = common::capture(|| {
+        handled(3);
+        time_block!(
+            "block",
+            on_complete = "measured",
+            silent,
+            clock_fn = "FakeInstant::now",
+            {
+                advance(4);
+            }
+        );
+    });
+    assert!(events.is_empty(), "{events:?}");
+    MEASURED.with_borrow(|measured| {
+        assert_eq!(
+            measured[..],
+            [
+                ("handled", Duration::from_millis(3)),
+                ("block", Duration::from_millis(4))
+            ]
+        );
+    });
+}
@@ -0,0 +1,53 @@
+//! `since_start` adds the time calls start at, relative to a baseline shared by the process.
+
+mod common;
+
+use std::{thread, time::Duration};
+
+use time_it::{time_block, time_it};
+
+#[time_it(since_start)]
+fn step() {
+    thread::sleep(Duration::from_millis(1));
+}
+
+#[time_it(since_start, structured)]
+fn structured_step() {
+    thread::sleep(Duration::from_millis(1));
+}
+
+/// Value of the float field `name` of `event`.
+fn field(event: &str, name: &str) -> f64 {
+    let prefix = format!("{name}=");
+    event
+        .split(' ')
+        .find_map(|field| field.strip_prefix(&prefix))
+        .unwrap_or_else(|| panic!("{event} has no `{name}` field"))
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn messages_include_the_start_time() {
+    let ((), events) = common::capture(|| {
+        step();
+        time_block!("block", since_start, {});
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains("[step]: Execution time: "));
+    for event in events {
+        assert!(event.contains(", t: "), "{event}");
+    }
+}
+
+#[test]
+fn start_times_increase_across_calls() {
+    let ((), events) = common::capture(|| {
+        structured_step();
+        structured_step();
+    });
+    assert_eq!(events.len(), 2);
+    let (first, second) = (field(&events[0], "t_ms"), field(&events[1], "t_ms"));
+    let duration = field(&events[0], "duration_ms");
+    assert!(second >= first + duration, "{first} then {second}");
+}
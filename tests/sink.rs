@@ -0,0 +1,47 @@
+//! `sink = "channel"` sends the name and duration to the channel of `time_it::channel` instead of
+//! emitting an event.
+#![cfg(not(feature = "no_std"))]
+
+mod common;
+
+use time_it::{time_block, time_it};
+
+#[time_it(sink = "channel")]
+fn sent(value: u32) -> u32 {
+    value + 1
+}
+
+#[time_it(threshold = "100s", sink = "channel")]
+fn fast() {}
+
+// The channel is global, so a single test drives it through every state in order.
+#[test]
+fn measurements_are_sent_without_blocking() {
+    // Dropped until the channel is created.
+    sent(0);
+    assert_eq!(time_it::channel::dropped(), 1);
+
+    let receiver = time_it::channel::init(2).unwrap();
+    assert!(time_it::channel::init(2).is_none());
+    assert!(time_it::channel::install(2, |_, _| {}).is_none());
+
+    let (value, events) = common::capture(|| {
+        fast();
+        sent(1) + time_block!("block", sink = "channel", { 1 })
+    });
+    assert_eq!(value, 3);
+    assert!(events.is_empty());
+    let names: Vec<_> = receiver.try_iter().map(|(name, _)| name).collect();
+    assert_eq!(names, ["sent", "block"]);
+
+    // Once full, new measurements are dropped rather than waiting for the receiver.
+    for value in 0..3 {
+        sent(value);
+    }
+    assert_eq!(time_it::channel::dropped(), 2);
+    assert_eq!(receiver.try_iter().count(), 2);
+
+    drop(receiver);
+    sent(0);
+    assert_eq!(time_it::channel::dropped(), 3);
+}
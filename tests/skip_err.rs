@@ -0,0 +1,52 @@
+//! `skip_err` leaves calls returning an `Err` unreported.
+
+mod common;
+
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use time_it::time_it;
+
+#[time_it(skip_err)]
+fn fallible(fail: bool) -> Result<u32, String> {
+    if fail { Err("failed".into()) } else { Ok(1) }
+}
+
+#[time_it(skip_err, structured, log_result)]
+async fn fetch(fail: bool) -> Result<u32, String> {
+    fallible(fail)
+}
+
+/// Polls a future that never suspends to completion.
+fn block_on<F: Future>(future: F) -> F::Output {
+    match pin!(future)
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => output,
+        Poll::Pending => unreachable!("the future never suspends"),
+    }
+}
+
+#[test]
+fn only_successes_are_reported() {
+    let (values, events) = common::capture(|| (fallible(false), fallible(true)));
+    assert_eq!(values, (Ok(1), Err("failed".into())));
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[fallible]: Execution time: "));
+}
+
+#[test]
+fn async_failures_are_skipped() {
+    let (value, events) = common::capture(|| block_on(fetch(true)));
+    assert!(value.is_err());
+    assert!(events.is_empty());
+
+    let (value, events) = common::capture(|| block_on(fetch(false)));
+    assert_eq!(value, Ok(1));
+    // The nested `fallible` call reports itself first, then `fetch` completes.
+    assert_eq!(events.len(), 2);
+    assert!(events[1].contains("function=\"fetch\""));
+    assert!(events[1].contains("result=\"Ok(1)\""));
+}
@@ -0,0 +1,161 @@
+//! `span` mode creates a span named after the function, which tools built on `tracing` such as
+//! `tokio-console` or `tracing-subscriber` layers show alongside the runtime's tasks.
+
+mod common;
+
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+use tracing::{
+    Event, Level, Metadata, Subscriber,
+    field::{Field, Visit},
+    span,
+};
+
+/// Metadata of a created span, and the fields recorded into it.
+#[derive(Debug)]
+struct Created {
+    name: &'static str,
+    target: &'static str,
+    level: Level,
+    declared: Vec<&'static str>,
+    recorded: Vec<&'static str>,
+    entered: usize,
+}
+
+/// Subscriber keeping the metadata of every span and counting how often each is entered.
+#[derive(Clone, Default)]
+struct Spans(Arc<Mutex<Vec<Created>>>);
+
+/// Names of the fields given a value.
+struct Names<'a>(&'a mut Vec<&'static str>);
+
+impl Visit for Names<'_> {
+    fn record_debug(&mut self, field: &Field, _: &dyn Debug) {
+        self.0.push(field.name());
+    }
+}
+
+impl Subscriber for Spans {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        let metadata = span.metadata();
+        let mut recorded = Vec::new();
+        span.record(&mut Names(&mut recorded));
+        let mut spans = self.0.lock().unwrap();
+        spans.push(Created {
+            name: metadata.name(),
+            target: metadata.target(),
+            level: *metadata.level(),
+            declared: metadata.fields().iter().map(|field| field.name()).collect(),
+            recorded,
+            entered: 0,
+        });
+        span::Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, id: &span::Id, values: &span::Record<'_>) {
+        let mut spans = self.0.lock().unwrap();
+        let index = usize::try_from(id.into_u64()).unwrap() - 1;
+        values.record(&mut Names(&mut spans[index].recorded));
+    }
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, id: &span::Id) {
+        let index = usize::try_from(id.into_u64()).unwrap() - 1;
+        self.0.lock().unwrap()[index].entered += 1;
+    }
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+/// Runs `f` with a [`Spans`] as the default subscriber, returning the spans it created.
+fn capture_spans(f: impl FnOnce()) -> Vec<Created> {
+    let spans = Spans::default();
+    tracing::subscriber::with_default(spans.clone(), f);
+    std::mem::take(&mut *spans.0.lock().unwrap())
+}
+
+/// A future that is pending on its first poll, waking itself so it's polled again.
+async fn yield_once() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
+/// Polls `future` to completion.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            return output;
+        }
+    }
+}
+
+#[time_it("info", span)]
+fn handle_request(id: u32) -> u32 {
+    id
+}
+
+#[time_it(span, target = "timings", fields(component = "db"))]
+async fn load_user() {
+    yield_once().await;
+}
+
+#[test]
+fn spans_are_named_after_the_function() {
+    let spans = capture_spans(|| {
+        assert_eq!(handle_request(1), 1);
+    });
+    assert_eq!(spans.len(), 1);
+    let span = &spans[0];
+    assert_eq!(span.name, "handle_request");
+    assert_eq!(span.target, "time_it::measurement");
+    assert_eq!(span.level, Level::INFO);
+    assert!(span.declared.contains(&"duration_ms"), "{span:?}");
+    assert_eq!(span.recorded, ["duration_ms"]);
+    assert_eq!(span.entered, 1);
+}
+
+#[test]
+fn async_spans_are_entered_whenever_the_body_runs() {
+    let spans = capture_spans(|| block_on(load_user()));
+    assert_eq!(spans.len(), 1);
+    let span = &spans[0];
+    assert_eq!(span.name, "load_user");
+    assert_eq!(span.target, "timings");
+    assert_eq!(span.level, Level::DEBUG);
+    assert_eq!(span.recorded, ["component", "duration_ms"]);
+    // Once per poll, and once more to drop the completed body inside the span.
+    assert_eq!(span.entered, 3);
+}
+
+#[test]
+fn spans_emit_no_event() {
+    let (_, events) = common::capture(|| handle_request(2));
+    assert!(events.is_empty(), "{events:?}");
+}
@@ -0,0 +1,119 @@
+//! `split_await` reports the time until the future first suspends next to the total.
+
+mod common;
+
+use std::{
+    cell::Cell,
+    future::{Future, poll_fn},
+    pin::pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use time_it::time_it;
+
+thread_local! {
+    /// Time of the fake clock, only advanced by `advance`.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+fn advance(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+/// Future suspending once before completing.
+async fn yield_once() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
+/// Polls `future` until it completes.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            return output;
+        }
+    }
+}
+
+#[time_it(split_await, clock_fn = "FakeInstant::now")]
+async fn prepared(suspend: bool) {
+    advance(10);
+    if suspend {
+        yield_once().await;
+        advance(5);
+        yield_once().await;
+        advance(5);
+    }
+}
+
+#[time_it(split_await, structured, clock_fn = "FakeInstant::now")]
+async fn prepared_structured() {
+    advance(10);
+    yield_once().await;
+    advance(5);
+}
+
+#[time_it(split_await, future, clock_fn = "FakeInstant::now")]
+fn prepared_future() -> impl Future<Output = ()> {
+    async {
+        advance(3);
+        yield_once().await;
+        advance(4);
+    }
+}
+
+#[test]
+fn the_first_suspension_splits_the_measurement() {
+    let ((), events) = common::capture(|| block_on(prepared(true)));
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[prepared]: Execution time: 20ms"));
+    // Later suspensions don't move the split.
+    assert!(events[0].ends_with(", sync_prep: 10ms, total: 20ms "));
+}
+
+#[test]
+fn futures_completing_right_away_are_all_preparation() {
+    let ((), events) = common::capture(|| block_on(prepared(false)));
+    assert!(events[0].ends_with(", sync_prep: 10ms, total: 10ms "));
+}
+
+#[test]
+fn the_split_is_a_field_in_structured_mode() {
+    let ((), events) = common::capture(|| block_on(prepared_structured()));
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains(" duration_ms=15.0 "));
+    assert!(events[0].contains(" sync_prep_ms=10.0 "));
+}
+
+#[test]
+fn returned_futures_are_split() {
+    let ((), events) = common::capture(|| block_on(prepared_future()));
+    assert!(events[0].ends_with(", sync_prep: 3ms, total: 7ms "));
+}
@@ -0,0 +1,79 @@
+//! `summarize` reports the calls below the `threshold` together, instead of one by one.
+
+mod common;
+
+use std::{cell::Cell, time::Duration};
+
+use time_it::time_it;
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+fn advance(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+#[time_it(threshold = "5ms", summarize = 3, clock_fn = "FakeInstant::now")]
+fn handle(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(threshold = "5ms", summarize = 2, structured, clock_fn = "FakeInstant::now")]
+fn handle_structured(millis: u64) {
+    advance(millis);
+}
+
+#[time_it(threshold = "5ms", summarize)]
+fn handle_default() {}
+
+#[test]
+fn fast_calls_are_summarized_and_slow_ones_reported() {
+    let ((), events) = common::capture(|| {
+        for millis in [1, 2, 10, 3, 1, 20, 1] {
+            handle(millis);
+        }
+    });
+    assert_eq!(events.len(), 3);
+    assert!(events[0].starts_with("message=[handle]: Execution time: 10ms"));
+    // The third fast call completes the first summary, the last one is still pending.
+    assert_eq!(events[1], "message=[handle]: 3 fast calls totaling 6ms ");
+    assert!(events[2].starts_with("message=[handle]: Execution time: 20ms"));
+}
+
+#[test]
+fn summaries_are_structured_in_structured_mode() {
+    let ((), events) = common::capture(|| {
+        handle_structured(1);
+        handle_structured(2);
+    });
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=fast calls summarized "));
+    assert!(events[0].contains(" fast_calls=2 "));
+    assert!(events[0].contains(" total_ms=3.0 "));
+}
+
+#[test]
+fn summaries_default_to_a_hundred_calls() {
+    let ((), events) = common::capture(|| {
+        for _ in 0..199 {
+            handle_default();
+        }
+    });
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[handle_default]: 100 fast calls totaling "));
+}
@@ -0,0 +1,81 @@
+//! Events and spans are given the `time_it::measurement` target unless `target` overrides it, so a
+//! filter can select exactly the timing events.
+
+use std::sync::{Arc, Mutex};
+
+use time_it::{time_block, time_it};
+use tracing::{Event, Level, Metadata, Subscriber, span};
+
+/// Subscriber enabling what `EnvFilter::new("time_it::measurement=info")` enables, and keeping the
+/// target of every event and span it's given.
+#[derive(Clone, Default)]
+struct Filtered(Arc<Mutex<Vec<&'static str>>>);
+
+impl Filtered {
+    fn targets(&self) -> Vec<&'static str> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl Subscriber for Filtered {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "time_it::measurement" && *metadata.level() <= Level::INFO
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        self.0.lock().unwrap().push(span.metadata().target());
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        self.0.lock().unwrap().push(event.metadata().target());
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[time_it(info)]
+fn measured() {
+    tracing::info!("not a measurement");
+}
+
+#[time_it(info, structured, trace_entry)]
+fn structured() {}
+
+#[time_it(info, span)]
+fn in_a_span() {}
+
+#[time_it(info, target = "timings::db")]
+fn retargeted() {}
+
+#[time_it(debug)]
+fn below_the_filter() {}
+
+#[test]
+fn filters_select_exactly_the_measurements() {
+    let filtered = Filtered::default();
+    tracing::subscriber::with_default(filtered.clone(), || {
+        measured();
+        structured();
+        in_a_span();
+        retargeted();
+        below_the_filter();
+        time_block!("block", info, {});
+    });
+    // The event of `measured`, the entry and completion events of `structured`, the span of
+    // `in_a_span` and the event of the block.
+    assert_eq!(filtered.targets(), ["time_it::measurement"; 5]);
+}
+
+#[test]
+fn target_overrides_the_default() {
+    let filtered = Filtered::default();
+    tracing::subscriber::with_default(filtered.clone(), retargeted);
+    assert!(filtered.targets().is_empty());
+}
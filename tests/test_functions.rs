@@ -0,0 +1,80 @@
+//! Test functions timed by `#[time_it]` placed above or below `#[test]`, which still register
+//! with the test harness and report each run once.
+
+mod common;
+
+use std::{
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+#[cfg(test)]
+mod timed_tests {
+    use time_it::time_it;
+
+    #[time_it]
+    #[test]
+    pub(super) fn time_it_above_test() {
+        assert_eq!(1 + 1, 2);
+    }
+
+    #[test]
+    #[time_it]
+    pub(super) fn time_it_below_test() {
+        assert_eq!(2 + 2, 4);
+    }
+
+    #[time_it]
+    #[test]
+    pub(super) fn returning_result() -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Drives `future` to completion on the current thread, as a runtime's test attribute does.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            return output;
+        }
+    }
+}
+
+#[time_it]
+async fn async_work() -> u32 {
+    1
+}
+
+// What `#[tokio::test]` above `#[time_it]` expands to: a synchronous test running the future.
+#[test]
+#[time_it]
+fn async_test() {
+    assert_eq!(block_on(async_work()), 1);
+}
+
+#[test]
+fn each_test_run_reports_once() {
+    let ((), events) = common::capture(|| {
+        timed_tests::time_it_above_test();
+        timed_tests::time_it_below_test();
+        timed_tests::returning_result().unwrap();
+    });
+    assert_eq!(events.len(), 3);
+    assert!(events[0].starts_with("message=[time_it_above_test]: Execution time: "));
+    assert!(events[1].starts_with("message=[time_it_below_test]: Execution time: "));
+    assert!(events[2].starts_with("message=[returning_result]: Execution time: "));
+}
+
+#[test]
+fn async_tests_report_the_future_and_the_test() {
+    let ((), events) = common::capture(async_test);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[async_work]: Execution time: "));
+    assert!(events[1].starts_with("message=[async_test]: Execution time: "));
+}
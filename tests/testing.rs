@@ -0,0 +1,101 @@
+//! `testing::capture` sets a subscriber capturing the timing events emitted by timed code, for
+//! assertions in tests.
+#![cfg(all(feature = "testing", not(feature = "no_std")))]
+
+use time_it::testing::capture;
+use time_it::{time_block, time_it};
+use tracing::Level;
+
+#[time_it]
+fn handle() {}
+
+#[time_it("info", label = "handler")]
+fn labeled() {}
+
+#[time_it(structured, fields(component = "db"))]
+fn query() {}
+
+#[time_it(structured, name_field = "operation", fields(component = "cache"))]
+fn renamed() {}
+
+#[time_it(
+    structured,
+    threshold = "100s",
+    summarize = 2,
+    name_field = "operation"
+)]
+fn summarized() {}
+
+#[time_it(span)]
+fn spanned() {}
+
+#[test]
+fn timed_calls_are_captured_by_name() {
+    let capture = capture();
+    handle();
+    handle();
+    labeled();
+    let timings = capture.timings();
+    assert_eq!(timings.len(), 3);
+    assert_eq!(capture.named("handle").len(), 2);
+    let labeled = &capture.named("handler")[0];
+    assert_eq!(labeled.level, Level::INFO);
+    assert!(labeled.message.starts_with("[handler]: Execution time: "));
+    assert_eq!(labeled.duration_ms, None);
+}
+
+#[test]
+fn structured_events_carry_their_duration_and_fields() {
+    let capture = capture();
+    query();
+    let timings = capture.named("query");
+    assert_eq!(timings.len(), 1);
+    assert_eq!(timings[0].message, "execution timed");
+    assert!(
+        timings[0]
+            .duration_ms
+            .is_some_and(|duration| duration >= 0.0)
+    );
+    assert_eq!(timings[0].field("component"), Some("db"));
+    assert_eq!(timings[0].field("function"), None);
+}
+
+#[test]
+fn renamed_name_fields_are_read_as_the_name() {
+    let capture = capture();
+    renamed();
+    summarized();
+    summarized();
+    let renamed = capture.named("renamed");
+    assert_eq!(renamed.len(), 1);
+    assert_eq!(renamed[0].field("component"), Some("cache"));
+    assert_eq!(renamed[0].field("operation"), None);
+    let summarized = capture.named("summarized");
+    assert_eq!(summarized.len(), 1);
+    assert_eq!(summarized[0].message, "fast calls summarized");
+}
+
+#[test]
+fn other_events_and_spans_are_left_out() {
+    let capture = capture();
+    tracing::info!("unrelated");
+    tracing::info!(count = 3, "[not a timing");
+    spanned();
+    time_block!("block", {});
+    let timings = capture.timings();
+    assert_eq!(timings.len(), 1);
+    assert_eq!(timings[0].name, "block");
+}
+
+#[test]
+fn events_are_only_captured_while_the_capture_is_alive() {
+    handle();
+    let capture = capture();
+    {
+        let nested = time_it::testing::capture();
+        handle();
+        assert_eq!(nested.timings().len(), 1);
+    }
+    handle();
+    assert_eq!(capture.timings().len(), 1);
+}
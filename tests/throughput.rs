@@ -0,0 +1,154 @@
+//! `bytes_arg` and `bytes_ret` report the throughput of each call, from the number of bytes it
+//! processes and the measured duration.
+
+mod common;
+
+use std::{
+    cell::Cell,
+    io,
+    pin::pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use time_it::time_it;
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+/// Advances the fake clock by `millis`.
+fn advance(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+#[time_it(bytes_arg = "len", clock_fn = "FakeInstant::now")]
+fn copy(len: usize, millis: u64) {
+    advance(millis);
+}
+
+#[time_it(bytes_arg = "delta", clock_fn = "FakeInstant::now")]
+fn seek(delta: i64) {
+    advance(1);
+}
+
+#[time_it(bytes_ret, clock_fn = "FakeInstant::now")]
+fn fill(buf: &mut [u8]) -> u64 {
+    buf.fill(1);
+    advance(4);
+    buf.len() as u64
+}
+
+#[time_it(bytes_ret, clock_fn = "FakeInstant::now")]
+fn read(len: usize, fail: bool) -> io::Result<usize> {
+    advance(2);
+    if fail {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(len)
+}
+
+#[time_it(bytes_ret, clock_fn = "FakeInstant::now")]
+async fn download(len: u32) -> u32 {
+    advance(8);
+    len
+}
+
+#[time_it(bytes_arg = "len", structured, clock_fn = "FakeInstant::now")]
+fn structured(len: u32) {
+    advance(5);
+}
+
+#[time_it("bytes_arg=len;clock_fn=FakeInstant::now")]
+fn packed(len: u16) {
+    advance(1);
+}
+
+/// The throughput in the message of `event`.
+fn throughput(event: &str) -> &str {
+    let (_, throughput) = event.split_once(", throughput: ").unwrap();
+    throughput.trim_end()
+}
+
+#[test]
+fn the_rate_is_computed_from_the_argument() {
+    let ((), events) = common::capture(|| {
+        copy(5_000_000, 10);
+        copy(1_500, 3);
+    });
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=[copy]: Execution time: 10ms, throughput: "));
+    assert_eq!(throughput(&events[0]), "500.00 MB/s");
+    assert_eq!(throughput(&events[1]), "0.50 MB/s");
+}
+
+#[test]
+fn zero_durations_leave_the_rate_unknown() {
+    let ((), events) = common::capture(|| copy(1_000, 0));
+    assert_eq!(throughput(&events[0]), "unknown");
+}
+
+#[test]
+fn negative_amounts_leave_the_rate_unknown() {
+    let ((), events) = common::capture(|| seek(-4_096));
+    assert_eq!(throughput(&events[0]), "unknown");
+}
+
+#[test]
+fn the_rate_is_computed_from_the_returned_value() {
+    let mut buf = [0; 8_000];
+    let (bytes, events) = common::capture(|| fill(&mut buf));
+    assert_eq!(bytes, 8_000);
+    assert_eq!(throughput(&events[0]), "2.00 MB/s");
+}
+
+#[test]
+fn errors_leave_the_rate_unknown() {
+    let (results, events) = common::capture(|| [read(3_000_000, false), read(3_000_000, true)]);
+    assert_eq!(results[0].as_ref().unwrap(), &3_000_000);
+    assert!(results[1].is_err());
+    assert_eq!(throughput(&events[0]), "1500.00 MB/s");
+    assert_eq!(throughput(&events[1]), "unknown");
+}
+
+#[test]
+fn futures_report_the_rate_once_complete() {
+    let (output, events) = common::capture(|| {
+        let mut future = pin!(download(4_000_000));
+        let mut cx = Context::from_waker(Waker::noop());
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => output,
+            Poll::Pending => unreachable!("the download never waits"),
+        }
+    });
+    assert_eq!(output, 4_000_000);
+    assert_eq!(throughput(&events[0]), "500.00 MB/s");
+}
+
+#[test]
+fn structured_events_have_bytes_and_rate_fields() {
+    let ((), events) = common::capture(|| structured(250_000));
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains(" bytes=250000 "), "{}", events[0]);
+    assert!(events[0].contains(" throughput_mb_s=50.0"), "{}", events[0]);
+}
+
+#[test]
+fn the_argument_can_be_packed() {
+    let ((), events) = common::capture(|| packed(60_000));
+    assert_eq!(throughput(&events[0]), "60.00 MB/s");
+}
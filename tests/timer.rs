@@ -0,0 +1,82 @@
+//! `Timer` is a guard reporting the scope it lives in when dropped, at a level
+//! chosen at runtime.
+
+#![cfg(not(feature = "no_std"))]
+
+mod common;
+
+use std::{panic, thread, time::Duration};
+
+use time_it::{Timer, time_it};
+use tracing::Level;
+
+#[time_it("info")]
+fn attributed() {}
+
+#[test]
+fn dropping_the_timer_emits_an_event() {
+    let ((), events) = common::capture(|| {
+        let _timer = Timer::new("scope", Level::INFO);
+    });
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[scope]: Execution time: "));
+}
+
+#[test]
+fn timers_read_like_timed_functions() {
+    let ((), events) = common::capture(|| {
+        attributed();
+        let _timer = Timer::new("attributed", Level::INFO);
+    });
+    let shape = |event: &str| event.split(": ").take(2).collect::<Vec<_>>().join(": ");
+    assert_eq!(shape(&events[0]), shape(&events[1]));
+}
+
+#[test]
+fn the_level_is_chosen_at_runtime() {
+    let ((), levels) = common::capture_levels(|| {
+        for level in [
+            Level::ERROR,
+            Level::WARN,
+            Level::INFO,
+            Level::DEBUG,
+            Level::TRACE,
+        ] {
+            let _timer = Timer::new("leveled", level);
+        }
+    });
+    assert_eq!(
+        levels,
+        [
+            Level::ERROR,
+            Level::WARN,
+            Level::INFO,
+            Level::DEBUG,
+            Level::TRACE
+        ]
+    );
+}
+
+#[test]
+fn elapsed_reads_the_time_so_far() {
+    let ((), events) = common::capture(|| {
+        let timer = Timer::new("sleeping", Level::DEBUG);
+        thread::sleep(Duration::from_millis(5));
+        assert!(timer.elapsed() >= Duration::from_millis(5));
+    });
+    assert_eq!(events.len(), 1);
+    assert!(events[0].starts_with("message=[sleeping]: Execution time: "));
+}
+
+#[test]
+fn unwinding_scopes_are_marked_as_panicked() {
+    let (result, events) = common::capture(|| {
+        panic::catch_unwind(|| {
+            let _timer = Timer::new("unwinding", Level::DEBUG);
+            panic!("boom");
+        })
+    });
+    assert!(result.is_err());
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains(" (panicked)"));
+}
@@ -0,0 +1,40 @@
+//! `trace_entry` emits an event when the call starts, before the completion event.
+
+mod common;
+
+use time_it::{time_block, time_it};
+
+#[time_it(trace_entry)]
+fn traced() {
+    tracing::info!("working");
+}
+
+#[time_it(trace_entry, structured, seq)]
+fn traced_structured() {}
+
+#[test]
+fn start_and_completion_are_reported_in_order() {
+    let ((), events) = common::capture(traced);
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0], "message=[traced]: Started ");
+    assert_eq!(events[1], "message=working ");
+    assert!(events[2].starts_with("message=[traced]: Execution time: "));
+}
+
+#[test]
+fn structured_start_events_carry_the_sequence_number() {
+    let ((), events) = common::capture(traced_structured);
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        events[0],
+        "message=execution started function=\"traced_structured\" seq=0 "
+    );
+    assert!(events[1].starts_with("message=execution timed "));
+}
+
+#[test]
+fn blocks_report_their_start() {
+    let ((), events) = common::capture(|| time_block!("block", trace_entry, {}));
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0], "message=[block]: Started ");
+}
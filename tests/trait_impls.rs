@@ -0,0 +1,181 @@
+//! An impl level `#[time_it]` on a trait impl keeps every signature as written, so the methods
+//! still implement the trait, with its associated types, bounds and `where` clauses.
+
+mod common;
+
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use time_it::time_it;
+
+/// Polls a future that never suspends to completion.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future suspended"),
+    }
+}
+
+trait Repository {
+    type Item: Debug;
+    type Error: Debug;
+
+    fn new() -> Self
+    where
+        Self: Sized;
+
+    fn insert(&mut self, item: Self::Item) -> usize;
+
+    fn get(&self, index: usize) -> Result<&Self::Item, Self::Error>;
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut Self::Item>;
+
+    fn first_or<'a>(&'a self, fallback: &'a Self::Item) -> &'a Self::Item;
+
+    fn find<P>(&self, predicate: P) -> Option<&Self::Item>
+    where
+        P: Fn(&Self::Item) -> bool;
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Item>;
+
+    fn describe(&self, prefix: impl Debug) -> String;
+
+    fn fetch(&self, index: usize) -> impl Future<Output = Option<Self::Item>> + Send
+    where
+        Self::Item: Clone + Send + Sync;
+
+    fn count(&self) -> impl Future<Output = usize> + Send;
+}
+
+#[derive(Debug)]
+struct Names<T> {
+    items: Vec<T>,
+}
+
+#[time_it("info", log_result)]
+impl<T> Repository for Names<T>
+where
+    T: Debug + Send + Sync,
+{
+    type Item = T;
+    type Error = String;
+
+    fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    fn insert(&mut self, item: Self::Item) -> usize {
+        self.items.push(item);
+        self.items.len() - 1
+    }
+
+    #[time_it("info", log_result, err_level = "warn")]
+    fn get(&self, index: usize) -> Result<&Self::Item, Self::Error> {
+        self.items
+            .get(index)
+            .ok_or_else(|| format!("no item {index}"))
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut Self::Item> {
+        self.items.get_mut(index)
+    }
+
+    fn first_or<'a>(&'a self, fallback: &'a Self::Item) -> &'a Self::Item {
+        self.items.first().unwrap_or(fallback)
+    }
+
+    fn find<P>(&self, predicate: P) -> Option<&Self::Item>
+    where
+        P: Fn(&Self::Item) -> bool,
+    {
+        self.items.iter().find(|item| predicate(item))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Item> {
+        self.items.iter()
+    }
+
+    fn describe(&self, prefix: impl Debug) -> String {
+        format!("{prefix:?}: {} items", self.items.len())
+    }
+
+    #[time_it("info", future, poll_count)]
+    fn fetch(&self, index: usize) -> impl Future<Output = Option<Self::Item>> + Send
+    where
+        Self::Item: Clone + Send + Sync,
+    {
+        let item = self.items.get(index).cloned();
+        async move { item }
+    }
+
+    async fn count(&self) -> usize {
+        self.items.len()
+    }
+}
+
+/// Uses the implementation only through the trait, so it must satisfy it.
+fn fill<R: Repository<Item = &'static str>>() -> R {
+    let mut repository = R::new();
+    repository.insert("first");
+    repository.insert("second");
+    repository
+}
+
+/// Asserts that a returned future is `Send`, as the trait requires.
+fn assert_send<F: Future + Send>(future: F) -> F {
+    future
+}
+
+#[test]
+fn instrumented_methods_implement_the_trait() {
+    let (count, events) = common::capture(|| {
+        let mut names: Names<&str> = fill();
+        assert_eq!(names.get(1), Ok(&"second"));
+        assert!(names.get(2).is_err());
+        *names.get_mut(0).unwrap() = "renamed";
+        assert_eq!(names.first_or(&"fallback"), &"renamed");
+        assert_eq!(names.find(|name| name.starts_with('r')), Some(&"renamed"));
+        assert_eq!(names.iter().count(), 2);
+        assert_eq!(names.describe('n'), "'n': 2 items");
+        assert_eq!(block_on(assert_send(names.fetch(0))), Some("renamed"));
+        block_on(assert_send(names.count()))
+    });
+    assert_eq!(count, 2);
+    let names: Vec<_> = events
+        .iter()
+        .map(|event| {
+            let (name, _) = event.split_once("]: ").unwrap();
+            name.strip_prefix("message=[").unwrap()
+        })
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "new", "insert", "insert", "get", "get", "get_mut", "first_or", "find", "iter",
+            "describe", "fetch", "count"
+        ]
+    );
+    assert!(
+        events[3].contains("returned: Ok(\"second\")"),
+        "{}",
+        events[3]
+    );
+    assert!(events[10].contains("polls: 1"), "{}", events[10]);
+}
+
+#[test]
+fn failures_keep_the_trait_error_type() {
+    let (_, levels) = common::capture_levels(|| {
+        let names = Names::<u8>::new();
+        names.get(0).unwrap_err()
+    });
+    assert_eq!(levels, [tracing::Level::INFO, tracing::Level::WARN]);
+}
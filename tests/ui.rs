@@ -0,0 +1,8 @@
+//! Diagnostics reported for invalid uses of the macros, and expansions that must keep compiling.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+    t.pass("tests/ui/pass/*.rs");
+}
@@ -0,0 +1,15 @@
+use time_it::time_it;
+
+#[time_it(format = "aligned", width = 0)]
+fn zero() {}
+
+#[time_it(format = "aligned", width = 33)]
+fn too_wide() {}
+
+#[time_it(width = 9)]
+fn without_aligned() {}
+
+#[time_it(format = "human", width = 9)]
+fn with_human() {}
+
+fn main() {}
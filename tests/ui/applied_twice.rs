@@ -0,0 +1,15 @@
+use time_it::time_it;
+
+#[time_it]
+#[time_it("info")]
+fn twice() {}
+
+#[time_it("info")]
+#[time_it::time_it(threshold = "10ms")]
+fn with_path() {}
+
+#[time_it]
+#[time_it]
+fn bare() {}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use time_it::time_it;
+
+#[time_it(info, "warn")]
+fn work() {}
+
+fn main() {
+    work();
+}
@@ -0,0 +1,9 @@
+use time_it::time_it;
+
+#[time_it(both_clocks)]
+fn without_feature() {}
+
+#[time_it(both_clocks, clock = "cpu")]
+fn with_clock() {}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use time_it::time_it;
+
+#[time_it(bytes_arg = "size")]
+fn unknown(len: usize) {}
+
+#[time_it(bytes_arg = "name")]
+fn not_a_number(name: &str) {}
+
+#[time_it(bytes_ret)]
+fn returns_nothing() {}
+
+#[time_it(bytes_ret)]
+fn returns_text() -> String {
+    String::new()
+}
+
+#[time_it(bytes_arg = "len", bytes_ret)]
+fn both(len: usize) -> usize {
+    len
+}
+
+fn main() {}
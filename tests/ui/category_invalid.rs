@@ -0,0 +1,15 @@
+use time_it::time_it;
+
+#[time_it(category = network)]
+fn unquoted() {}
+
+#[time_it(category = "")]
+fn empty() {}
+
+#[time_it(category = "network", fields(category = "storage"))]
+fn duplicated() {}
+
+#[time_it(category = "network", output = "stdout")]
+fn printed() {}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use time_it::time_it;
+
+fn not_a_clock() -> u32 {
+    0
+}
+
+#[time_it(clock_fn = "not_a_clock")]
+fn mismatched() {}
+
+#[time_it(clock_fn = "std::time::Instant::now", clock = "wall")]
+fn both_clocks() {}
+
+fn main() {
+    mismatched();
+}
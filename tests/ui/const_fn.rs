@@ -0,0 +1,10 @@
+use time_it::time_it;
+
+#[time_it]
+const fn answer() -> u32 {
+    42
+}
+
+fn main() {
+    let _ = answer();
+}
@@ -0,0 +1,30 @@
+#![deny(deprecated)]
+
+use time_it::time_it;
+
+#[time_it]
+#[deprecated = "use `current` instead"]
+fn sync() {}
+
+#[time_it(log_result)]
+#[deprecated]
+fn wrapped() -> u32 {
+    1
+}
+
+#[time_it(inner_fn, log_result)]
+#[deprecated]
+fn nested() -> u32 {
+    1
+}
+
+#[time_it]
+#[deprecated]
+async fn asynchronous() {}
+
+fn main() {
+    sync();
+    wrapped();
+    nested();
+    drop(asynchronous());
+}
@@ -0,0 +1,9 @@
+use time_it::time_it;
+
+#[time_it(depth)]
+async fn asynchronous() {}
+
+#[time_it(top_only)]
+fn without_depth() {}
+
+fn main() {}
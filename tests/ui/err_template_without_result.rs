@@ -0,0 +1,10 @@
+use time_it::time_it;
+
+#[time_it(err_template = "{name} failed after {duration}")]
+fn infallible() -> u32 {
+    1
+}
+
+fn main() {
+    infallible();
+}
@@ -0,0 +1,6 @@
+use time_it::time_it;
+
+#[time_it(exclude_idle)]
+fn synchronous() {}
+
+fn main() {}
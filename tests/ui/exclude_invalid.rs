@@ -0,0 +1,14 @@
+use time_it::{time_block, time_it};
+
+time_it::exclusions!();
+
+#[time_it(exclude = "")]
+fn empty() {}
+
+#[time_it(exclude = "lock")]
+async fn asynchronous() {}
+
+fn main() {
+    time_block!("block", exclude = "lock", {});
+    time_it::exclude!("lock");
+}
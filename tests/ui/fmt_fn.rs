@@ -0,0 +1,18 @@
+use std::time::Duration;
+
+use time_it::time_it;
+
+fn wrong_signature(_duration: &Duration) -> String {
+    String::new()
+}
+
+#[time_it(fmt_fn = "missing_formatter")]
+fn unresolved() {}
+
+#[time_it(fmt_fn = "wrong_signature")]
+fn mismatched() {}
+
+fn main() {
+    unresolved();
+    mismatched();
+}
@@ -0,0 +1,12 @@
+use time_it::time_it;
+
+#[time_it(fmt_fn = "not a path")]
+fn invalid() {}
+
+#[time_it(fmt_fn = "format", unit = "ms")]
+fn with_unit() {}
+
+fn main() {
+    invalid();
+    with_unit();
+}
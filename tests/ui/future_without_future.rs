@@ -0,0 +1,11 @@
+use time_it::time_it;
+
+#[time_it(future)]
+fn not_a_future() -> u32 {
+    1
+}
+
+#[time_it(future)]
+async fn already_async() {}
+
+fn main() {}
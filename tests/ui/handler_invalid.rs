@@ -0,0 +1,12 @@
+use time_it::time_it;
+
+#[time_it(handler = |duration| println!("{duration:?}"))]
+fn one_argument() {}
+
+#[time_it(handler = "record")]
+fn not_a_closure() {}
+
+#[time_it(handler = |name: u32, _| println!("{name}"))]
+fn mismatching_types() {}
+
+fn main() {}
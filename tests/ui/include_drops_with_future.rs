@@ -0,0 +1,10 @@
+use time_it::time_it;
+
+#[time_it(include_drops, future)]
+fn work(name: String) -> impl Future<Output = usize> {
+    async move { name.len() }
+}
+
+fn main() {
+    let _ = work(String::new());
+}
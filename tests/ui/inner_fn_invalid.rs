@@ -0,0 +1,17 @@
+use time_it::time_it;
+
+struct Counter(u32);
+
+impl Counter {
+    #[time_it(inner_fn)]
+    fn get(&self) -> u32 {
+        self.0
+    }
+
+    #[time_it(inner_fn)]
+    fn new() -> Self {
+        Counter(0)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use time_it::time_it;
+
+#[time_it(threshold = "50 ms")]
+fn spaced() {}
+
+#[time_it(threshold = "50")]
+fn unitless() {}
+
+#[time_it(min_report = "ms")]
+fn amountless() {}
+
+#[time_it(tiers = "1.5ms:warn")]
+fn fractional() {}
+
+#[time_it(threshold = "307445734561825861m")]
+fn too_long() {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use time_it::time_it;
+
+#[time_it(fields(component = "db", component = "cache"))]
+fn duplicate() {}
+
+#[time_it(fields(initial = 'c'))]
+fn char_literal() {}
+
+#[time_it(fields = "component")]
+fn not_a_list() {}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use time_it::time_it;
+
+#[time_it("loud")]
+fn work() {}
+
+fn main() {
+    work();
+}
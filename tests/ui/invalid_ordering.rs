@@ -0,0 +1,9 @@
+use time_it::time_it;
+
+#[time_it(seq, ordering = "acquire")]
+fn acquire() {}
+
+#[time_it(ordering = "seqcst")]
+fn no_atomics() {}
+
+fn main() {}
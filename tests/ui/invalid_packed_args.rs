@@ -0,0 +1,20 @@
+use time_it::time_it;
+
+#[time_it("level=info;threshold=10xs")]
+fn invalid_duration() {}
+
+#[time_it("level=info;colour=red")]
+fn unknown_key() {}
+
+#[time_it("level=info;=ms")]
+fn missing_key() {}
+
+#[time_it("level=info", "level=warn")]
+fn duplicate() {}
+
+fn main() {
+    invalid_duration();
+    unknown_key();
+    missing_key();
+    duplicate();
+}
@@ -0,0 +1,22 @@
+use time_it::{time_block, time_it};
+
+#[time_it(label_arg = "operation")]
+fn missing(op: &str, value: u32) {}
+
+#[time_it(label_arg = "op")]
+fn destructured((op, value): (&str, u32)) {}
+
+#[time_it(label_arg = "op", label = "dispatch")]
+fn labeled(op: &str) {}
+
+#[time_it(label_arg = "op", span)]
+fn spanned(op: &str) {}
+
+fn record(_name: &'static str, _duration: std::time::Duration) {}
+
+#[time_it(label_arg = "op", on_complete = "record")]
+fn completed(op: &str) {}
+
+fn main() {
+    time_block!("block", label_arg = "op", {});
+}
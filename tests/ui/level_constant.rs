@@ -0,0 +1,16 @@
+use time_it::time_it;
+
+#[time_it(level = Level::LOUD)]
+fn unknown_constant() {}
+
+#[time_it(level = LogLevel::INFO)]
+fn unknown_type() {}
+
+#[time_it(label = Level::INFO)]
+fn not_a_level() {}
+
+fn main() {
+    unknown_constant();
+    unknown_type();
+    not_a_level();
+}
@@ -0,0 +1,13 @@
+use time_it::time_it;
+
+fn not_a_level() -> u8 {
+    0
+}
+
+#[time_it(level_fn = not_a_level)]
+fn wrong_return_type() {}
+
+#[time_it(level_fn = "not_a_level", level = "info")]
+fn with_level() {}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use time_it::time_it;
+
+struct Opaque;
+
+#[time_it(log_err_value)]
+fn without_err_level() -> Result<(), ()> {
+    Ok(())
+}
+
+#[time_it(err_level = "warn", log_err_value)]
+fn opaque_error() -> Result<(), Opaque> {
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use time_it::time_it;
+
+#[time_it(format = "human", min_unit = "minutes")]
+fn unknown() {}
+
+#[time_it(min_unit = "us")]
+fn without_human() {}
+
+#[time_it(format = "aligned", min_unit = "us")]
+fn aligned() {}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use time_it::time_it;
+
+#[time_it(mode = "trace")]
+fn unknown_mode() {}
+
+#[time_it(mode = "span", span)]
+fn mode_and_span() {}
+
+#[time_it(mode = "event", span)]
+fn conflicting() {}
+
+#[time_it(mode = "span", structured)]
+fn structured_span() {}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use time_it::time_it;
+
+#[time_it(label = "shapes")]
+mod shapes {
+    pub fn area() {}
+}
+
+fn main() {
+    shapes::area();
+}
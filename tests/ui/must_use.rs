@@ -0,0 +1,57 @@
+#![deny(unused_must_use)]
+
+use time_it::time_it;
+
+#[must_use]
+struct Token;
+
+#[time_it]
+#[must_use]
+fn annotated() -> u32 {
+    1
+}
+
+#[time_it(log_result)]
+#[must_use = "the sum is the point"]
+fn annotated_with_result(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[time_it]
+fn must_use_type() -> Token {
+    Token
+}
+
+#[time_it(err_level = "error")]
+fn fallible() -> Result<(), String> {
+    Ok(())
+}
+
+#[time_it(log_result)]
+#[must_use]
+async fn fetch() -> u32 {
+    1
+}
+
+async fn caller() {
+    fetch().await;
+}
+
+struct Counter;
+
+#[time_it]
+impl Counter {
+    #[must_use]
+    fn count(&self) -> u32 {
+        1
+    }
+}
+
+fn main() {
+    annotated();
+    annotated_with_result(1, 2);
+    must_use_type();
+    fallible();
+    Counter.count();
+    let _ = caller();
+}
@@ -0,0 +1,6 @@
+use time_it::time_it;
+
+#[time_it(label = "work", name = "work")]
+fn work() {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use time_it::time_it;
+
+#[time_it(structured, name_field = "")]
+fn empty() {}
+
+#[time_it(structured, name_field = "my-op")]
+fn not_an_identifier() {}
+
+#[time_it(name_field = "operation")]
+fn without_structured() {}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use time_it::time_it;
+
+#[time_it]
+static LIMIT: u32 = 10;
+
+#[time_it]
+struct Config {
+    limit: u32,
+}
+
+#[allow(unused_macros)]
+macro_rules! limit {
+    () => {
+        const fn limit() -> u32 {
+            10
+        }
+    };
+}
+
+#[time_it]
+limit!();
+
+fn main() {
+    let _ = LIMIT;
+    let _ = Config { limit: 1 };
+}
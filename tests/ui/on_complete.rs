@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use time_it::time_it;
+
+fn wrong_signature(_name: &str) {}
+
+#[time_it(on_complete = "missing_handler")]
+fn unresolved() {}
+
+#[time_it(on_complete = "wrong_signature")]
+fn mismatched() {}
+
+fn handler(_name: &'static str, _duration: Duration) {}
+
+#[time_it(on_complete = "handler")]
+fn valid() {}
+
+fn main() {
+    unresolved();
+    mismatched();
+    valid();
+}
@@ -0,0 +1,8 @@
+use time_it::time_it;
+
+#[time_it(on_complete = "not a path")]
+fn invalid() {}
+
+fn main() {
+    invalid();
+}
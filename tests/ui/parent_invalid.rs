@@ -0,0 +1,9 @@
+use time_it::time_it;
+
+#[time_it(parent = "request +")]
+fn invalid_expression() {}
+
+#[time_it(parent = "42")]
+fn not_a_span() {}
+
+fn main() {}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use time_it::time_it;
+
+#[time_it]
+fn bare() {}
+
+#[time_it("info")]
+fn positional_level() {}
+
+#[time_it(level = "warn", label = "work", threshold = "1ms", format = "human", location)]
+fn named_arguments() {}
+
+#[time_it(unit = "us", target = "timings", thread)]
+fn unit() {}
+
+#[time_it(err_level = "error", log_result)]
+fn fallible(fail: bool) -> Result<u32, String> {
+    if fail { Err("failed".into()) } else { Ok(1) }
+}
+
+#[time_it(tiers = "1ms: info, 1s: warn")]
+fn tiered() -> Duration {
+    Duration::ZERO
+}
+
+fn main() {
+    bare();
+    positional_level();
+    named_arguments();
+    unit();
+    let _ = fallible(false);
+    tiered();
+}
@@ -0,0 +1,53 @@
+//! Doc comments, `#[inline]` and `#[cold]` stay on the timed function in every mode, so a crate
+//! denying missing docs and misplaced attributes still compiles.
+
+#![deny(missing_docs, unused_attributes)]
+
+use time_it::time_it;
+
+/// Documented synchronous function.
+#[time_it]
+#[inline(always)]
+pub fn inlined() -> u32 {
+    1
+}
+
+/// Documented function whose body is wrapped to log its value.
+#[time_it(log_result)]
+#[inline]
+pub fn wrapped() -> u32 {
+    2
+}
+
+/// Documented function whose body is moved into a nested function.
+#[time_it(inner_fn, log_result)]
+#[inline(always)]
+pub fn nested() -> u32 {
+    3
+}
+
+/// Documented async function.
+#[time_it(log_result)]
+#[inline]
+pub async fn asynchronous() -> u32 {
+    4
+}
+
+/// Documented cold function, whose body is moved into a nested function.
+#[time_it(inner_fn)]
+#[cold]
+pub fn cold() {}
+
+#[time_it]
+/// Documented function, with the docs below `#[time_it]`.
+#[inline(never)]
+pub fn documented_below() {}
+
+fn main() {
+    inlined();
+    wrapped();
+    nested();
+    drop(asynchronous());
+    cold();
+    documented_below();
+}
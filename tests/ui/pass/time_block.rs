@@ -0,0 +1,6 @@
+use time_it::time_block;
+
+fn main() {
+    let sum: u32 = time_block!("sum", level = "info", { (1..=10).sum() });
+    assert_eq!(sum, 55);
+}
@@ -0,0 +1,8 @@
+use time_it::time_it;
+
+#[time_it(poll_count)]
+fn sync_work() {}
+
+fn main() {
+    sync_work();
+}
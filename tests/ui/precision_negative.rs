@@ -0,0 +1,6 @@
+use time_it::time_it;
+
+#[time_it(unit = "ms", precision = -1)]
+fn negative() {}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use time_it::time_it;
+
+#[time_it(precision = 2)]
+fn debug_format() {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use time_it::{time_block, time_it};
+
+struct Service;
+
+#[time_it(quantiles)]
+impl Service {
+    fn handle(&self) {}
+}
+
+fn main() {
+    time_block!("block", quantiles, {});
+}
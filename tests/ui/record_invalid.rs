@@ -0,0 +1,12 @@
+use time_it::time_it;
+
+#[time_it(record = "")]
+fn empty() {}
+
+#[time_it(record = "elapsed_ms", in_span)]
+fn with_in_span() {}
+
+#[time_it(record = "elapsed_ms", structured)]
+fn with_event_arguments() {}
+
+fn main() {}
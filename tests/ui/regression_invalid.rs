@@ -0,0 +1,15 @@
+use time_it::time_it;
+
+#[time_it(regression = 1.0)]
+fn not_slower() {}
+
+#[time_it(regression = "3x")]
+fn not_a_number() {}
+
+#[time_it(regression = 3.0, threshold = "1ms", slow_level = "error")]
+fn with_slow_level() {}
+
+#[time_it(regression = 3.0, span)]
+fn spanned() {}
+
+fn main() {}
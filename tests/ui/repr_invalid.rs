@@ -0,0 +1,9 @@
+use time_it::time_it;
+
+#[time_it(repr = "millis")]
+fn unknown() {}
+
+#[time_it(repr = "nanos", unit = "ms")]
+fn overlapping() {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use time_it::time_it;
+
+#[time_it(warn_zero, warn_zero)]
+fn repeated() {}
+
+#[time_it(clock = "high_res")]
+fn without_feature() {}
+
+fn main() {}
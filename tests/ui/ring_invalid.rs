@@ -0,0 +1,19 @@
+use time_it::time_it;
+
+#[time_it(ring = 0)]
+fn empty() {}
+
+#[time_it(ring = 8, structured)]
+fn with_event() {}
+
+struct Client;
+
+#[time_it(ring = 8)]
+impl Client {
+    fn connect(&self) {}
+}
+
+fn main() {
+    empty();
+    with_event();
+}
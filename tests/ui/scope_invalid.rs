@@ -0,0 +1,13 @@
+fn without_feature() {
+    time_it::scope!("request", {});
+}
+
+fn with_arguments() {
+    time_it::scope!("request", threshold = "1ms", {});
+}
+
+fn without_block() {
+    time_it::scope!("request", info);
+}
+
+fn main() {}
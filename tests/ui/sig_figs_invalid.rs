@@ -0,0 +1,15 @@
+use time_it::time_it;
+
+#[time_it(format = "human", sig_figs = 0)]
+fn zero() {}
+
+#[time_it(format = "human", sig_figs = 7)]
+fn too_many() {}
+
+#[time_it(sig_figs = 3)]
+fn without_human() {}
+
+#[time_it(format = "human", sig_figs = 3, precision = 2)]
+fn with_precision() {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use time_it::time_it;
+
+#[time_it(silent)]
+fn nowhere() {}
+
+#[time_it(quantiles, silent, level = "info")]
+fn leveled() {}
+
+#[time_it(quantiles, silent, threshold = "1ms")]
+fn thresholded() {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use time_it::time_it;
+
+#[time_it(sink = "socket")]
+fn unknown_sink() {}
+
+#[time_it(sink = "channel", structured)]
+fn shaped_event() {}
+
+#[time_it(sink = "channel", handler = |_, _| {})]
+fn with_handler() {}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use time_it::time_it;
+
+#[time_it(skip_err)]
+fn infallible() -> u32 {
+    1
+}
+
+fn main() {
+    infallible();
+}
@@ -0,0 +1,9 @@
+use time_it::time_it;
+
+#[time_it(slow_level = "error")]
+fn without_threshold() {}
+
+#[time_it(rate_limit = "0s")]
+fn empty_window() {}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use time_it::time_it;
+
+#[time_it(split_await)]
+fn sync_work() {}
+
+fn main() {
+    sync_work();
+}
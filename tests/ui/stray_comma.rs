@@ -0,0 +1,8 @@
+use time_it::time_it;
+
+#[time_it(,)]
+fn work() {}
+
+fn main() {
+    work();
+}
@@ -0,0 +1,8 @@
+use time_it::time_it;
+
+#[time_it(summarize = 10)]
+fn unfiltered() {}
+
+fn main() {
+    unfiltered();
+}
@@ -0,0 +1,8 @@
+use time_it::time_it;
+
+#[time_it(threshold = "5pc")]
+fn work() {}
+
+fn main() {
+    work();
+}
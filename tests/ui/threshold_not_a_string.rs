@@ -0,0 +1,8 @@
+use time_it::time_it;
+
+#[time_it(threshold = 5)]
+fn work() {}
+
+fn main() {
+    work();
+}
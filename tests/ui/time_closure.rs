@@ -0,0 +1,6 @@
+use time_it::time_closure;
+
+fn main() {
+    let _logged = time_closure!("logged", log_args, |x: u32| x);
+    let _missing = time_closure!("missing", level = "info");
+}
@@ -0,0 +1,7 @@
+use time_it::time_scope;
+
+fn main() {
+    time_scope!("missing level", {});
+    time_scope!("extra argument", info, threshold = "1ms", {});
+    time_scope!("invalid level", loud, {});
+}
@@ -0,0 +1,8 @@
+use time_it::time_it;
+
+trait Service {
+    #[time_it]
+    fn call(&self);
+}
+
+fn main() {}
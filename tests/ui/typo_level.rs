@@ -0,0 +1,8 @@
+use time_it::time_it;
+
+#[time_it(level = "infro")]
+fn work() {}
+
+fn main() {
+    work();
+}
@@ -0,0 +1,16 @@
+use time_it::time_it;
+
+#[time_it(via = "timing_log!")]
+fn with_bang() {}
+
+#[time_it(via = "logging::<u32>")]
+fn with_generics() {}
+
+#[time_it(via = "timing_log", span)]
+fn with_span() {}
+
+fn main() {
+    with_bang();
+    with_generics();
+    with_span();
+}
@@ -0,0 +1,72 @@
+//! `via` emits events through a macro of the caller's instead of the `tracing` ones.
+
+mod common;
+
+use time_it::{time_block, time_it};
+use tracing::Level;
+
+/// Wrapper logging every event at the warn level, marked with a `wrapped` field.
+macro_rules! timing_log {
+    (target: $target:expr, $($arg:tt)*) => {
+        tracing::warn!(target: $target, wrapped = true, $($arg)*)
+    };
+    ($($arg:tt)*) => {
+        tracing::warn!(wrapped = true, $($arg)*)
+    };
+}
+
+mod logging {
+    macro_rules! tagged_log {
+        ($($arg:tt)*) => {
+            tracing::info!(tag = "timing", $($arg)*)
+        };
+    }
+
+    pub(crate) use tagged_log;
+}
+
+#[time_it(via = "timing_log")]
+fn work() {}
+
+#[time_it(via = "logging::tagged_log", label = "tagged")]
+fn tagged_work() {}
+
+#[time_it(via = "timing_log", structured, trace_entry, target = "timings")]
+fn structured_work() {}
+
+#[time_it(level = "debug", err_level = "error", via = "timing_log")]
+fn fallible(fail: bool) -> Result<(), ()> {
+    if fail { Err(()) } else { Ok(()) }
+}
+
+#[test]
+fn messages_are_emitted_through_the_macro() {
+    let ((), events) = common::capture(|| {
+        work();
+        tagged_work();
+        time_block!("block", via = "timing_log", {});
+    });
+    assert_eq!(events.len(), 3);
+    assert!(events[0].starts_with("message=[work]: Execution time: "));
+    assert!(events[0].ends_with(" wrapped=true "));
+    assert!(events[1].starts_with("message=[tagged]: Execution time: "));
+    assert!(events[1].ends_with(" tag=\"timing\" "));
+    assert!(events[2].starts_with("message=[block]: Execution time: "));
+    assert!(events[2].ends_with(" wrapped=true "));
+}
+
+#[test]
+fn structured_events_and_targets_are_passed_on() {
+    let ((), events) = common::capture(structured_work);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("message=execution started wrapped=true "));
+    assert!(events[0].contains("function=\"structured_work\" "));
+    assert!(events[1].starts_with("message=execution timed wrapped=true "));
+    assert!(events[1].contains("duration_ms="));
+}
+
+#[test]
+fn the_macro_is_invoked_for_every_level() {
+    let (_, levels) = common::capture_levels(|| (fallible(false), fallible(true)));
+    assert_eq!(levels, [Level::WARN, Level::WARN]);
+}
@@ -0,0 +1,126 @@
+//! `warn_zero` warns once when a call measures no time, as its duration is then below the
+//! resolution of the clock rather than an actual measurement.
+
+mod common;
+
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+use time_it::{time_block, time_it};
+use tracing::Level;
+
+thread_local! {
+    /// Time of the fake clock, only advanced by the timed functions.
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Starting point of a measurement on the fake clock.
+struct FakeInstant(Duration);
+
+impl FakeInstant {
+    fn now() -> Self {
+        Self(NOW.with(Cell::get))
+    }
+
+    fn elapsed(&self) -> Duration {
+        NOW.with(Cell::get) - self.0
+    }
+}
+
+/// Advances the fake clock by `micros`.
+fn advance(micros: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_micros(micros)));
+}
+
+#[time_it("info", warn_zero, clock_fn = "FakeInstant::now")]
+fn tiny(micros: u64) {
+    advance(micros);
+}
+
+#[time_it(warn_zero, clock_fn = "FakeInstant::now")]
+fn measurable() {
+    advance(1);
+}
+
+#[time_it("info", warn_zero, clock_fn = "FakeInstant::now")]
+fn instant() {}
+
+#[time_it(warn_zero, threshold = "1ms", clock_fn = "FakeInstant::now")]
+fn below_threshold() {}
+
+#[test]
+fn the_first_zero_measurement_is_warned_about() {
+    let ((), events) = common::capture(|| {
+        tiny(5);
+        tiny(0);
+        tiny(0);
+    });
+    assert_eq!(events.len(), 4);
+    assert!(events[0].starts_with("message=[tiny]: Execution time: 5µs"));
+    assert_eq!(
+        events[1].trim_end(),
+        "message=[tiny]: Measured no time, below the resolution of the clock"
+    );
+    assert!(events[2].starts_with("message=[tiny]: Execution time: 0ns"));
+    assert!(events[3].starts_with("message=[tiny]: Execution time: 0ns"));
+}
+
+#[test]
+fn warnings_are_emitted_at_warn() {
+    let ((), levels) = common::capture_levels(instant);
+    assert_eq!(levels, [Level::WARN, Level::INFO]);
+}
+
+#[test]
+fn measured_calls_are_not_warned_about() {
+    let ((), events) = common::capture(|| {
+        for _ in 0..3 {
+            measurable();
+        }
+    });
+    assert_eq!(events.len(), 3);
+    assert!(
+        events
+            .iter()
+            .all(|event| !event.contains("Measured no time"))
+    );
+}
+
+#[test]
+fn warnings_ignore_the_threshold() {
+    let ((), events) = common::capture(below_threshold);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].contains("Measured no time"), "{}", events[0]);
+}
+
+#[test]
+fn blocks_warn_about_zero_measurements() {
+    let ((), events) = common::capture(|| {
+        time_block!("block", warn_zero, clock_fn = "FakeInstant::now", {});
+    });
+    assert_eq!(events.len(), 2);
+    assert!(
+        events[0].contains("[block]: Measured no time"),
+        "{}",
+        events[0]
+    );
+}
+
+#[test]
+fn the_default_clock_resolves_below_a_microsecond() {
+    // The smallest step `Instant` takes between two readings bounds its resolution. `Instant` uses
+    // the highest resolution monotonic clock of the platform, e.g. `QueryPerformanceCounter` on
+    // Windows or `CLOCK_MONOTONIC` on Linux.
+    let mut resolution = Duration::MAX;
+    for _ in 0..1_000 {
+        let start = Instant::now();
+        let mut now = Instant::now();
+        while now == start {
+            now = Instant::now();
+        }
+        resolution = resolution.min(now - start);
+    }
+    assert!(resolution < Duration::from_micros(1), "{resolution:?}");
+}
@@ -0,0 +1,2331 @@
+//! Parsing of the arguments accepted by the `#[time_it]` attribute and the `time_block!` macro.
+
+use std::{str::FromStr, time::Duration};
+
+use proc_macro2::Span;
+use quote::quote;
+use strum::{AsRefStr, EnumString, VariantNames};
+use syn::{
+    Block, Expr, ExprClosure, Ident, Lit, LitInt, LitStr, Path, Token,
+    parse::ParseStream,
+    token::{Brace, Paren},
+};
+
+use crate::expand::tracing_crate;
+
+/// Number of fast calls reported together by a bare `summarize` flag.
+const DEFAULT_SUMMARY_CALLS: u64 = 100;
+
+/// Names of all supported `key = value` arguments, used in diagnostics.
+const KEYS: &[&str] = &[
+    "level",
+    "label",
+    "name",
+    "threshold",
+    "budget",
+    "format",
+    "unit",
+    "target",
+    "err_level",
+    "clock",
+    "clock_fn",
+    "metric",
+    "template",
+    "err_template",
+    "tiers",
+    "on_complete",
+    "collector",
+    "min_report",
+    "output",
+    "span_level",
+    "first",
+    "sample",
+    "precision",
+    "sig_figs",
+    "min_unit",
+    "width",
+    "ordering",
+    "fmt_fn",
+    "fields",
+    "category",
+    "via",
+    "ring",
+    "handler",
+    "parent",
+    "summarize",
+    "repr",
+    "level_fn",
+    "slow_level",
+    "rate_limit",
+    "sink",
+    "label_arg",
+    "bytes_arg",
+    "mode",
+    "regression",
+    "record",
+    "name_field",
+    "exclude",
+];
+
+/// Names of all supported flag arguments, used in diagnostics.
+const FLAGS: &[&str] = &[
+    "debug_only",
+    "location",
+    "structured",
+    "span",
+    "log_result",
+    "log_args",
+    "aggregate",
+    "thread",
+    "env_gate",
+    "in_span",
+    "future",
+    "raw_ns",
+    "seq",
+    "corr_id",
+    "otel",
+    "allocs",
+    "trace_entry",
+    "kind",
+    "chrome_trace",
+    "include_drops",
+    "inner_fn",
+    "on_cancel",
+    "poll_count",
+    "split_await",
+    "skip_err",
+    "since_start",
+    "depth",
+    "top_only",
+    "skip",
+    "quantiles",
+    "both_clocks",
+    "log_err_value",
+    "exclude_idle",
+    "warn_zero",
+    "runtime",
+    "silent",
+    "bytes_ret",
+];
+
+/// Arguments accepted by the `#[time_it]` attribute.
+///
+/// Arguments are written as comma separated `key = "value"` pairs or bare flags. For backwards
+/// compatibility a lone string literal or identifier is accepted as the log level, so
+/// `#[time_it("info")]`, `#[time_it(info)]` and `#[time_it(level = "info")]` are equivalent. A
+/// string literal containing `=` or `;` instead packs several arguments, as in
+/// `#[time_it("level=info;threshold=10ms")]`.
+#[derive(Default)]
+pub(crate) struct Args {
+    pub(crate) level: Option<LogLevel>,
+    /// Function returning the `tracing::Level` of each event, called when the event is emitted.
+    pub(crate) level_fn: Option<Path>,
+    /// Name used in events instead of the function name, given with `label` or `name`.
+    pub(crate) label: Option<LitStr>,
+    /// Argument of the function whose value names each call in events, instead of the function
+    /// name.
+    pub(crate) label_arg: Option<LitStr>,
+    /// Argument of the function holding the number of bytes each call processes, for the
+    /// throughput.
+    pub(crate) bytes_arg: Option<LitStr>,
+    /// Takes the number of bytes each call processes from its returned value instead.
+    pub(crate) bytes_ret: bool,
+    /// Field of the current span the duration is recorded into, as with `in_span`.
+    pub(crate) record: Option<LitStr>,
+    /// Key of the field naming the function in structured events, `function` by default.
+    pub(crate) name_field: Option<LitStr>,
+    pub(crate) threshold: Option<Duration>,
+    /// Duration calls must not exceed in tests, or they panic.
+    pub(crate) budget: Option<Duration>,
+    /// Resolution below which measurements are discarded as noise.
+    pub(crate) min_report: Option<Duration>,
+    pub(crate) format: Option<DurationFormat>,
+    /// Representation selecting the `format` or `unit`, resolved into them by `validate`.
+    pub(crate) repr: Option<Repr>,
+    /// Whether calls are reported as events or spans, resolved into `span` by `validate`.
+    pub(crate) mode: Option<Mode>,
+    pub(crate) unit: Option<TimeUnit>,
+    /// Number of decimals of durations rendered with `unit` or the human format.
+    pub(crate) precision: Option<u8>,
+    /// Number of significant figures of durations rendered with the human format.
+    pub(crate) sig_figs: Option<u8>,
+    /// Smallest unit of durations rendered with the human format, shorter durations being clamped
+    /// to it.
+    pub(crate) min_unit: Option<TimeUnit>,
+    /// Minimum number of characters of durations rendered with the aligned format.
+    pub(crate) width: Option<u8>,
+    /// Function rendering durations in the message, replacing `format` and `unit`.
+    pub(crate) fmt_fn: Option<Path>,
+    pub(crate) target: Option<LitStr>,
+    pub(crate) err_level: Option<LogLevel>,
+    pub(crate) span_level: Option<LogLevel>,
+    /// Level of the events of calls reaching the `threshold`.
+    pub(crate) slow_level: Option<LogLevel>,
+    /// Minimum time between two events, the ones in between being dropped.
+    pub(crate) rate_limit: Option<Duration>,
+    /// Factor over the fastest call so far from which a call is reported at WARN.
+    pub(crate) regression: Option<f64>,
+    pub(crate) clock: Option<Clock>,
+    /// Function returning the starting point of a measurement, replacing the `clock`.
+    pub(crate) clock_fn: Option<Path>,
+    pub(crate) output: Option<Output>,
+    /// Macro invoked to emit events instead of the logging macro of the backend.
+    pub(crate) via: Option<Path>,
+    /// Expression evaluated when the timed code starts, giving the parent span of the events.
+    pub(crate) parent: Option<Expr>,
+    /// Histogram name given with `metric = "..."`, or `Some(None)` for a bare `metric` flag, which
+    /// defaults to the function name.
+    pub(crate) metric: Option<Option<LitStr>>,
+    pub(crate) template: Option<Vec<TemplatePiece>>,
+    /// Template of the message for calls returning an `Err`.
+    pub(crate) err_template: Option<Vec<TemplatePiece>>,
+    /// Levels used from a minimum duration on, sorted by increasing duration.
+    pub(crate) tiers: Option<Vec<(Duration, LogLevel)>>,
+    /// Function called with the name and duration of every call.
+    pub(crate) on_complete: Option<Path>,
+    /// Closure called with the name and duration of every call instead of emitting an event.
+    pub(crate) handler: Option<ExprClosure>,
+    /// Value whose `record` method is called with the name and duration of every call.
+    pub(crate) collector: Option<Path>,
+    /// Destination of the name and duration of every call instead of an event.
+    pub(crate) sink: Option<Sink>,
+    /// Number of calls reported before going silent.
+    pub(crate) first: Option<u64>,
+    /// Interval between reported calls, e.g. 100 to report one call in every 100.
+    pub(crate) sample: Option<u64>,
+    /// Number of calls below the `threshold` reported together in a summary.
+    pub(crate) summarize: Option<u64>,
+    /// Number of recent durations kept in a ring buffer instead of emitting events.
+    pub(crate) ring: Option<u64>,
+    /// Memory ordering of the operations on the generated atomics.
+    pub(crate) ordering: Option<MemoryOrdering>,
+    /// Constant fields added to every event, given as `fields(key = literal, ...)`.
+    pub(crate) fields: Option<Vec<(Ident, Lit)>>,
+    /// Subsystem the timed code belongs to, added to every event as the `category` field.
+    pub(crate) category: Option<LitStr>,
+    pub(crate) debug_only: bool,
+    pub(crate) location: bool,
+    pub(crate) structured: bool,
+    pub(crate) span: bool,
+    pub(crate) log_result: bool,
+    /// Adds the `Debug` representation of the error to the events of calls returning an `Err`.
+    pub(crate) log_err_value: bool,
+    pub(crate) log_args: bool,
+    pub(crate) aggregate: bool,
+    /// Records every duration into a histogram the quantiles are estimated from.
+    pub(crate) quantiles: bool,
+    /// Emits no event, leaving the measurements to statistics and callbacks.
+    pub(crate) silent: bool,
+    pub(crate) thread: bool,
+    /// Adds the flavor of the tokio runtime the future completes on.
+    pub(crate) runtime: bool,
+    pub(crate) env_gate: bool,
+    pub(crate) in_span: bool,
+    pub(crate) future: bool,
+    pub(crate) raw_ns: bool,
+    /// Numbers the calls with a per function sequence number.
+    pub(crate) seq: bool,
+    /// Tags the events of each call with a random correlation id.
+    pub(crate) corr_id: bool,
+    /// Sets the duration as an attribute of the current OpenTelemetry span.
+    pub(crate) otel: bool,
+    /// Reports the allocations made by the current thread during the call.
+    pub(crate) allocs: bool,
+    /// Also emits an event when the call starts.
+    pub(crate) trace_entry: bool,
+    /// Adds a `kind` field telling async and sync functions apart.
+    pub(crate) kind: bool,
+    /// Adds the start time and the duration in microseconds, as the `ts` and `dur` fields of
+    /// chrome trace events.
+    pub(crate) chrome_trace: bool,
+    /// Moves the arguments into the body, so their destructors are part of the measurement.
+    pub(crate) include_drops: bool,
+    /// Moves the body into a nested function, called while timing instead of a closure.
+    pub(crate) inner_fn: bool,
+    /// Reports calls whose future is dropped before completing as cancelled.
+    pub(crate) on_cancel: bool,
+    /// Counts the polls of the future until it completes.
+    pub(crate) poll_count: bool,
+    /// Reports the time until the future first suspends next to the total.
+    pub(crate) split_await: bool,
+    /// Measures the time spent polling the future, leaving out the time it waited to be polled.
+    pub(crate) exclude_idle: bool,
+    /// Name of the exclusions registered with `exclude!`, whose time is left out of the
+    /// measurement.
+    pub(crate) exclude: Option<LitStr>,
+    /// Leaves calls returning an `Err` unreported.
+    pub(crate) skip_err: bool,
+    /// Adds the start time relative to the baseline shared by the process.
+    pub(crate) since_start: bool,
+    /// Adds the number of calls to the function the current thread was already in.
+    pub(crate) depth: bool,
+    /// Only reports calls made outside of other calls to the function, with `depth`.
+    pub(crate) top_only: bool,
+    /// Leaves the function unchanged, given as the only argument.
+    pub(crate) skip: bool,
+    /// Measures the CPU time spent by the current thread next to the wall-clock time.
+    pub(crate) both_clocks: bool,
+    /// Warns once when a call measures no time, i.e. runs below the resolution of the clock.
+    pub(crate) warn_zero: bool,
+}
+
+/// Level used by events that don't set one, overriding the default of DEBUG.
+///
+/// Read when this crate is compiled, which cargo redoes whenever the variable changes.
+const DEFAULT_LEVEL_VAR: Option<&str> = option_env!("TIME_IT_DEFAULT_LEVEL");
+
+/// Levels ordered from the most verbose to the most severe.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumString, AsRefStr, VariantNames)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub(crate) enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// `TIME_IT_DEFAULT_LEVEL` if set at build time, DEBUG otherwise. Invalid values are reported
+/// when the arguments are validated.
+impl Default for LogLevel {
+    fn default() -> Self {
+        DEFAULT_LEVEL_VAR
+            .and_then(|level| level.parse().ok())
+            .unwrap_or(Self::Debug)
+    }
+}
+
+impl LogLevel {
+    /// Name of the `tracing` macro emitting an event at this level.
+    pub(crate) fn macro_ident(self) -> Ident {
+        Ident::new(self.as_ref(), Span::call_site())
+    }
+
+    /// Whether events at this level are kept by the `max_level_*` features. The most restrictive
+    /// feature applies when several of them are enabled.
+    pub(crate) fn statically_enabled(self) -> bool {
+        let min = if cfg!(feature = "max_level_off") {
+            return false;
+        } else if cfg!(feature = "max_level_error") {
+            Self::Error
+        } else if cfg!(feature = "max_level_warn") {
+            Self::Warn
+        } else if cfg!(feature = "max_level_info") {
+            Self::Info
+        } else if cfg!(feature = "max_level_debug") {
+            Self::Debug
+        } else {
+            Self::Trace
+        };
+        self >= min
+    }
+
+    /// Path to the matching `tracing::Level` constant.
+    pub(crate) fn tracing_level(self) -> proc_macro2::TokenStream {
+        let level = Ident::new(&self.as_ref().to_uppercase(), Span::call_site());
+        let tracing = tracing_crate();
+        quote! {#tracing::Level::#level}
+    }
+
+    /// Path to the matching `log::Level` variant.
+    pub(crate) fn log_level(self) -> proc_macro2::TokenStream {
+        let level = match self {
+            Self::Error => "Error",
+            Self::Warn => "Warn",
+            Self::Info => "Info",
+            Self::Debug => "Debug",
+            Self::Trace => "Trace",
+        };
+        let level = Ident::new(level, Span::call_site());
+        quote! {::log::Level::#level}
+    }
+}
+
+/// How the measured duration is rendered in the event message.
+#[derive(Clone, Copy, Default, EnumString, VariantNames)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub(crate) enum DurationFormat {
+    /// The `Debug` representation of `Duration`, e.g. `1.234567891s`.
+    #[default]
+    Debug,
+    /// A rounded representation, e.g. `1.23s`, `45.6ms` or `900µs`.
+    Human,
+    /// A right-justified number of milliseconds of fixed width, e.g. `   45.612 ms`.
+    Aligned,
+}
+
+/// How calls are reported, selecting the `span` flag it stands for.
+#[derive(Clone, Copy, PartialEq, Eq, EnumString, VariantNames)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub(crate) enum Mode {
+    /// An event emitted once the call completes, the default.
+    Event,
+    /// The `span` flag, a span covering the call with the duration recorded into it.
+    Span,
+}
+
+/// A representation of the duration in the message, selecting the `format` or `unit` it stands for.
+#[derive(Clone, Copy, EnumString, VariantNames)]
+#[strum(ascii_case_insensitive, serialize_all = "snake_case")]
+pub(crate) enum Repr {
+    /// `format = "debug"`.
+    Debug,
+    /// `unit = "s"`, the fractional number of seconds.
+    SecsF64,
+    /// `unit = "ns"`, the whole number of nanoseconds.
+    Nanos,
+    /// `format = "human"`.
+    Human,
+    /// `format = "aligned"`.
+    Aligned,
+}
+
+/// Unit of the durations in messages that don't set a `unit` or `format`, replacing their `Debug`
+/// formatting.
+///
+/// Read when this crate is compiled, like `TIME_IT_DEFAULT_LEVEL`.
+const DEFAULT_UNIT_VAR: Option<&str> = option_env!("TIME_IT_DEFAULT_UNIT");
+
+/// A fixed unit every duration is converted to before being logged.
+#[derive(Clone, Copy, EnumString, VariantNames)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub(crate) enum TimeUnit {
+    Ns,
+    Us,
+    Ms,
+    S,
+}
+
+impl TimeUnit {
+    /// `TIME_IT_DEFAULT_UNIT` if set at build time. Invalid values are reported when the
+    /// arguments are validated.
+    pub(crate) fn configured() -> Option<Self> {
+        DEFAULT_UNIT_VAR.and_then(|unit| unit.parse().ok())
+    }
+
+    /// Format specifier for the converted duration, including the unit suffix, rounded to
+    /// `precision` decimals if given.
+    pub(crate) fn spec(self, precision: Option<u8>) -> String {
+        let suffix = match self {
+            Self::Ns => "ns",
+            Self::Us => "us",
+            Self::Ms => "ms",
+            Self::S => "s",
+        };
+        match precision {
+            Some(precision) => format!("{{:.{precision}}}{suffix}"),
+            None => format!("{{}}{suffix}"),
+        }
+    }
+
+    /// Power of ten of the nanoseconds in one of this unit.
+    pub(crate) fn exponent(self) -> u32 {
+        match self {
+            Self::Ns => 0,
+            Self::Us => 3,
+            Self::Ms => 6,
+            Self::S => 9,
+        }
+    }
+
+    /// Expression converting `__duration` into this unit. With a `precision`, the duration is
+    /// converted to a fractional amount, truncated to an integer otherwise.
+    pub(crate) fn accessor(self, precision: Option<u8>) -> proc_macro2::TokenStream {
+        match (self, precision) {
+            (Self::Ns, None) => quote! {__duration.as_nanos()},
+            (Self::Us, None) => quote! {__duration.as_micros()},
+            (Self::Ms, None) => quote! {__duration.as_millis()},
+            (Self::Ns, Some(_)) => quote! {__duration.as_nanos() as f64},
+            (Self::Us, Some(_)) => quote! {__duration.as_nanos() as f64 / 1_000.0},
+            (Self::Ms, Some(_)) => quote! {__duration.as_nanos() as f64 / 1_000_000.0},
+            (Self::S, _) => quote! {__duration.as_secs_f64()},
+        }
+    }
+}
+
+/// A destination measurements are sent to instead of emitting an event.
+#[derive(Clone, Copy, EnumString, VariantNames)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub(crate) enum Sink {
+    /// The channel of `time_it::channel`.
+    Channel,
+}
+
+/// A part of a `template`, in the order it appears in the message.
+pub(crate) enum TemplatePiece {
+    /// Literal text, with braces still escaped as `{{` and `}}`.
+    Text(String),
+    /// The `{name}` placeholder, replaced by the function name or label.
+    Name,
+    /// The `{duration}` placeholder, replaced by the duration as selected by `format` or `unit`.
+    Duration,
+}
+
+/// A standard stream events are printed to instead of going through the logging backend.
+#[derive(Clone, Copy, EnumString, VariantNames)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub(crate) enum Output {
+    Stdout,
+    Stderr,
+}
+
+impl Output {
+    /// Name of the macro printing a line to this stream.
+    pub(crate) fn macro_ident(self) -> Ident {
+        let name = match self {
+            Self::Stdout => "println",
+            Self::Stderr => "eprintln",
+        };
+        Ident::new(name, Span::call_site())
+    }
+}
+
+/// The clock measuring the execution time.
+#[derive(Clone, Copy, Default, PartialEq, Eq, EnumString, VariantNames)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub(crate) enum Clock {
+    /// Wall-clock time, measured with `std::time::Instant`.
+    #[default]
+    Wall,
+    /// CPU time spent by the current thread, measured with `cpu_time::ThreadTime`.
+    Cpu,
+    /// Wall-clock time, measured with `quanta::Instant`, which reads the CPU's timestamp counter
+    /// where it's reliable and falls back to the same OS clock as `Instant` otherwise.
+    #[strum(serialize = "high_res")]
+    HighRes,
+}
+
+/// The memory ordering used by the atomics counting calls, e.g. for `seq` or `aggregate`.
+#[derive(Clone, Copy, Default, EnumString, VariantNames)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub(crate) enum MemoryOrdering {
+    #[default]
+    Relaxed,
+    AcqRel,
+    SeqCst,
+}
+
+impl MemoryOrdering {
+    /// Ordering of read-modify-write operations such as `fetch_add`.
+    pub(crate) fn rmw(self) -> proc_macro2::TokenStream {
+        match self {
+            Self::Relaxed => quote! {::core::sync::atomic::Ordering::Relaxed},
+            Self::AcqRel => quote! {::core::sync::atomic::Ordering::AcqRel},
+            Self::SeqCst => quote! {::core::sync::atomic::Ordering::SeqCst},
+        }
+    }
+
+    /// Ordering of stores, which can't acquire, so `AcqRel` only releases.
+    pub(crate) fn store(self) -> proc_macro2::TokenStream {
+        match self {
+            Self::Relaxed => quote! {::core::sync::atomic::Ordering::Relaxed},
+            Self::AcqRel => quote! {::core::sync::atomic::Ordering::Release},
+            Self::SeqCst => quote! {::core::sync::atomic::Ordering::SeqCst},
+        }
+    }
+
+    /// Ordering of loads, which can't release, so `AcqRel` only acquires.
+    pub(crate) fn load(self) -> proc_macro2::TokenStream {
+        match self {
+            Self::Relaxed => quote! {::core::sync::atomic::Ordering::Relaxed},
+            Self::AcqRel => quote! {::core::sync::atomic::Ordering::Acquire},
+            Self::SeqCst => quote! {::core::sync::atomic::Ordering::SeqCst},
+        }
+    }
+}
+
+impl Clock {
+    /// Type of the starting point, providing `now()` and `elapsed()`.
+    pub(crate) fn instant_type(self) -> proc_macro2::TokenStream {
+        match self {
+            Self::Wall => quote! {::std::time::Instant},
+            Self::Cpu => quote! {::cpu_time::ThreadTime},
+            Self::HighRes => quote! {::quanta::Instant},
+        }
+    }
+
+    /// Expression reading the time elapsed since `start`, a starting point of this clock.
+    pub(crate) fn elapsed(self, start: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match self {
+            Self::Wall | Self::Cpu => quote! {#start.elapsed()},
+            Self::HighRes => quote! {::quanta::Instant::now().duration_since(#start)},
+        }
+    }
+}
+
+impl syn::parse::Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        if is_skip(input) {
+            input.parse::<Ident>()?;
+            input.parse::<Option<Token![,]>>()?;
+            args.skip = true;
+            return Ok(args);
+        }
+        while !input.is_empty() {
+            args.parse_arg(input)?;
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        args.validate()?;
+        Ok(args)
+    }
+}
+
+/// Input of the `time_block!` macro: a label, optional arguments and the block to time.
+///
+/// Arguments are written as for `#[time_it]`, with the label given as a leading string literal
+/// and the block last, e.g. `time_block!("parsing", level = "info", { parse() })`.
+pub(crate) struct BlockInput {
+    pub(crate) label: LitStr,
+    pub(crate) args: Args,
+    pub(crate) block: Block,
+}
+
+impl syn::parse::Parse for BlockInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let label: LitStr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let mut args = Args::default();
+        while !input.peek(Brace) {
+            if input.is_empty() {
+                return Err(input.error("Expected the block to time, e.g. `{ work() }`"));
+            }
+            args.parse_arg(input)?;
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        let block: Block = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        if !input.is_empty() {
+            return Err(input.error("Expected the block to be the last argument"));
+        }
+        args.validate()?;
+        Ok(Self { label, args, block })
+    }
+}
+
+/// Input of the `time_closure!` macro: a label, optional arguments and the closure to time.
+///
+/// Arguments are written as for `time_block!`, with the closure last, e.g.
+/// `time_closure!("worker", level = "info", |x| x + 1)`.
+pub(crate) struct ClosureInput {
+    pub(crate) label: LitStr,
+    pub(crate) args: Args,
+    pub(crate) closure: ExprClosure,
+}
+
+impl syn::parse::Parse for ClosureInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let label: LitStr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let mut args = Args::default();
+        while !(input.peek(Token![|])
+            || input.peek(Token![||])
+            || input.peek(Token![move])
+            || input.peek(Token![async]))
+        {
+            if input.is_empty() {
+                return Err(input.error("Expected the closure to time, e.g. `|x| x + 1`"));
+            }
+            args.parse_arg(input)?;
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        let closure: ExprClosure = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        if !input.is_empty() {
+            return Err(input.error("Expected the closure to be the last argument"));
+        }
+        args.validate()?;
+        Ok(Self {
+            label,
+            args,
+            closure,
+        })
+    }
+}
+
+/// Input of the `time_scope!` macro: a name, a level and the block to time, e.g.
+/// `time_scope!("parsing", info, { parse() })`.
+///
+/// Unlike `time_block!`, the level is required and no other argument is accepted.
+pub(crate) struct ScopeInput {
+    pub(crate) label: LitStr,
+    pub(crate) args: Args,
+    pub(crate) block: Block,
+}
+
+impl syn::parse::Parse for ScopeInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let label: LitStr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        if !(input.peek(LitStr) || input.peek(Ident) || input.peek(Token![::])) {
+            return Err(input.error(
+                "Expected the level of the event, e.g. `info`, `\"info\"` or `Level::INFO`",
+            ));
+        }
+        let level = parse_level(input)?;
+        input.parse::<Token![,]>()?;
+        if !input.peek(Brace) {
+            return Err(input.error(
+                "Expected the block to time after the level, `time_scope!` only takes a name, a \
+                 level and a block, use `time_block!` for other arguments",
+            ));
+        }
+        let block: Block = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        if !input.is_empty() {
+            return Err(input.error("Expected the block to be the last argument"));
+        }
+        let mut args = Args {
+            level: Some(level),
+            ..Args::default()
+        };
+        args.validate()?;
+        Ok(Self { label, args, block })
+    }
+}
+
+/// Input of the `exclude!` macro: the name of the exclusion and the expression whose time is left
+/// out, e.g. `exclude!("lock", mutex.lock())`.
+pub(crate) struct ExcludeInput {
+    pub(crate) name: LitStr,
+    pub(crate) expr: Expr,
+}
+
+impl syn::parse::Parse for ExcludeInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: LitStr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let expr: Expr = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        if !input.is_empty() {
+            return Err(input.error(
+                "Expected the expression to be the last argument, `exclude!` only takes a name and \
+                 an expression",
+            ));
+        }
+        Ok(Self { name, expr })
+    }
+}
+
+/// Input of the `scope!` macro: a name, an optional level and the block grouping the timed calls,
+/// e.g. `scope!("request", info, { handle() })`.
+pub(crate) struct GroupInput {
+    pub(crate) label: LitStr,
+    pub(crate) args: Args,
+    pub(crate) block: Block,
+}
+
+impl syn::parse::Parse for GroupInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let label: LitStr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let level = if input.peek(LitStr) || input.peek(Ident) || input.peek(Token![::]) {
+            let level = parse_level(input)?;
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+            Some(level)
+        } else {
+            None
+        };
+        if !input.peek(Brace) {
+            return Err(input.error(
+                "Expected the block to group, `scope!` only takes a name, an optional level and a \
+                 block",
+            ));
+        }
+        let block: Block = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        if !input.is_empty() {
+            return Err(input.error("Expected the block to be the last argument"));
+        }
+        let args = Args {
+            level,
+            ..Args::default()
+        };
+        Ok(Self { label, args, block })
+    }
+}
+
+/// Parses a level given as `info`, `"info"` or `Level::INFO`.
+fn parse_level(input: ParseStream) -> syn::Result<LogLevel> {
+    if input.peek(LitStr) {
+        let lit: LitStr = input.parse()?;
+        parse_enum("log level", &lit.value(), lit.span())
+    } else {
+        let path: Path = input.parse()?;
+        match path.get_ident() {
+            Some(ident) => parse_enum("log level", &ident.to_string(), ident.span()),
+            None => level_constant(&path),
+        }
+    }
+}
+
+impl Args {
+    /// Parses a single argument: a lone string literal or level, a `key = value` pair or a flag.
+    fn parse_arg(&mut self, input: ParseStream) -> syn::Result<()> {
+        if input.peek(Token![,]) {
+            return Err(input.error("Unexpected `,`, expected an argument before it"));
+        }
+        if !input.peek(LitStr) && !input.peek(Ident) {
+            return Err(input.error(
+                "Expected an argument, such as `\"info\"`, `level = \"info\"` or `location`",
+            ));
+        }
+        if input.peek(LitStr) {
+            let lit: LitStr = input.parse()?;
+            if lit.value().contains(['=', ';']) {
+                return self.parse_packed(&lit);
+            }
+            set_once(
+                &mut self.level,
+                parse_enum("log level", &lit.value(), lit.span())?,
+                "level",
+                lit.span(),
+            )
+        } else {
+            let key: Ident = input.parse()?;
+            if key == "fields" && input.peek(Paren) {
+                let span = key.span();
+                set_once(&mut self.fields, parse_fields(input)?, "fields", span)
+            } else if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                if key == "handler" {
+                    let span = key.span();
+                    set_once(&mut self.handler, parse_handler(input)?, "handler", span)
+                } else if input.peek(Ident) || input.peek(Token![::]) {
+                    let path: Path = input.parse()?;
+                    self.set_level_path(&key, &path)
+                } else {
+                    let value: Lit = input.parse()?;
+                    self.set(&key, value)
+                }
+            } else {
+                self.set_flag(&key)
+            }
+        }
+    }
+
+    /// Applies a level given as a `tracing::Level` constant instead of a string, such as
+    /// `level = Level::INFO`, or the function of `level_fn`, which takes a path either way.
+    fn set_level_path(&mut self, key: &Ident, path: &Path) -> syn::Result<()> {
+        let span = key.span();
+        if key == "level_fn" {
+            return set_once(&mut self.level_fn, path.clone(), "level_fn", span);
+        }
+        let slot = match key.to_string().as_str() {
+            "level" => &mut self.level,
+            "err_level" => &mut self.err_level,
+            "span_level" => &mut self.span_level,
+            "slow_level" => &mut self.slow_level,
+            name if KEYS.contains(&name) => {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    format!(
+                        "Expected a literal for `{key}`, only levels can be given as a \
+                         `tracing::Level` constant"
+                    ),
+                ));
+            }
+            _ => return Err(unknown_argument(key)),
+        };
+        set_once(slot, level_constant(path)?, &key.to_string(), span)
+    }
+
+    /// Applies the arguments packed in a single string literal, such as
+    /// `"level=info;threshold=10ms;location"`. Segments are separated by `;`, and are either a
+    /// `key=value` pair or a flag. Values consisting only of digits are integers, and any other
+    /// value a string.
+    fn parse_packed(&mut self, lit: &LitStr) -> syn::Result<()> {
+        let packed = lit.value();
+        let span = lit.span();
+        let segments = packed.trim_end().strip_suffix(';').unwrap_or(&packed);
+        for segment in segments.split(';').map(str::trim) {
+            let (key, value) = match segment.split_once('=') {
+                Some((key, value)) => (key.trim_end(), Some(value.trim_start())),
+                None => (segment, None),
+            };
+            let applied = match syn::parse_str::<Ident>(key) {
+                Ok(mut key) => {
+                    key.set_span(span);
+                    match value {
+                        Some(value)
+                            if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) =>
+                        {
+                            self.set(&key, Lit::Int(LitInt::new(value, span)))
+                        }
+                        Some(value) => self.set(&key, Lit::Str(LitStr::new(value, span))),
+                        None => self.set_flag(&key),
+                    }
+                }
+                Err(_) => Err(syn::Error::new(
+                    span,
+                    "Expected a `key=value` pair or a flag, e.g. `\"level=info;location\"`",
+                )),
+            };
+            applied.map_err(|error| {
+                let mut invalid =
+                    syn::Error::new(span, format!("Invalid segment `{segment}` in {packed:?}"));
+                invalid.combine(error);
+                invalid
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Whether the `max_level_*` features filter out every event and span these arguments create,
+    /// so the code can be left unchanged. Functions also reporting through anything else, such as
+    /// `aggregate`, `metric` or `output`, are always timed.
+    pub(crate) fn statically_disabled(&self) -> bool {
+        if self.aggregate
+            || self.ring.is_some()
+            || self.quantiles
+            || self.metric.is_some()
+            || self.on_complete.is_some()
+            || self.handler.is_some()
+            || self.sink.is_some()
+            || self.collector.is_some()
+            || self.output.is_some()
+            || self.in_span
+            || self.otel
+            // The level is only known at runtime.
+            || self.level_fn.is_some()
+        {
+            return false;
+        }
+        let level = self.level.unwrap_or_default();
+        let mut levels = std::iter::once(level)
+            .chain(self.err_level)
+            .chain(self.span_level)
+            .chain(self.slow_level)
+            .chain(self.regression.map(|_| LogLevel::Warn))
+            .chain(self.warn_zero.then_some(LogLevel::Warn))
+            .chain(self.tiers.iter().flatten().map(|&(_, level)| level));
+        !levels.any(LogLevel::statically_enabled)
+    }
+
+    /// Rejects combinations of arguments that cannot be applied together, after resolving `repr`
+    /// into the `format` or `unit` it selects.
+    fn validate(&mut self) -> syn::Result<()> {
+        if let Some(repr) = self.repr {
+            if self.format.is_some() || self.unit.is_some() {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "`repr` cannot be combined with `format` or `unit`, which it replaces",
+                ));
+            }
+            match repr {
+                Repr::Debug => self.format = Some(DurationFormat::Debug),
+                Repr::Human => self.format = Some(DurationFormat::Human),
+                Repr::Aligned => self.format = Some(DurationFormat::Aligned),
+                Repr::SecsF64 => self.unit = Some(TimeUnit::S),
+                Repr::Nanos => self.unit = Some(TimeUnit::Ns),
+            }
+        }
+        if let Some(mode) = self.mode {
+            if self.span {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "`mode` cannot be combined with `span`, which `mode = \"span\"` stands for",
+                ));
+            }
+            self.span = mode == Mode::Span;
+        }
+        if let Some(record) = &self.record {
+            if self.in_span {
+                return Err(syn::Error::new_spanned(
+                    record,
+                    "`record` cannot be combined with `in_span`, which it implies",
+                ));
+            }
+            if record.value().is_empty() {
+                return Err(syn::Error::new_spanned(
+                    record,
+                    "Expected a field name for `record`, e.g. `record = \"elapsed_ms\"`",
+                ));
+            }
+            self.in_span = true;
+        }
+        if let Some(exclude) = self.exclude.as_ref().filter(|name| name.value().is_empty()) {
+            return Err(syn::Error::new_spanned(
+                exclude,
+                "Expected a name for `exclude`, e.g. `exclude = \"lock\"`",
+            ));
+        }
+        if let Some(category) = self
+            .category
+            .as_ref()
+            .filter(|name| name.value().is_empty())
+        {
+            return Err(syn::Error::new_spanned(
+                category,
+                "Expected a name for `category`, e.g. `category = \"network\"`",
+            ));
+        }
+        if let Some((key, _)) = self
+            .fields
+            .iter()
+            .flatten()
+            .filter(|_| self.category.is_some())
+            .find(|(key, _)| key == "category")
+        {
+            return Err(syn::Error::new_spanned(
+                key,
+                "`fields` cannot have a `category` key, which `category` sets",
+            ));
+        }
+        if let Some(name_field) = &self.name_field {
+            let name = name_field.value();
+            let mut chars = name.chars();
+            let identifier = chars
+                .next()
+                .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !identifier || name == "_" {
+                return Err(syn::Error::new_spanned(
+                    name_field,
+                    "Expected an identifier for `name_field`, e.g. `name_field = \"operation\"`",
+                ));
+            }
+            if !self.structured {
+                return Err(syn::Error::new_spanned(
+                    name_field,
+                    "`name_field` requires `structured`, as only structured events name the \
+                     function in a field",
+                ));
+            }
+        }
+        if let Some(level) = DEFAULT_LEVEL_VAR {
+            parse_enum::<LogLevel>("log level", level, Span::call_site()).map_err(|_| {
+                syn::Error::new(
+                    Span::call_site(),
+                    format!("Invalid log level `{level}` set in `TIME_IT_DEFAULT_LEVEL`"),
+                )
+            })?;
+        }
+        if let Some(unit) = DEFAULT_UNIT_VAR {
+            parse_enum::<TimeUnit>("time unit", unit, Span::call_site()).map_err(|_| {
+                syn::Error::new(
+                    Span::call_site(),
+                    format!("Invalid time unit `{unit}` set in `TIME_IT_DEFAULT_UNIT`"),
+                )
+            })?;
+        }
+        if self.both_clocks && (self.clock.is_some() || self.clock_fn.is_some()) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`both_clocks` cannot be combined with `clock` or `clock_fn`, as it measures with \
+                 both the wall and the CPU clock",
+            ));
+        }
+        if self.clock_fn.is_some() && self.clock.is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`clock_fn` cannot be combined with `clock`",
+            ));
+        }
+        if let (Some(_), Some(_)) = (&self.format, &self.unit) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`format` cannot be combined with `unit`",
+            ));
+        }
+        if self.ordering.is_some()
+            && !(self.seq
+                || self.aggregate
+                || self.ring.is_some()
+                || self.quantiles
+                || self.first.is_some()
+                || self.sample.is_some()
+                || self.summarize.is_some()
+                || self.rate_limit.is_some()
+                || self.regression.is_some())
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`ordering` requires `seq`, `aggregate`, `ring`, `quantiles`, `first`, `sample`, \
+                 `summarize`, `rate_limit` or `regression`, which use atomics",
+            ));
+        }
+        if self.top_only && !self.depth {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`top_only` requires `depth`, which tells the outermost calls apart",
+            ));
+        }
+        if self.slow_level.is_some() && self.threshold.is_none() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`slow_level` requires a `threshold`, the calls reaching it using the level",
+            ));
+        }
+        if self.log_err_value && self.err_level.is_none() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`log_err_value` requires an `err_level`, the events of failed calls carrying the \
+                 error",
+            ));
+        }
+        if self.slow_level.is_some() && self.tiers.is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`slow_level` cannot be combined with `tiers`, which also set the level of slow \
+                 calls",
+            ));
+        }
+        if self.regression.is_some() && (self.slow_level.is_some() || self.tiers.is_some()) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`regression` cannot be combined with `slow_level` or `tiers`, which also set the \
+                 level of slow calls",
+            ));
+        }
+        if self.regression.is_some() && self.span {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`regression` cannot be combined with `span`, which reports calls without an event",
+            ));
+        }
+        if let (Some(bytes_arg), true) = (&self.bytes_arg, self.bytes_ret) {
+            return Err(syn::Error::new_spanned(
+                bytes_arg,
+                "`bytes_arg` cannot be combined with `bytes_ret`, choose where the number of bytes \
+                 is taken from",
+            ));
+        }
+        if let Some(label_arg) = &self.label_arg {
+            if self.label.is_some() {
+                return Err(syn::Error::new_spanned(
+                    label_arg,
+                    "`label_arg` cannot be combined with `label`, which it replaces",
+                ));
+            }
+            if self.span {
+                return Err(syn::Error::new_spanned(
+                    label_arg,
+                    "`label_arg` cannot be combined with `span`, as span names are static",
+                ));
+            }
+            // Both are given the name as a `&'static str`.
+            for (enabled, arg) in [
+                (self.on_complete.is_some(), "on_complete"),
+                (self.collector.is_some(), "collector"),
+            ] {
+                if enabled {
+                    return Err(syn::Error::new_spanned(
+                        label_arg,
+                        format!(
+                            "`label_arg` cannot be combined with `{arg}`, which is given a \
+                             `&'static str` name"
+                        ),
+                    ));
+                }
+            }
+        }
+        if self.summarize.is_some() && self.threshold.is_none() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`summarize` requires a `threshold`, the calls below it being summarized",
+            ));
+        }
+        if self.precision.is_some()
+            && self.unit.is_none()
+            && !matches!(
+                self.format,
+                Some(DurationFormat::Human | DurationFormat::Aligned)
+            )
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`precision` requires `unit`, `format = \"human\"` or `format = \"aligned\"`",
+            ));
+        }
+        if self.width.is_some() && !matches!(self.format, Some(DurationFormat::Aligned)) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`width` requires `format = \"aligned\"`",
+            ));
+        }
+        if self.sig_figs.is_some() && !matches!(self.format, Some(DurationFormat::Human)) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`sig_figs` requires `format = \"human\"`",
+            ));
+        }
+        if self.min_unit.is_some() && !matches!(self.format, Some(DurationFormat::Human)) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`min_unit` requires `format = \"human\"`",
+            ));
+        }
+        if self.sig_figs.is_some() && self.precision.is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`sig_figs` cannot be combined with `precision`, which rounds to a number of \
+                 decimals instead",
+            ));
+        }
+        if self.fmt_fn.is_some()
+            && (self.format.is_some() || self.unit.is_some() || self.structured || self.span)
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`fmt_fn` cannot be combined with `format`, `unit`, `structured` or `span`",
+            ));
+        }
+        if self.structured && (self.format.is_some() || self.unit.is_some()) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`structured` cannot be combined with `format` or `unit`",
+            ));
+        }
+        if self.span && (self.err_level.is_some() || self.log_result) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`err_level` and `log_result` cannot be combined with `span`",
+            ));
+        }
+        if self.skip_err && (self.err_level.is_some() || self.err_template.is_some() || self.span) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`skip_err` cannot be combined with `err_level`, `err_template` or `span`",
+            ));
+        }
+        if self.span
+            && (self.structured
+                || self.threshold.is_some()
+                || self.format.is_some()
+                || self.unit.is_some())
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`span` cannot be combined with `structured`, `threshold`, `format` or `unit`",
+            ));
+        }
+        if self.output.is_some()
+            && (self.structured
+                || self.span
+                || self.in_span
+                || self.raw_ns
+                || self.kind
+                || self.chrome_trace
+                || self.fields.is_some()
+                || self.category.is_some())
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`output` cannot be combined with `structured`, `span`, `in_span`, `raw_ns`, \
+                 `kind`, `chrome_trace`, `fields` or `category`, which require `tracing`",
+            ));
+        }
+        if self.via.is_some() && (self.span || self.output.is_some()) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`via` cannot be combined with `span` or `output`",
+            ));
+        }
+        if self.parent.is_some()
+            && (self.span || self.in_span || self.via.is_some() || self.output.is_some())
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`parent` cannot be combined with `span`, `in_span`, `via` or `output`",
+            ));
+        }
+        if self.level_fn.is_some()
+            && (self.level.is_some() || self.span || self.via.is_some() || self.output.is_some())
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`level_fn` cannot be combined with `level`, `span`, `via` or `output`",
+            ));
+        }
+        if self.tiers.is_some() && self.span {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`tiers` cannot be combined with `span`",
+            ));
+        }
+        if self.span_level.is_some() && !self.span {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`span_level` requires `span`",
+            ));
+        }
+        if self.future && self.span {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`future` cannot be combined with `span`",
+            ));
+        }
+        if (self.first.is_some() || self.sample.is_some()) && self.span {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`first` and `sample` cannot be combined with `span`",
+            ));
+        }
+        if self.first.is_some() && self.sample.is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`first` cannot be combined with `sample`",
+            ));
+        }
+        if self.otel && self.span {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`otel` cannot be combined with `span`, whose `duration_ms` field is already \
+                 exported by `tracing-opentelemetry`",
+            ));
+        }
+        if self.chrome_trace && self.span {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`chrome_trace` cannot be combined with `span`, whose timings are already exported \
+                 by `tracing-chrome`",
+            ));
+        }
+        if self.include_drops && self.future {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`include_drops` cannot be combined with `future`, as the arguments are moved \
+                 into the returned future",
+            ));
+        }
+        if self.inner_fn && (self.include_drops || self.future) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`inner_fn` cannot be combined with `include_drops`, which it implies as the \
+                 arguments are moved into the nested function, or `future`",
+            ));
+        }
+        if self.trace_entry
+            && (self.span || self.first.is_some() || self.sample.is_some() || self.env_gate)
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`trace_entry` cannot be combined with `span`, `first`, `sample` or `env_gate`",
+            ));
+        }
+        if self.env_gate && self.span {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`env_gate` cannot be combined with `span`",
+            ));
+        }
+        if (self.template.is_some() || self.err_template.is_some())
+            && (self.structured || self.span)
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`template` and `err_template` cannot be combined with `structured` or `span`",
+            ));
+        }
+        if self.aggregate && (self.min_report.is_some() || self.sample.is_some()) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`min_report` and `sample` cannot be combined with `aggregate`, which counts every \
+                 call",
+            ));
+        }
+        if self.aggregate && (self.shapes_event() || self.threshold.is_some() || self.span) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`aggregate` does not emit events and can only be combined with `clock`, `metric`, \
+                 `otel`, `on_complete`, `collector`, `env_gate`, `first` and `debug_only`",
+            ));
+        }
+        if self.ring.is_some() && (self.shapes_event() || self.span || self.in_span) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`ring` does not emit events and can only be combined with `threshold`, \
+                 `min_report`, `first`, `sample`, `clock`, `metric`, `otel`, `on_complete`, \
+                 `collector`, `env_gate`, `aggregate` and `debug_only`",
+            ));
+        }
+        if self.silent
+            && (self.shapes_event()
+                || self.threshold.is_some()
+                || self.warn_zero
+                || self.span
+                || self.in_span
+                || self.handler.is_some()
+                || self.sink.is_some()
+                || self.output.is_some())
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`silent` does not emit events and can only be combined with `aggregate`, `ring`, \
+                 `quantiles`, `metric`, `otel`, `on_complete`, `collector`, `min_report`, `first`, \
+                 `sample`, `clock`, `env_gate` and `debug_only`",
+            ));
+        }
+        if self.silent
+            && !(self.aggregate
+                || self.ring.is_some()
+                || self.quantiles
+                || self.metric.is_some()
+                || self.otel
+                || self.on_complete.is_some()
+                || self.collector.is_some())
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`silent` requires somewhere to keep the measurements: `aggregate`, `ring`, \
+                 `quantiles`, `metric`, `otel`, `on_complete` or `collector`",
+            ));
+        }
+        if self.handler.is_some()
+            && (self.shapes_event()
+                || self.span
+                || self.in_span
+                || self.aggregate
+                || self.ring.is_some())
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`handler` replaces the event and can only be combined with `threshold`, \
+                 `min_report`, `first`, `sample`, `clock`, `metric`, `otel`, `on_complete`, \
+                 `collector`, `env_gate` and `debug_only`",
+            ));
+        }
+        if self.sink.is_some()
+            && (self.shapes_event()
+                || self.span
+                || self.in_span
+                || self.aggregate
+                || self.ring.is_some()
+                || self.handler.is_some())
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`sink` replaces the event and can only be combined with `threshold`, \
+                 `min_report`, `first`, `sample`, `clock`, `metric`, `otel`, `on_complete`, \
+                 `collector`, `env_gate` and `debug_only`",
+            ));
+        }
+        if self.in_span && (self.shapes_event() || self.span || self.aggregate) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`in_span` does not emit events and can only be combined with `threshold`, \
+                 `clock`, `metric`, `otel`, `on_complete`, `collector`, `env_gate`, `first`, \
+                 `sample` and `debug_only`",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether any argument only affecting the emitted event is given.
+    fn shapes_event(&self) -> bool {
+        self.level.is_some()
+            || self.level_fn.is_some()
+            || self.slow_level.is_some()
+            || self.rate_limit.is_some()
+            || self.regression.is_some()
+            || self.label.is_some()
+            || self.label_arg.is_some()
+            || self.name_field.is_some()
+            || self.format.is_some()
+            || self.unit.is_some()
+            || self.fmt_fn.is_some()
+            || self.via.is_some()
+            || self.parent.is_some()
+            || self.summarize.is_some()
+            || self.target.is_some()
+            || self.err_level.is_some()
+            || self.template.is_some()
+            || self.err_template.is_some()
+            || self.tiers.is_some()
+            || self.location
+            || self.structured
+            || self.log_result
+            || self.log_err_value
+            || self.log_args
+            || self.thread
+            || self.bytes_arg.is_some()
+            || self.bytes_ret
+            || self.runtime
+            || self.raw_ns
+            || self.seq
+            || self.corr_id
+            || self.allocs
+            || self.trace_entry
+            || self.kind
+            || self.chrome_trace
+            || self.on_cancel
+            || self.poll_count
+            || self.split_await
+            || self.since_start
+            || self.depth
+            || self.both_clocks
+            || self.fields.is_some()
+            || self.category.is_some()
+    }
+
+    /// Applies a single `key = value` argument.
+    fn set(&mut self, key: &Ident, value: Lit) -> syn::Result<()> {
+        let span = key.span();
+        match key.to_string().as_str() {
+            "level" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.level,
+                    parse_enum("log level", &lit.value(), lit.span())?,
+                    "level",
+                    span,
+                )
+            }
+            "label" | "name" => {
+                let lit = expect_str(key, value)?;
+                if self.label.is_some() {
+                    return Err(syn::Error::new(
+                        span,
+                        "Duplicate `label` argument, `name` is an alias of `label`",
+                    ));
+                }
+                self.label = Some(lit);
+                Ok(())
+            }
+            "label_arg" => {
+                let lit = expect_str(key, value)?;
+                set_once(&mut self.label_arg, lit, "label_arg", span)
+            }
+            "bytes_arg" => {
+                let lit = expect_str(key, value)?;
+                set_once(&mut self.bytes_arg, lit, "bytes_arg", span)
+            }
+            "record" => {
+                let lit = expect_str(key, value)?;
+                set_once(&mut self.record, lit, "record", span)
+            }
+            "name_field" => {
+                let lit = expect_str(key, value)?;
+                set_once(&mut self.name_field, lit, "name_field", span)
+            }
+            "exclude" => {
+                let lit = expect_str(key, value)?;
+                set_once(&mut self.exclude, lit, "exclude", span)
+            }
+            "threshold" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.threshold,
+                    parse_duration(&lit)?,
+                    "threshold",
+                    span,
+                )
+            }
+            "budget" => {
+                let lit = expect_str(key, value)?;
+                set_once(&mut self.budget, parse_duration(&lit)?, "budget", span)
+            }
+            "min_report" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.min_report,
+                    parse_duration(&lit)?,
+                    "min_report",
+                    span,
+                )
+            }
+            "format" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.format,
+                    parse_enum("duration format", &lit.value(), lit.span())?,
+                    "format",
+                    span,
+                )
+            }
+            "mode" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.mode,
+                    parse_enum("mode", &lit.value(), lit.span())?,
+                    "mode",
+                    span,
+                )
+            }
+            "repr" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.repr,
+                    parse_enum("duration representation", &lit.value(), lit.span())?,
+                    "repr",
+                    span,
+                )
+            }
+            "unit" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.unit,
+                    parse_enum("time unit", &lit.value(), lit.span())?,
+                    "unit",
+                    span,
+                )
+            }
+            "min_unit" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.min_unit,
+                    parse_enum("time unit", &lit.value(), lit.span())?,
+                    "min_unit",
+                    span,
+                )
+            }
+            "target" => set_once(&mut self.target, expect_str(key, value)?, "target", span),
+            "category" => set_once(
+                &mut self.category,
+                expect_str(key, value)?,
+                "category",
+                span,
+            ),
+            "err_level" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.err_level,
+                    parse_enum("log level", &lit.value(), lit.span())?,
+                    "err_level",
+                    span,
+                )
+            }
+            "slow_level" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.slow_level,
+                    parse_enum("log level", &lit.value(), lit.span())?,
+                    "slow_level",
+                    span,
+                )
+            }
+            "rate_limit" => {
+                let lit = expect_str(key, value)?;
+                let window = parse_duration(&lit)?;
+                if window.is_zero() {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "Expected a positive duration for `rate_limit`, e.g. `rate_limit = \"5s\"`",
+                    ));
+                }
+                set_once(&mut self.rate_limit, window, "rate_limit", span)
+            }
+            "output" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.output,
+                    parse_enum("output stream", &lit.value(), lit.span())?,
+                    "output",
+                    span,
+                )
+            }
+            "sink" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.sink,
+                    parse_enum("sink", &lit.value(), lit.span())?,
+                    "sink",
+                    span,
+                )
+            }
+            "span_level" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.span_level,
+                    parse_enum("log level", &lit.value(), lit.span())?,
+                    "span_level",
+                    span,
+                )
+            }
+            "clock" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.clock,
+                    parse_enum("clock", &lit.value(), lit.span())?,
+                    "clock",
+                    span,
+                )
+            }
+            "metric" => set_once(
+                &mut self.metric,
+                Some(expect_str(key, value)?),
+                "metric",
+                span,
+            ),
+            "on_complete" => set_once(
+                &mut self.on_complete,
+                parse_path(key, value)?,
+                "on_complete",
+                span,
+            ),
+            "level_fn" => set_once(
+                &mut self.level_fn,
+                parse_path(key, value)?,
+                "level_fn",
+                span,
+            ),
+            "fmt_fn" => set_once(&mut self.fmt_fn, parse_path(key, value)?, "fmt_fn", span),
+            "via" => {
+                let lit = expect_str(key, value)?;
+                let path = lit
+                    .parse::<Path>()
+                    .ok()
+                    // Macros can't be given generic arguments.
+                    .filter(|path| {
+                        path.segments
+                            .iter()
+                            .all(|segment| segment.arguments.is_none())
+                    })
+                    .ok_or_else(|| {
+                        syn::Error::new(
+                            lit.span(),
+                            "Expected a path to a macro without the `!`, e.g. `timing_log` or \
+                             `crate::logging::timing_log`",
+                        )
+                    })?;
+                set_once(&mut self.via, path, "via", span)
+            }
+            "parent" => {
+                let lit = expect_str(key, value)?;
+                let parent = lit.parse().map_err(|_| {
+                    syn::Error::new(
+                        lit.span(),
+                        "Expected an expression evaluating to the parent span, e.g. \
+                         `request_span()` or `&self.span`",
+                    )
+                })?;
+                set_once(&mut self.parent, parent, "parent", span)
+            }
+            "clock_fn" => set_once(
+                &mut self.clock_fn,
+                parse_path(key, value)?,
+                "clock_fn",
+                span,
+            ),
+            "collector" => set_once(
+                &mut self.collector,
+                parse_path(key, value)?,
+                "collector",
+                span,
+            ),
+            "tiers" => {
+                let lit = expect_str(key, value)?;
+                set_once(&mut self.tiers, parse_tiers(&lit)?, "tiers", span)
+            }
+            "template" => {
+                let lit = expect_str(key, value)?;
+                set_once(&mut self.template, parse_template(&lit)?, "template", span)
+            }
+            "err_template" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.err_template,
+                    parse_template(&lit)?,
+                    "err_template",
+                    span,
+                )
+            }
+            "first" => set_once(&mut self.first, expect_count(key, value)?, "first", span),
+            "sample" => set_once(&mut self.sample, expect_count(key, value)?, "sample", span),
+            "summarize" => set_once(
+                &mut self.summarize,
+                expect_count(key, value)?,
+                "summarize",
+                span,
+            ),
+            "ring" => set_once(&mut self.ring, expect_count(key, value)?, "ring", span),
+            "regression" => set_once(
+                &mut self.regression,
+                expect_factor(key, value)?,
+                "regression",
+                span,
+            ),
+            "fields" => Err(fields_error(span)),
+            "handler" => Err(handler_error(span)),
+            "ordering" => {
+                let lit = expect_str(key, value)?;
+                set_once(
+                    &mut self.ordering,
+                    parse_enum("memory ordering", &lit.value(), lit.span())?,
+                    "ordering",
+                    span,
+                )
+            }
+            "precision" => set_once(
+                &mut self.precision,
+                expect_precision(value)?,
+                "precision",
+                span,
+            ),
+            "sig_figs" => set_once(
+                &mut self.sig_figs,
+                expect_sig_figs(value)?,
+                "sig_figs",
+                span,
+            ),
+            "width" => set_once(&mut self.width, expect_width(value)?, "width", span),
+            other if FLAGS.contains(&other) => Err(syn::Error::new(
+                span,
+                format!("`{other}` is a flag and does not take a value"),
+            )),
+            _ => Err(unknown_argument(key)),
+        }
+    }
+
+    /// Applies a single flag argument.
+    fn set_flag(&mut self, key: &Ident) -> syn::Result<()> {
+        let span = key.span();
+        match key.to_string().as_str() {
+            "debug_only" => set_flag_once(&mut self.debug_only, "debug_only", span),
+            "location" => set_flag_once(&mut self.location, "location", span),
+            "structured" => set_flag_once(&mut self.structured, "structured", span),
+            "span" => set_flag_once(&mut self.span, "span", span),
+            "log_result" => set_flag_once(&mut self.log_result, "log_result", span),
+            "log_err_value" => set_flag_once(&mut self.log_err_value, "log_err_value", span),
+            "log_args" => set_flag_once(&mut self.log_args, "log_args", span),
+            "aggregate" => set_flag_once(&mut self.aggregate, "aggregate", span),
+            "quantiles" => set_flag_once(&mut self.quantiles, "quantiles", span),
+            "thread" => set_flag_once(&mut self.thread, "thread", span),
+            "runtime" => set_flag_once(&mut self.runtime, "runtime", span),
+            "bytes_ret" => set_flag_once(&mut self.bytes_ret, "bytes_ret", span),
+            "silent" => set_flag_once(&mut self.silent, "silent", span),
+            "env_gate" => set_flag_once(&mut self.env_gate, "env_gate", span),
+            "in_span" => set_flag_once(&mut self.in_span, "in_span", span),
+            "future" => set_flag_once(&mut self.future, "future", span),
+            "raw_ns" => set_flag_once(&mut self.raw_ns, "raw_ns", span),
+            "seq" => set_flag_once(&mut self.seq, "seq", span),
+            "corr_id" => set_flag_once(&mut self.corr_id, "corr_id", span),
+            "otel" => set_flag_once(&mut self.otel, "otel", span),
+            "allocs" => set_flag_once(&mut self.allocs, "allocs", span),
+            "trace_entry" => set_flag_once(&mut self.trace_entry, "trace_entry", span),
+            "kind" => set_flag_once(&mut self.kind, "kind", span),
+            "chrome_trace" => set_flag_once(&mut self.chrome_trace, "chrome_trace", span),
+            "include_drops" => set_flag_once(&mut self.include_drops, "include_drops", span),
+            "inner_fn" => set_flag_once(&mut self.inner_fn, "inner_fn", span),
+            "on_cancel" => set_flag_once(&mut self.on_cancel, "on_cancel", span),
+            "poll_count" => set_flag_once(&mut self.poll_count, "poll_count", span),
+            "split_await" => set_flag_once(&mut self.split_await, "split_await", span),
+            "exclude_idle" => set_flag_once(&mut self.exclude_idle, "exclude_idle", span),
+            "skip_err" => set_flag_once(&mut self.skip_err, "skip_err", span),
+            "since_start" => set_flag_once(&mut self.since_start, "since_start", span),
+            "depth" => set_flag_once(&mut self.depth, "depth", span),
+            "top_only" => set_flag_once(&mut self.top_only, "top_only", span),
+            "both_clocks" => set_flag_once(&mut self.both_clocks, "both_clocks", span),
+            "warn_zero" => set_flag_once(&mut self.warn_zero, "warn_zero", span),
+            "skip" => Err(syn::Error::new(
+                span,
+                "`skip` must be the only argument, e.g. `#[time_it(skip)]`",
+            )),
+            "fields" => Err(fields_error(span)),
+            "handler" => Err(handler_error(span)),
+            // `metric` doubles as a flag, naming the histogram after the function.
+            "metric" => set_once(&mut self.metric, None, "metric", span),
+            "summarize" => set_once(
+                &mut self.summarize,
+                DEFAULT_SUMMARY_CALLS,
+                "summarize",
+                span,
+            ),
+            other if KEYS.contains(&other) => Err(syn::Error::new(
+                span,
+                format!("Expected a value for `{other}`, e.g. `{other} = \"...\"`"),
+            )),
+            // No flag is named after a level, so the level can also be given as a bare identifier.
+            other => match other.parse() {
+                Ok(level) => set_once(&mut self.level, level, "level", span),
+                Err(_) => Err(unknown_argument(key)),
+            },
+        }
+    }
+}
+
+/// Parses the parenthesized list of `fields`, such as `(component = "db", critical = true)`.
+fn parse_fields(input: ParseStream) -> syn::Result<Vec<(Ident, Lit)>> {
+    let content;
+    syn::parenthesized!(content in input);
+    let mut fields: Vec<(Ident, Lit)> = Vec::new();
+    while !content.is_empty() {
+        let key: Ident = content.parse()?;
+        content.parse::<Token![=]>()?;
+        let value: Lit = content.parse()?;
+        if !matches!(
+            value,
+            Lit::Str(_) | Lit::Int(_) | Lit::Float(_) | Lit::Bool(_)
+        ) {
+            return Err(syn::Error::new(
+                value.span(),
+                format!("Expected a string, integer, float or bool literal for field `{key}`"),
+            ));
+        }
+        if fields.iter().any(|(existing, _)| *existing == key) {
+            return Err(syn::Error::new(
+                key.span(),
+                format!("Duplicate field `{key}`"),
+            ));
+        }
+        fields.push((key, value));
+        if content.is_empty() {
+            break;
+        }
+        content.parse::<Token![,]>()?;
+    }
+    Ok(fields)
+}
+
+/// Error for `fields` given without its parenthesized list.
+fn fields_error(span: Span) -> syn::Error {
+    syn::Error::new(
+        span,
+        "Expected a list of fields, e.g. `fields(component = \"db\", critical = true)`",
+    )
+}
+
+/// Parses the closure given to `handler`, which must take the name and the duration.
+fn parse_handler(input: ParseStream) -> syn::Result<ExprClosure> {
+    let span = input.span();
+    let handler: ExprClosure = input.parse().map_err(|_| handler_error(span))?;
+    if handler.inputs.len() != 2 {
+        return Err(syn::Error::new_spanned(
+            &handler.inputs,
+            format!(
+                "Expected the `handler` closure to take 2 arguments, the name and the duration, \
+                 found {}",
+                handler.inputs.len()
+            ),
+        ));
+    }
+    if let Some(asyncness) = &handler.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "The `handler` closure is called as the timed code completes and can't be `async`",
+        ));
+    }
+    Ok(handler)
+}
+
+/// Error for `handler` given without a closure.
+fn handler_error(span: Span) -> syn::Error {
+    syn::Error::new(
+        span,
+        "Expected a closure taking the name and duration, e.g. \
+         `handler = |name, duration| println!(\"{name}: {duration:?}\")`",
+    )
+}
+
+/// Whether `input` only consists of the `skip` flag, with an optional trailing comma.
+fn is_skip(input: ParseStream) -> bool {
+    let fork = input.fork();
+    fork.parse::<Ident>().is_ok_and(|ident| ident == "skip")
+        && fork.parse::<Option<Token![,]>>().is_ok()
+        && fork.is_empty()
+}
+
+/// Maps a `tracing::Level` constant, such as `Level::INFO` or `tracing::Level::INFO`, to its level.
+///
+/// The path is only matched by name, as `tracing` may not even be a dependency with the `log`
+/// backend.
+fn level_constant(path: &Path) -> syn::Result<LogLevel> {
+    let mut segments = path.segments.iter().rev();
+    let level = match (segments.next(), segments.next()) {
+        (Some(constant), Some(ty)) if ty.ident == "Level" && ty.arguments.is_none() => {
+            match constant.ident.to_string().as_str() {
+                "TRACE" => Some(LogLevel::Trace),
+                "DEBUG" => Some(LogLevel::Debug),
+                "INFO" => Some(LogLevel::Info),
+                "WARN" => Some(LogLevel::Warn),
+                "ERROR" => Some(LogLevel::Error),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    level.ok_or_else(|| {
+        syn::Error::new_spanned(
+            path,
+            "Expected a `tracing::Level` constant, one of: `Level::TRACE`, `Level::DEBUG`, \
+             `Level::INFO`, `Level::WARN` or `Level::ERROR`",
+        )
+    })
+}
+
+/// Error for an argument name that is neither a known key nor a known flag.
+fn unknown_argument(key: &Ident) -> syn::Error {
+    let expected: Vec<&str> = KEYS.iter().chain(FLAGS).copied().collect();
+    syn::Error::new(
+        key.span(),
+        format!(
+            "Unknown macro argument `{key}`, expected one of: {}",
+            expected.join(", ")
+        ),
+    )
+}
+
+/// Stores `value` in `slot`, failing if the argument was already given.
+fn set_once<T>(slot: &mut Option<T>, value: T, name: &str, span: Span) -> syn::Result<()> {
+    if slot.replace(value).is_some() {
+        return Err(syn::Error::new(
+            span,
+            format!("Duplicate `{name}` argument"),
+        ));
+    }
+    Ok(())
+}
+
+/// Sets `flag`, failing if the flag was already given.
+fn set_flag_once(flag: &mut bool, name: &str, span: Span) -> syn::Result<()> {
+    if std::mem::replace(flag, true) {
+        return Err(syn::Error::new(
+            span,
+            format!("Duplicate `{name}` argument"),
+        ));
+    }
+    Ok(())
+}
+
+/// Ensures the value of `key` is a string literal.
+fn expect_str(key: &Ident, value: Lit) -> syn::Result<LitStr> {
+    match value {
+        Lit::Str(lit) => Ok(lit),
+        other => Err(syn::Error::new(
+            other.span(),
+            format!("Expected a string literal for `{key}`"),
+        )),
+    }
+}
+
+/// Parses the value of `key`, a string literal naming the function or collector it calls.
+fn parse_path(key: &Ident, value: Lit) -> syn::Result<Path> {
+    let lit = expect_str(key, value)?;
+    lit.parse().map_err(|_| {
+        let expected = match key.to_string().as_str() {
+            "level_fn" => "a function, e.g. `current_level` or `crate::config::current_level`",
+            "fmt_fn" => "a function, e.g. `format` or `crate::timings::format`",
+            "clock_fn" => "a function, e.g. `now` or `crate::clock::now`",
+            "collector" => "a collector, e.g. `STATS` or `crate::timings::STATS`",
+            _ => "a function, e.g. `record` or `crate::timings::record`",
+        };
+        syn::Error::new(lit.span(), format!("Expected a path to {expected}"))
+    })
+}
+
+/// Ensures the value of `key` is an integer literal of at least 1.
+fn expect_count(key: &Ident, value: Lit) -> syn::Result<u64> {
+    let count = match &value {
+        Lit::Int(lit) => lit.base10_parse().ok().filter(|&count| count > 0),
+        _ => None,
+    };
+    count.ok_or_else(|| {
+        syn::Error::new(
+            value.span(),
+            format!("Expected a positive integer for `{key}`, e.g. `{key} = 10`"),
+        )
+    })
+}
+
+/// Ensures the value of `key` is a number greater than 1, given as a float or integer literal, or
+/// as a string in packed arguments.
+fn expect_factor(key: &Ident, value: Lit) -> syn::Result<f64> {
+    let factor = match &value {
+        Lit::Float(lit) => lit.base10_parse().ok(),
+        Lit::Int(lit) => lit.base10_parse().ok(),
+        Lit::Str(lit) => lit.value().parse().ok(),
+        _ => None,
+    };
+    factor
+        .filter(|&factor: &f64| factor.is_finite() && factor > 1.0)
+        .ok_or_else(|| {
+            syn::Error::new(
+                value.span(),
+                format!("Expected a number greater than 1 for `{key}`, e.g. `{key} = 3.0`"),
+            )
+        })
+}
+
+/// Ensures the value of `precision` is an integer literal from 0 to 9, the number of decimals
+/// down to nanoseconds in seconds.
+fn expect_precision(value: Lit) -> syn::Result<u8> {
+    let precision = match &value {
+        Lit::Int(lit) => lit.base10_parse().ok().filter(|&precision| precision <= 9),
+        _ => None,
+    };
+    precision.ok_or_else(|| {
+        syn::Error::new(
+            value.span(),
+            "Expected an integer from 0 to 9 for `precision`, e.g. `precision = 2`",
+        )
+    })
+}
+
+/// Ensures the value of `sig_figs` is an integer literal from 1 to 6, the number of significant
+/// figures.
+fn expect_sig_figs(value: Lit) -> syn::Result<u8> {
+    let figures = match &value {
+        Lit::Int(lit) => lit
+            .base10_parse()
+            .ok()
+            .filter(|figures| (1..=6).contains(figures)),
+        _ => None,
+    };
+    figures.ok_or_else(|| {
+        syn::Error::new(
+            value.span(),
+            "Expected an integer from 1 to 6 for `sig_figs`, e.g. `sig_figs = 3`",
+        )
+    })
+}
+
+/// Ensures the value of `width` is an integer literal from 1 to 32, the minimum number of
+/// characters of the number.
+fn expect_width(value: Lit) -> syn::Result<u8> {
+    let width = match &value {
+        Lit::Int(lit) => lit
+            .base10_parse()
+            .ok()
+            .filter(|width| (1..=32).contains(width)),
+        _ => None,
+    };
+    width.ok_or_else(|| {
+        syn::Error::new(
+            value.span(),
+            "Expected an integer from 1 to 32 for `width`, e.g. `width = 9`",
+        )
+    })
+}
+
+/// Parses a string into one of the argument enums, reporting errors at `span`. `kind` names the
+/// expected value in diagnostics, e.g. `log level`.
+fn parse_enum<T>(kind: &str, value: &str, span: Span) -> syn::Result<T>
+where
+    T: FromStr + VariantNames,
+{
+    value.parse().map_err(|_| {
+        let expected = T::VARIANTS.join(", ");
+        let message = if value.is_empty() {
+            format!("empty {kind}; expected one of: {expected}")
+        } else {
+            format!("unknown {kind} \"{value}\"; expected one of: {expected}")
+        };
+        syn::Error::new(span, message)
+    })
+}
+
+/// Parses a duration literal such as `"50ms"`, `"1s"` or `"200us"`.
+fn parse_duration(lit: &LitStr) -> syn::Result<Duration> {
+    duration_from_str(&lit.value(), lit.span())
+}
+
+/// Parses a duration such as `50ms`, reporting errors at `span`.
+///
+/// Durations are a whole amount directly followed by one of the units `ns`, `us` (or `µs`), `ms`,
+/// `s` and `m`, for minutes. This is shared by every argument taking a duration.
+fn duration_from_str(value: &str, span: Span) -> syn::Result<Duration> {
+    let error = |message: String| Err(syn::Error::new(span, message));
+    if value.contains(char::is_whitespace) {
+        return error(format!(
+            "Unexpected whitespace in duration {value:?}, write the amount and unit together, \
+             e.g. {:?}",
+            value.split_whitespace().collect::<String>()
+        ));
+    }
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let Ok(amount) = amount.parse::<u64>() else {
+        return error(format!(
+            "Expected a duration such as \"50ms\", \"1s\" or \"200us\", found {value:?}"
+        ));
+    };
+    match unit {
+        "ns" => Ok(Duration::from_nanos(amount)),
+        "us" | "µs" => Ok(Duration::from_micros(amount)),
+        "ms" => Ok(Duration::from_millis(amount)),
+        "s" => Ok(Duration::from_secs(amount)),
+        "m" => match amount.checked_mul(60) {
+            Some(secs) => Ok(Duration::from_secs(secs)),
+            None => error(format!("The duration {value:?} is too long")),
+        },
+        _ if unit.starts_with('.') => error(format!(
+            "Expected a whole amount in duration {value:?}, use a smaller unit instead, e.g. \
+             \"1500us\" for \"1.5ms\""
+        )),
+        "" => error(format!(
+            "Missing unit in duration {value:?}, e.g. \"{amount}ms\", expected one of `ns`, \
+             `us`, `ms`, `s` or `m`"
+        )),
+        _ => error(format!(
+            "Unknown duration unit `{unit}`, expected one of `ns`, `us`, `ms`, `s` or `m`"
+        )),
+    }
+}
+
+/// Parses a list of tiers such as `"10ms:debug,100ms:warn"`, each giving the level used from
+/// that duration on.
+fn parse_tiers(lit: &LitStr) -> syn::Result<Vec<(Duration, LogLevel)>> {
+    let span = lit.span();
+    let mut tiers: Vec<(Duration, LogLevel)> = Vec::new();
+    for tier in lit.value().split(',') {
+        let Some((duration, level)) = tier.trim().split_once(':') else {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "Expected a tier such as `10ms:debug`, found `{}`",
+                    tier.trim()
+                ),
+            ));
+        };
+        let duration = duration_from_str(duration.trim(), span)?;
+        let level = parse_enum("log level", level.trim(), span)?;
+        if tiers
+            .last()
+            .is_some_and(|(previous, _)| duration <= *previous)
+        {
+            return Err(syn::Error::new(
+                span,
+                "Tier durations must be strictly increasing",
+            ));
+        }
+        tiers.push((duration, level));
+    }
+    Ok(tiers)
+}
+
+/// Parses a message template such as `"{name} took {duration}"` into its pieces.
+fn parse_template(lit: &LitStr) -> syn::Result<Vec<TemplatePiece>> {
+    let value = lit.value();
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push_str("{{");
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push_str("}}");
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                "Unclosed `{` in template. Use `{{` for a literal brace",
+                            ));
+                        }
+                    }
+                }
+                let piece = match placeholder.as_str() {
+                    "name" => TemplatePiece::Name,
+                    "duration" => TemplatePiece::Duration,
+                    other => {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            format!(
+                                "Unknown placeholder `{{{other}}}`, expected `{{name}}` or \
+                                 `{{duration}}`. Use `{{{{` and `}}}}` for literal braces"
+                            ),
+                        ));
+                    }
+                };
+                pieces.push(TemplatePiece::Text(std::mem::take(&mut text)));
+                pieces.push(piece);
+            }
+            '}' => {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "Unmatched `}` in template. Use `}}` for a literal brace",
+                ));
+            }
+            c => text.push(c),
+        }
+    }
+    pieces.push(TemplatePiece::Text(text));
+    Ok(pieces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Result<Duration, String> {
+        duration_from_str(value, Span::call_site()).map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn every_unit_is_parsed() {
+        assert_eq!(parse("50ns"), Ok(Duration::from_nanos(50)));
+        assert_eq!(parse("50us"), Ok(Duration::from_micros(50)));
+        assert_eq!(parse("50µs"), Ok(Duration::from_micros(50)));
+        assert_eq!(parse("50ms"), Ok(Duration::from_millis(50)));
+        assert_eq!(parse("50s"), Ok(Duration::from_secs(50)));
+        assert_eq!(parse("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse("0ms"), Ok(Duration::ZERO));
+    }
+
+    #[test]
+    fn whitespace_is_rejected_with_the_joined_duration() {
+        let err = parse("50 ms").unwrap_err();
+        assert!(err.starts_with("Unexpected whitespace"), "{err}");
+        assert!(err.contains("\"50ms\""), "{err}");
+    }
+
+    #[test]
+    fn a_missing_unit_is_rejected() {
+        let err = parse("50").unwrap_err();
+        assert!(err.starts_with("Missing unit"), "{err}");
+        assert!(err.contains("\"50ms\""), "{err}");
+    }
+
+    #[test]
+    fn a_missing_amount_is_rejected() {
+        for value in ["", "ms"] {
+            let err = parse(value).unwrap_err();
+            assert!(err.starts_with("Expected a duration"), "{value:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn unknown_units_are_rejected() {
+        let err = parse("50h").unwrap_err();
+        assert!(err.starts_with("Unknown duration unit `h`"), "{err}");
+    }
+
+    #[test]
+    fn fractional_amounts_are_rejected() {
+        let err = parse("1.5ms").unwrap_err();
+        assert!(err.starts_with("Expected a whole amount"), "{err}");
+    }
+
+    #[test]
+    fn overflowing_amounts_are_rejected() {
+        let err = parse("18446744073709551616ns").unwrap_err();
+        assert!(err.starts_with("Expected a duration"), "{err}");
+        assert_eq!(
+            parse("307445734561825860m"),
+            Ok(Duration::from_secs(307_445_734_561_825_860 * 60))
+        );
+        let err = parse("307445734561825861m").unwrap_err();
+        assert!(err.contains("is too long"), "{err}");
+    }
+}
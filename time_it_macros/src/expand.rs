@@ -0,0 +1,3357 @@
+//! Generation of the instrumented functions and blocks.
+
+use std::time::Duration;
+
+use proc_macro2::{Literal, Span, TokenStream, TokenTree};
+use quote::{ToTokens, format_ident, quote, quote_spanned};
+use syn::{
+    Block, Expr, ExprClosure, FnArg, Ident, ImplItem, Item, ItemFn, ItemImpl, ItemMod, LitStr, Pat,
+    PatType, Path, ReturnType, Signature, Stmt, TraitItemFn, Type, ext::IdentExt, parse_quote,
+    spanned::Spanned,
+};
+
+use crate::args::{Args, Clock, DurationFormat, LogLevel, MemoryOrdering, TemplatePiece, TimeUnit};
+
+/// Target of the events and spans not given a `target`, so filters such as
+/// `RUST_LOG=time_it::measurement=info` select exactly them. It can't be the module path of any
+/// timed code, as the `time_it` crate has no `measurement` module.
+const DEFAULT_TARGET: &str = "time_it::measurement";
+
+/// Default minimum width of the number of milliseconds with `format = "aligned"`, enough for up
+/// to 99 999.999ms.
+const ALIGNED_WIDTH: u8 = 9;
+
+/// Default number of decimals with `format = "aligned"`, down to microseconds.
+const ALIGNED_PRECISION: u8 = 3;
+
+/// Target replacing [`DEFAULT_TARGET`] for the events and spans not given a `target`.
+///
+/// Read when this crate is compiled, like `TIME_IT_DEFAULT_LEVEL`.
+const DEFAULT_TARGET_VAR: Option<&str> = option_env!("TIME_IT_DEFAULT_TARGET");
+
+/// Everything event generation needs to know about the timed function or block.
+struct Context<'a> {
+    args: &'a Args,
+    /// Expression naming the timed code in events.
+    name: TokenStream,
+    /// Span the reported source location is taken from.
+    location: Span,
+    backend: Backend,
+    /// Whether the returned value is logged; never the case for `()` returning functions.
+    log_result: bool,
+    /// Whether the arguments are logged; never the case for functions without named arguments.
+    log_args: bool,
+    /// The static accumulating the calls in `aggregate` mode, instead of emitting events.
+    stats: Option<Ident>,
+    /// The static keeping the most recent durations in `ring` mode, instead of emitting events.
+    ring: Option<Ident>,
+    /// The static holding the histogram of `quantiles` mode.
+    quantiles: Option<Ident>,
+    /// Name of the `metrics` histogram each measurement is recorded into, if any.
+    metric: Option<TokenStream>,
+    /// Environment variable that must be set for anything to be reported, in `env_gate` mode.
+    env_gate: Option<String>,
+    /// Value of the `kind` field, `"async"` or `"sync"`, in `kind` mode.
+    kind: Option<&'static str>,
+}
+
+/// Expands `input` into the same function with its execution timed according to `args`.
+pub(crate) fn time_it(args: &Args, input: &ItemFn) -> syn::Result<TokenStream> {
+    // Attributes expand outermost first, so a nested `#[time_it]` is still in place. Wrapping it
+    // would time its reporting too, and report every call twice.
+    if let Some(nested) = input
+        .attrs
+        .iter()
+        .find(|attr| is_time_it(attr) && !attr.parse_args::<Args>().is_ok_and(|args| args.skip))
+    {
+        return Err(syn::Error::new_spanned(
+            nested,
+            "`#[time_it]` applied multiple times, merge the arguments into a single attribute",
+        ));
+    }
+    let rebound;
+    let input = if args.include_drops {
+        rebound = rebind_args(input);
+        &rebound
+    } else {
+        input
+    };
+    // The arguments are logged under the names they are declared with, which `inner_fn` may
+    // replace.
+    let declared = &input.sig;
+    let hoisted;
+    let (input, inner_fn) = if args.inner_fn {
+        hoisted = hoist_body(input)?;
+        (&hoisted.0, Some(&hoisted.1))
+    } else {
+        (input, None)
+    };
+    let fn_name = &input.sig.ident;
+    let fn_block = &input.block;
+    let fn_vis = &input.vis;
+    let fn_sig = &input.sig;
+    let fn_attrs = &input.attrs;
+    let asyncness = &input.sig.asyncness;
+
+    if let Some(constness) = &fn_sig.constness {
+        return Err(syn::Error::new_spanned(
+            constness,
+            "`const fn`s cannot be timed, as reading the clock is not possible in const contexts",
+        ));
+    }
+    let backend = Backend::current();
+    check_features(args, backend)?;
+    let clock = args.clock.unwrap_or_default();
+    if let (Clock::Cpu, Some(asyncness)) = (clock, asyncness) {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "`clock = \"cpu\"` is not supported on async functions, which can move between \
+             threads while suspended",
+        ));
+    }
+    let returned_future = if args.future {
+        Some(returned_future(fn_sig)?)
+    } else {
+        None
+    };
+    if let (Clock::Cpu, true) = (clock, args.future) {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`clock = \"cpu\"` is not supported with `future`, as futures can move between \
+             threads while suspended",
+        ));
+    }
+    if let (true, Some(asyncness)) = (args.both_clocks, asyncness) {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "`both_clocks` is not supported on async functions, which can move between threads \
+             while suspended",
+        ));
+    }
+    if args.both_clocks && args.future {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`both_clocks` is not supported with `future`, as futures can move between threads \
+             while suspended",
+        ));
+    }
+    if let (true, Some(asyncness)) = (args.allocs, asyncness) {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "`allocs` is not supported on async functions, which can move between threads while \
+             suspended",
+        ));
+    }
+    if args.allocs && args.future {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`allocs` is not supported with `future`, as futures can move between threads while \
+             suspended",
+        ));
+    }
+    if args.on_cancel && asyncness.is_none() && !args.future {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`on_cancel` requires an async function or `future`, as only futures can be dropped \
+             before completing",
+        ));
+    }
+    if args.poll_count && asyncness.is_none() && !args.future {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`poll_count` requires an async function or `future`, as only futures are polled",
+        ));
+    }
+    if args.runtime && asyncness.is_none() && !args.future {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`runtime` requires an async function or `future`, as only futures are run by a \
+             runtime",
+        ));
+    }
+    if args.split_await && asyncness.is_none() && !args.future {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`split_await` requires an async function or `future`, as only futures suspend",
+        ));
+    }
+    if args.exclude_idle && asyncness.is_none() && !args.future {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`exclude_idle` requires an async function or `future`, as only futures wait between \
+             polls",
+        ));
+    }
+    if args.depth && (asyncness.is_some() || args.future) {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`depth` requires a synchronous function, as the depth is counted per thread and \
+             futures can move between threads and interleave",
+        ));
+    }
+    if args.exclude.is_some() && (asyncness.is_some() || args.future) {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`exclude` requires a synchronous function, as exclusions are counted per thread and \
+             futures can move between threads and interleave",
+        ));
+    }
+    for (enabled, arg) in [
+        (args.err_level.is_some(), "err_level"),
+        (args.err_template.is_some(), "err_template"),
+        (args.skip_err, "skip_err"),
+    ] {
+        if enabled && !returns_result(&fn_sig.output) {
+            return Err(syn::Error::new_spanned(
+                &fn_sig.output,
+                format!("`{arg}` requires a function returning `Result`"),
+            ));
+        }
+    }
+
+    // Arguments are formatted when the function is entered, as the body may move them.
+    let logged_args = if args.log_args {
+        named_args(declared)
+    } else {
+        Vec::new()
+    };
+    let capture_args = (!logged_args.is_empty()).then(|| {
+        let format = logged_args
+            .iter()
+            .map(|arg| format!("{arg} = {{:?}}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        // Arguments named `_name` are logged too, although they are otherwise unused.
+        quote! {
+            #[allow(clippy::used_underscore_binding)]
+            let __args = ::std::format!(#format, #(#logged_args),*);
+        }
+    });
+    // The value is formatted when the function is entered too, so the events can name the call
+    // after it.
+    let capture_label = match &args.label_arg {
+        Some(label_arg) => {
+            let arg = named_arg(declared, label_arg, "label_arg")?;
+            Some(quote_spanned! {arg.span()=>
+                let __label = ::std::string::ToString::to_string(&#arg);
+            })
+        }
+        None => None,
+    };
+    // Negative amounts, or ones beyond `u64`, leave the throughput unknown. Spanned at the
+    // argument so a non-integer one is reported there. The conversion is only fallible for some
+    // integer types, and does nothing for `u64`, but all of them are accepted the same way.
+    let capture_bytes = match &args.bytes_arg {
+        Some(bytes_arg) => {
+            let arg = named_arg(declared, bytes_arg, "bytes_arg")?;
+            Some(quote_spanned! {arg.span()=>
+                #[allow(clippy::unnecessary_fallible_conversions, clippy::useless_conversion)]
+                let __bytes = <u64 as ::core::convert::TryFrom<_>>::try_from(#arg).ok();
+            })
+        }
+        None => None,
+    };
+    if args.bytes_ret && returns_nothing(&fn_sig.output) {
+        return Err(syn::Error::new_spanned(
+            &fn_sig.output,
+            "`bytes_ret` requires a function returning the number of bytes, or a `Result` of it",
+        ));
+    }
+    if args.bytes_ret && args.future {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`bytes_ret` is not supported with `future`, as the output of the future isn't part \
+             of the signature, use an `async fn` instead",
+        ));
+    }
+
+    let seq = args.seq.then(|| seq_number(args));
+    let corr_id = args.corr_id.then(corr_id);
+    let parent = args.parent.as_ref().map(parent);
+    let depth = args.depth.then(call_depth);
+    let cx = Context {
+        args,
+        name: match (&args.label, &capture_label) {
+            (Some(label), _) => quote! {#label},
+            (None, Some(_)) => quote! {__label.as_str()},
+            (None, None) => quote! {::core::stringify!(#fn_name)},
+        },
+        location: fn_name.span(),
+        backend,
+        // The value of `()` returning functions carries no information, and diverging functions
+        // have none, so neither is logged.
+        log_result: args.log_result && !returns_nothing(&fn_sig.output),
+        log_args: capture_args.is_some(),
+        stats: args.aggregate.then(|| {
+            let name = fn_name.unraw().to_string().to_uppercase();
+            format_ident!("__TIME_IT_STATS_{}", name)
+        }),
+        ring: args.ring.map(|_| {
+            let name = fn_name.unraw().to_string().to_uppercase();
+            format_ident!("__TIME_IT_RING_{}", name)
+        }),
+        quantiles: args.quantiles.then(|| {
+            let name = fn_name.unraw().to_string().to_uppercase();
+            format_ident!("__TIME_IT_QUANTILES_{}", name)
+        }),
+        metric: args.metric.as_ref().map(|metric| match metric {
+            Some(name) => quote! {#name},
+            None => quote! {::core::stringify!(#fn_name)},
+        }),
+        env_gate: args
+            .env_gate
+            .then(|| env_gate_var(&fn_name.unraw().to_string())),
+        // Functions timed in `future` mode are measured like `async fn`s.
+        kind: args.kind.then_some(if asyncness.is_some() || args.future {
+            "async"
+        } else {
+            "sync"
+        }),
+    };
+    let span = args.span.then(|| cx.span());
+    let entry = args
+        .trace_entry
+        .then(|| switched(cx.top_only(quote! {__depth}, cx.entry_event())));
+    let log_line = cx.log_line();
+
+    let tracing = tracing_crate();
+    let mut fields = Vec::new();
+    if args.span {
+        fields.push(GuardField {
+            ident: ident("__span"),
+            ty: quote! {#tracing::Span},
+            init: quote! {::core::clone::Clone::clone(&__span)},
+        });
+    }
+    if cx.log_args && !args.span {
+        fields.push(GuardField {
+            ident: ident("__args"),
+            ty: quote! {::std::string::String},
+            init: quote! {__args},
+        });
+    }
+    if capture_label.is_some() {
+        fields.push(GuardField {
+            ident: ident("__label"),
+            ty: quote! {::std::string::String},
+            init: quote! {__label},
+        });
+    }
+    if args.seq && !args.span {
+        fields.push(seq_field());
+    }
+    if args.corr_id && !args.span {
+        fields.push(corr_id_field());
+    }
+    if args.parent.is_some() {
+        fields.push(parent_field());
+    }
+    if args.bytes_arg.is_some() || args.bytes_ret {
+        fields.push(GuardField {
+            ident: ident("__bytes"),
+            ty: quote! {::core::option::Option<u64>},
+            init: if args.bytes_ret {
+                quote! {::core::option::Option::None}
+            } else {
+                quote! {__bytes}
+            },
+        });
+    }
+    if args.depth {
+        fields.push(GuardField {
+            ident: ident("__depth"),
+            ty: quote! {u64},
+            init: quote! {__depth},
+        });
+    }
+    if args.allocs {
+        fields.push(allocs_field());
+    }
+    if args.both_clocks {
+        fields.push(cpu_start_field());
+    }
+    if args.chrome_trace {
+        fields.push(start_time_field());
+    }
+    if args.since_start {
+        fields.push(since_start_field());
+    }
+    if args.poll_count {
+        fields.push(GuardField {
+            ident: ident("__polls"),
+            ty: quote! {u64},
+            init: quote! {0},
+        });
+    }
+    // The time spent in the polls so far, reported instead of the elapsed time.
+    if args.exclude_idle {
+        fields.push(GuardField {
+            ident: ident("__busy"),
+            ty: quote! {::core::time::Duration},
+            init: quote! {::core::time::Duration::ZERO},
+        });
+    }
+    // The time excluded by the calls the function is nested in, restored once it completes.
+    if let Some(name) = &args.exclude {
+        fields.push(GuardField {
+            ident: ident("__outer_excluded"),
+            ty: quote! {::core::time::Duration},
+            init: quote! {crate::time_it_exclusions::enter(#name)},
+        });
+    }
+    // Set when a poll first returns `Pending`, left unset if the future completes right away.
+    if args.split_await {
+        fields.push(GuardField {
+            ident: ident("__sync_prep"),
+            ty: quote! {::core::option::Option<::core::time::Duration>},
+            init: quote! {::core::option::Option::None},
+        });
+    }
+    // Resolved at the macro definition, so it can't collide with a `result` of the function's own.
+    let returned = Ident::new("__time_it_result", Span::mixed_site());
+    let mut inspections = Vec::new();
+    // Set once the body produced its value, so a guard dropped without it was cancelled.
+    if args.on_cancel {
+        fields.push(GuardField {
+            ident: ident("__completed"),
+            ty: quote! {bool},
+            init: quote! {false},
+        });
+        inspections.push(quote! {__guard.__completed = true;});
+    }
+    if args.err_level.is_some() || args.err_template.is_some() || args.skip_err {
+        fields.push(GuardField {
+            ident: ident("__failed"),
+            ty: quote! {bool},
+            init: quote! {false},
+        });
+        inspections.push(quote! {__guard.__failed = #returned.is_err();});
+    }
+    // Errors leave the throughput unknown, as they carry no number of bytes. Spanned at the return
+    // type so a value other than an integer is reported there.
+    if args.bytes_ret {
+        let ty = match &fn_sig.output {
+            ReturnType::Type(_, ty) => ty,
+            ReturnType::Default => unreachable!("unit returns are rejected"),
+        };
+        let bytes = if returns_result(&fn_sig.output) {
+            quote_spanned! {ty.span()=>
+                match &#returned {
+                    ::core::result::Result::Ok(__bytes) => {
+                        <u64 as ::core::convert::TryFrom<_>>::try_from(*__bytes).ok()
+                    }
+                    ::core::result::Result::Err(_) => ::core::option::Option::None,
+                }
+            }
+        } else {
+            quote_spanned! {ty.span()=>
+                {
+                    let __bytes: &_ = &#returned;
+                    <u64 as ::core::convert::TryFrom<_>>::try_from(*__bytes).ok()
+                }
+            }
+        };
+        inspections.push(quote! {
+            #[allow(clippy::unnecessary_fallible_conversions, clippy::useless_conversion)]
+            let __bytes = #bytes;
+            __guard.__bytes = __bytes;
+        });
+    }
+    // The clock is stopped first so formatting the value or error isn't part of the measurement.
+    let stoppable = cx.log_result || args.log_err_value;
+    if stoppable {
+        let elapsed = elapsed(quote! {__guard}, args.clock_fn.is_some(), clock);
+        inspections.push(quote! {__guard.__stop = ::core::option::Option::Some(#elapsed);});
+    }
+    if cx.log_result {
+        fields.push(GuardField {
+            ident: ident("__result"),
+            ty: quote! {::core::option::Option<::std::string::String>},
+            init: quote! {::core::option::Option::None},
+        });
+        // Spanned at the return type so a missing `Debug` implementation is reported there.
+        let ReturnType::Type(_, ty) = &fn_sig.output else {
+            unreachable!("unit and never returns are not logged");
+        };
+        inspections.push(quote_spanned! {ty.span()=>
+            __guard.__result = ::core::option::Option::Some(::std::format!("{:?}", #returned));
+        });
+    }
+    if args.log_err_value {
+        fields.push(GuardField {
+            ident: ident("__error"),
+            ty: quote! {::core::option::Option<::std::string::String>},
+            init: quote! {::core::option::Option::None},
+        });
+        // Spanned at the return type so a return type other than `Result`, or an error without a
+        // `Debug` implementation, is reported there.
+        let span = match &fn_sig.output {
+            ReturnType::Type(_, ty) => ty.span(),
+            ReturnType::Default => fn_sig.ident.span(),
+        };
+        inspections.push(quote_spanned! {span=>
+            if let ::core::result::Result::Err(__error) = &#returned {
+                __guard.__error =
+                    ::core::option::Option::Some(::std::format!("{:?}", __error));
+            }
+        });
+    }
+    let inspect = !inspections.is_empty();
+    let polled = args.poll_count || args.split_await || args.exclude_idle;
+
+    // The body runs unchanged in the function's own scope (or future), so `return`, `?`, labeled
+    // breaks and borrows of `self` and the arguments behave exactly as written. The event is
+    // emitted when the guard is dropped, which covers every way of leaving the body.
+    // `std::time::Instant` is runtime agnostic, so async functions don't depend on any executor.
+    let guard = Guard {
+        fields,
+        mutable: inspect || polled,
+        stoppable,
+        busy: args.exclude_idle,
+        excluded: args.exclude.clone(),
+        clock,
+        clock_fn: args.clock_fn.clone(),
+        // Run however the guard is dropped, so the depth is restored even by unwinding calls.
+        exit: args.depth.then(|| {
+            quote! {__DEPTH.with(|depth| depth.set(depth.get() - 1));}
+        }),
+    }
+    .expand(&log_line);
+    let enter = (args.span && asyncness.is_none()).then(|| {
+        quote! {let __entered = __span.enter();}
+    });
+    // When the returned value has to be inspected, the body is evaluated in a closure (or an
+    // async block) first so `return` and `?` still produce the value instead of leaving the
+    // function directly. The declared return type is given to the closure, as its return type
+    // would otherwise be inferred from the body alone and break coercions of the returned value,
+    // such as `Box<T>` to `Box<dyn Trait>`. `impl Trait` types can't be named there, but they are
+    // defined by the concrete type the body returns anyway.
+    let result_ty = match &fn_sig.output {
+        ReturnType::Type(_, ty) if !mentions_ident(ty.to_token_stream(), "impl") => Some(ty),
+        _ => None,
+    };
+    // In `poll_count`, `split_await` and `exclude_idle` modes, the future is polled through a
+    // closure counting its polls, noting when it first suspends and timing each poll. Every poll
+    // of the function's own future polls it once, until it completes.
+    let awaited = |future: TokenStream| {
+        if polled {
+            let count = args.poll_count.then(|| quote! {__guard.__polls += 1;});
+            let poll_start = args.exclude_idle.then(|| match &args.clock_fn {
+                Some(now) => quote_spanned! {now.span()=> let __poll_start = #now();},
+                None => {
+                    let clock = clock.instant_type();
+                    quote! {let __poll_start = #clock::now();}
+                }
+            });
+            let busy = args.exclude_idle.then(|| {
+                let elapsed = match &args.clock_fn {
+                    Some(_) => quote! {__poll_start.elapsed()},
+                    None => clock.elapsed(quote! {__poll_start}),
+                };
+                quote! {__guard.__busy += #elapsed;}
+            });
+            let split = args.split_await.then(|| {
+                let elapsed = elapsed(quote! {__guard}, args.clock_fn.is_some(), clock);
+                quote! {
+                    if __poll.is_pending() && __guard.__sync_prep.is_none() {
+                        __guard.__sync_prep = ::core::option::Option::Some(#elapsed);
+                    }
+                }
+            });
+            quote! {
+                {
+                    let mut __polled = ::core::pin::pin!(#future);
+                    ::core::future::poll_fn(|__cx| {
+                        #count
+                        #poll_start
+                        let __poll = ::core::future::Future::poll(__polled.as_mut(), __cx);
+                        #busy
+                        #split
+                        __poll
+                    })
+                    .await
+                }
+            }
+        } else {
+            quote! {#future.await}
+        }
+    };
+    let value = match (asyncness.is_some(), args.span, inspect || polled) {
+        // The body only creates the future, which was stored in `__future` beforehand.
+        _ if args.future => awaited(quote! {__future}),
+        (true, true, _) => {
+            awaited(quote! {#tracing::Instrument::instrument(async #fn_block, __span)})
+        }
+        (true, false, true) => awaited(match &result_ty {
+            Some(ty) => quote! {async { let __value: #ty = #fn_block; __value }},
+            None => quote! {async #fn_block},
+        }),
+        (false, _, true) => call_once(match &result_ty {
+            Some(ty) => quote! {|| -> #ty #fn_block},
+            None => quote! {|| #fn_block},
+        }),
+        // Splicing the statements rather than the block keeps the function body exactly as
+        // written, and avoids `unused_braces` warnings on single expression bodies.
+        (_, _, false) => {
+            let stmts = &fn_block.stmts;
+            quote! {#(#stmts)*}
+        }
+    };
+    let body = if inspect {
+        quote! {
+            #enter
+            let #returned = #value;
+            #(#inspections)*
+            #returned
+        }
+    } else {
+        quote! {
+            #enter
+            #value
+        }
+    };
+    let timed_body = quote! {
+        #span
+        #entry
+        #guard
+        #body
+    };
+    // In `future` mode the body runs as written to create the future, which is then awaited in
+    // the returned future so the measurement covers it until completion.
+    let timed_body = match returned_future {
+        Some(returned) => {
+            let create = call_once(match &result_ty {
+                Some(ty) => quote! {|| -> #ty #fn_block},
+                None => quote! {|| #fn_block},
+            });
+            let timed_future = quote! {async move { #timed_body }};
+            let timed_future = match returned {
+                ReturnedFuture::Opaque => timed_future,
+                ReturnedFuture::Pinned => quote! {::std::boxed::Box::pin(#timed_future)},
+            };
+            quote! {
+                let __future = #create;
+                #timed_future
+            }
+        }
+        None => timed_body,
+    };
+    let timed_fn = quote! {
+        #(#fn_attrs)*
+        #fn_vis #fn_sig {
+            #inner_fn
+            #capture_args
+            #capture_label
+            #capture_bytes
+            #seq
+            #corr_id
+            #parent
+            #depth
+            #timed_body
+        }
+    };
+
+    // The accessor exists in every build, so callers don't depend on `debug_only`.
+    let stats = cx.stats.as_ref().map(|stats| {
+        let ordering = args.ordering.unwrap_or_default();
+        let load = ordering.load();
+        let rmw = ordering.rmw();
+        let accessor = format_ident!("{}_stats", fn_name.unraw(), span = fn_name.span());
+        let reset = format_ident!("{}_reset_stats", fn_name.unraw(), span = fn_name.span());
+        let doc = format!(
+            " Number of calls to `{}` and total time spent in them so far.",
+            fn_name.unraw()
+        );
+        let reset_doc = format!(
+            " Resets the totals of `{}` to zero, returning the number of calls and total time \
+             they held.",
+            fn_name.unraw()
+        );
+        quote! {
+            static #stats: (::core::sync::atomic::AtomicU64, ::core::sync::atomic::AtomicU64) = (
+                ::core::sync::atomic::AtomicU64::new(0),
+                ::core::sync::atomic::AtomicU64::new(0),
+            );
+            #[doc = #doc]
+            #fn_vis fn #accessor() -> (u64, ::core::time::Duration) {
+                (
+                    #stats.0.load(#load),
+                    ::core::time::Duration::from_nanos(
+                        #stats.1.load(#load),
+                    ),
+                )
+            }
+            #[doc = #reset_doc]
+            #fn_vis fn #reset() -> (u64, ::core::time::Duration) {
+                (
+                    #stats.0.swap(0, #rmw),
+                    ::core::time::Duration::from_nanos(
+                        #stats.1.swap(0, #rmw),
+                    ),
+                )
+            }
+        }
+    });
+
+    let ring = cx.ring.as_ref().zip(args.ring).map(|(ring, capacity)| {
+        let load = args.ordering.unwrap_or_default().load();
+        let capacity = Literal::u64_unsuffixed(capacity);
+        let accessor = format_ident!("{}_recent", fn_name.unraw(), span = fn_name.span());
+        let doc = format!(
+            " Durations of the last calls to `{}`, from the oldest to the most recent.",
+            fn_name.unraw()
+        );
+        quote! {
+            // The durations in nanoseconds, and the number of them written so far.
+            static #ring: (
+                [::core::sync::atomic::AtomicU64; #capacity],
+                ::core::sync::atomic::AtomicUsize,
+            ) = (
+                [const { ::core::sync::atomic::AtomicU64::new(0) }; #capacity],
+                ::core::sync::atomic::AtomicUsize::new(0),
+            );
+            #[doc = #doc]
+            #fn_vis fn #accessor() -> ::std::vec::Vec<::core::time::Duration> {
+                use ::core::iter::Iterator as _;
+                let __written = #ring.1.load(#load);
+                let __start = __written.saturating_sub(#ring.0.len());
+                (__start..__written)
+                    .map(|__index| {
+                        ::core::time::Duration::from_nanos(
+                            #ring.0[__index % #ring.0.len()].load(#load),
+                        )
+                    })
+                    .collect()
+            }
+        }
+    });
+
+    let quantiles = cx.quantiles.as_ref().map(|quantiles| {
+        let load = args.ordering.unwrap_or_default().load();
+        let accessor = format_ident!("{}_quantiles", fn_name.unraw(), span = fn_name.span());
+        let doc = format!(
+            " Estimated median, 90th and 99th percentile of the durations of `{}`, or `None` \
+             before its first call.",
+            fn_name.unraw()
+        );
+        quote! {
+            // The number of durations in each bucket, see `quantile_bucket`.
+            static #quantiles: [::core::sync::atomic::AtomicU64; #QUANTILE_BUCKETS] =
+                [const { ::core::sync::atomic::AtomicU64::new(0) }; #QUANTILE_BUCKETS];
+            #[doc = #doc]
+            #fn_vis fn #accessor() -> ::core::option::Option<(
+                ::core::time::Duration,
+                ::core::time::Duration,
+                ::core::time::Duration,
+            )> {
+                use ::core::iter::Iterator as _;
+                let __counts: [u64; #QUANTILE_BUCKETS] =
+                    ::core::array::from_fn(|__bucket| #quantiles[__bucket].load(#load));
+                let __total: u64 = __counts.iter().sum();
+                if __total == 0 {
+                    return ::core::option::Option::None;
+                }
+                // The middle of the bucket holding the duration of the given rank, counting from
+                // 1, so the estimate is off by at most half the width of the bucket.
+                let __estimate = |__percent: u64| {
+                    let __rank = (__total * __percent).div_ceil(100);
+                    let mut __seen = 0;
+                    let __bucket = __counts
+                        .iter()
+                        .position(|__count| {
+                            __seen += __count;
+                            __seen >= __rank
+                        })
+                        .unwrap_or(#QUANTILE_BUCKETS - 1);
+                    let (__low, __width) = if __bucket < 16 {
+                        (__bucket as u64, 1)
+                    } else {
+                        let __shift = __bucket / 16 - 1;
+                        (((16 + __bucket % 16) as u64) << __shift, 1u64 << __shift)
+                    };
+                    ::core::time::Duration::from_nanos(__low + (__width - 1) / 2)
+                };
+                ::core::option::Option::Some((__estimate(50), __estimate(90), __estimate(99)))
+            }
+        }
+    });
+
+    if args.debug_only {
+        Ok(quote! {
+            #stats
+            #ring
+            #quantiles
+            #[cfg(debug_assertions)]
+            #timed_fn
+            #[cfg(not(debug_assertions))]
+            #input
+        })
+    } else {
+        Ok(quote! {
+            #stats
+            #ring
+            #quantiles
+            #timed_fn
+        })
+    }
+}
+
+/// Expands `input` into the same impl block with every method timed according to `args`.
+///
+/// Methods marked with `#[time_it(skip)]` and `const fn`s, which cannot be timed, are left
+/// unchanged, and methods with their own `#[time_it]` attribute are left to it.
+pub(crate) fn time_impl(args: &Args, input: &ItemImpl) -> syn::Result<TokenStream> {
+    if let Some(label) = &args.label {
+        return Err(syn::Error::new_spanned(
+            label,
+            "`label` would name every method the same, annotate the methods to label them instead",
+        ));
+    }
+    for (enabled, flag) in [
+        (args.aggregate, "aggregate"),
+        (args.ring.is_some(), "ring"),
+        (args.quantiles, "quantiles"),
+    ] {
+        if enabled {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                format!(
+                    "`{flag}` only supports free functions, as it generates a `static` next to \
+                     the function"
+                ),
+            ));
+        }
+    }
+    let mut input = input.clone();
+    for item in &mut input.items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        if take_skip(&mut method.attrs)
+            || method.sig.constness.is_some()
+            || method.attrs.iter().any(is_time_it)
+        {
+            continue;
+        }
+        if let Some(defaultness) = &method.defaultness {
+            return Err(syn::Error::new_spanned(
+                defaultness,
+                "`default` methods cannot be timed by an impl level `#[time_it]`",
+            ));
+        }
+        let method_fn = ItemFn {
+            attrs: method.attrs.clone(),
+            vis: method.vis.clone(),
+            sig: method.sig.clone(),
+            block: Box::new(method.block.clone()),
+        };
+        *item = ImplItem::Verbatim(time_it(args, &method_fn)?);
+    }
+    Ok(input.into_token_stream())
+}
+
+/// Expands `input` into the same inline module with every function directly inside it timed
+/// according to `args`.
+///
+/// Functions are selected as the methods of an impl block are. Everything else, including nested
+/// modules and impl blocks, is left unchanged, so they are only timed when annotated themselves.
+pub(crate) fn time_mod(args: &Args, input: &ItemMod) -> syn::Result<TokenStream> {
+    if let Some(label) = &args.label {
+        return Err(syn::Error::new_spanned(
+            label,
+            "`label` would name every function the same, annotate the functions to label them \
+             instead",
+        ));
+    }
+    let mut input = input.clone();
+    let Some((_, items)) = &mut input.content else {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "`#[time_it]` only supports inline modules, annotate the module's own file instead",
+        ));
+    };
+    for item in items {
+        let Item::Fn(function) = item else {
+            continue;
+        };
+        if take_skip(&mut function.attrs)
+            || function.sig.constness.is_some()
+            || function.attrs.iter().any(is_time_it)
+        {
+            continue;
+        }
+        *item = Item::Verbatim(time_it(args, function)?);
+    }
+    Ok(input.into_token_stream())
+}
+
+/// `input` unchanged, as with `skip` or the `disabled` feature, but for the `#[time_it(skip)]`
+/// markers of its methods, which are only meant for the impl level attribute.
+pub(crate) fn untimed_impl(input: &ItemImpl) -> TokenStream {
+    let mut input = input.clone();
+    for item in &mut input.items {
+        if let ImplItem::Fn(method) = item {
+            take_skip(&mut method.attrs);
+        }
+    }
+    input.into_token_stream()
+}
+
+/// `input` unchanged, as with `skip` or the `disabled` feature, but for the `#[time_it(skip)]`
+/// markers of its functions, which are only meant for the module level attribute.
+pub(crate) fn untimed_mod(input: &ItemMod) -> TokenStream {
+    let mut input = input.clone();
+    for item in input.content.iter_mut().flat_map(|(_, items)| items) {
+        if let Item::Fn(function) = item {
+            take_skip(&mut function.attrs);
+        }
+    }
+    input.into_token_stream()
+}
+
+/// The statements of `block` unchanged, as with the `disabled` feature, in braces of the macro's
+/// own. Reusing the braces of the invocation would get them linted as `unused_braces` when the
+/// block is the value of a `let` or a tail expression.
+pub(crate) fn untimed_block(block: &Block) -> TokenStream {
+    let stmts = &block.stmts;
+    quote_spanned! {Span::mixed_site()=> { #(#stmts)* }}
+}
+
+/// Removes the `#[time_it(skip)]` marker from `attrs`, returning whether it was there. The marker
+/// only tells an impl or module level attribute to leave the item out.
+fn take_skip(attrs: &mut Vec<syn::Attribute>) -> bool {
+    let skip = attrs
+        .iter()
+        .position(|attr| is_time_it(attr) && attr.parse_args::<Args>().is_ok_and(|args| args.skip));
+    skip.map(|skip| attrs.remove(skip)).is_some()
+}
+
+/// Expands `block` into the same block with its execution timed according to `args`.
+///
+/// `label` names the block in events and determines the reported source location.
+pub(crate) fn time_block(args: &Args, label: &LitStr, block: &Block) -> syn::Result<TokenStream> {
+    timed_block(args, label, &block.stmts, "time_block!")
+}
+
+/// Expands `block` into the same block timed at the level of `args`, for `time_scope!`.
+pub(crate) fn time_scope(args: &Args, label: &LitStr, block: &Block) -> syn::Result<TokenStream> {
+    timed_block(args, label, &block.stmts, "time_scope!")
+}
+
+/// Expands `closure` into the same closure with each of its calls timed according to `args`.
+///
+/// Only the body is replaced, so the closure captures the same values and implements the same
+/// `Fn` traits as written.
+pub(crate) fn time_closure(
+    args: &Args,
+    label: &LitStr,
+    closure: &ExprClosure,
+) -> syn::Result<TokenStream> {
+    let stmts = match &*closure.body {
+        Expr::Block(body) if body.label.is_none() && body.attrs.is_empty() => {
+            body.block.stmts.clone()
+        }
+        body => vec![Stmt::Expr(body.clone(), None)],
+    };
+    let mut closure = closure.clone();
+    closure.body = Box::new(Expr::Verbatim(timed_block(
+        args,
+        label,
+        &stmts,
+        "time_closure!",
+    )?));
+    Ok(closure.into_token_stream())
+}
+
+/// Expands `stmts` into a block timed according to `args`, for the `macro_name` macro.
+fn timed_block(
+    args: &Args,
+    label: &LitStr,
+    stmts: &[Stmt],
+    macro_name: &str,
+) -> syn::Result<TokenStream> {
+    let backend = Backend::current();
+    check_features(args, backend)?;
+    if let Some(label) = &args.label {
+        return Err(syn::Error::new_spanned(
+            label,
+            format!("The label of `{macro_name}` is given as its first argument"),
+        ));
+    }
+    for (enabled, flag) in [
+        (args.debug_only, "debug_only"),
+        (args.span, "span"),
+        (args.err_level.is_some(), "err_level"),
+        (args.err_template.is_some(), "err_template"),
+        (args.skip_err, "skip_err"),
+        (args.log_result, "log_result"),
+        (args.log_args, "log_args"),
+        (args.aggregate, "aggregate"),
+        (args.ring.is_some(), "ring"),
+        (args.quantiles, "quantiles"),
+        (args.label_arg.is_some(), "label_arg"),
+        (args.bytes_arg.is_some(), "bytes_arg"),
+        (args.bytes_ret, "bytes_ret"),
+        (args.kind, "kind"),
+        (args.include_drops, "include_drops"),
+        (args.inner_fn, "inner_fn"),
+        (args.on_cancel, "on_cancel"),
+        (args.poll_count, "poll_count"),
+        (args.runtime, "runtime"),
+        (args.split_await, "split_await"),
+        (args.exclude_idle, "exclude_idle"),
+        (args.exclude.is_some(), "exclude"),
+        (args.depth, "depth"),
+        (args.top_only, "top_only"),
+    ] {
+        if enabled {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                format!("`{flag}` is not supported by `{macro_name}`"),
+            ));
+        }
+    }
+
+    let cx = Context {
+        args,
+        name: quote! {#label},
+        location: label.span(),
+        backend,
+        log_result: false,
+        log_args: false,
+        stats: None,
+        ring: None,
+        quantiles: None,
+        metric: args.metric.as_ref().map(|metric| match metric {
+            Some(name) => quote! {#name},
+            None => quote! {#label},
+        }),
+        env_gate: args.env_gate.then(|| env_gate_var(&label.value())),
+        kind: None,
+    };
+    let seq = args.seq.then(|| seq_number(args));
+    let corr_id = args.corr_id.then(corr_id);
+    let parent = args.parent.as_ref().map(parent);
+    let entry = args.trace_entry.then(|| switched(cx.entry_event()));
+    let mut fields = Vec::new();
+    if args.seq {
+        fields.push(seq_field());
+    }
+    if args.corr_id {
+        fields.push(corr_id_field());
+    }
+    if args.parent.is_some() {
+        fields.push(parent_field());
+    }
+    if args.allocs {
+        fields.push(allocs_field());
+    }
+    if args.both_clocks {
+        fields.push(cpu_start_field());
+    }
+    if args.chrome_trace {
+        fields.push(start_time_field());
+    }
+    if args.since_start {
+        fields.push(since_start_field());
+    }
+    let guard = Guard {
+        fields,
+        mutable: false,
+        stoppable: false,
+        busy: false,
+        excluded: None,
+        clock: args.clock.unwrap_or_default(),
+        clock_fn: args.clock_fn.clone(),
+        exit: None,
+    }
+    .expand(&cx.log_line());
+    // As for functions, the statements are spliced next to the guard so the block behaves exactly
+    // as written, including `.await`, `?` and `return`, and its value is the value of the macro.
+    Ok(quote! {
+        {
+            #seq
+            #corr_id
+            #parent
+            #entry
+            #guard
+            #(#stmts)*
+        }
+    })
+}
+
+/// Expands into the `__time_it_allocs` module, which installs a global allocator counting the
+/// allocations of every thread for `allocs` to report.
+///
+/// The counters are `const` initialized thread locals without destructors, so accessing them
+/// never allocates. Allocations made while they are being torn down are not counted.
+pub(crate) fn count_allocations() -> syn::Result<TokenStream> {
+    if !cfg!(feature = "alloc_stats") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`count_allocations!` requires the `alloc_stats` feature",
+        ));
+    }
+    Ok(quote! {
+        #[doc(hidden)]
+        pub mod __time_it_allocs {
+            use ::std::alloc::{GlobalAlloc, Layout, System};
+
+            ::std::thread_local! {
+                /// Number of allocations and total bytes allocated by the thread so far.
+                static COUNTS: ::std::cell::Cell<(u64, u64)> =
+                    const { ::std::cell::Cell::new((0, 0)) };
+            }
+
+            /// Number of allocations and total bytes allocated by the current thread so far.
+            pub fn counts() -> (u64, u64) {
+                COUNTS.try_with(::std::cell::Cell::get).unwrap_or_default()
+            }
+
+            fn count(size: usize) {
+                let _ = COUNTS.try_with(|counts| {
+                    let (count, bytes) = counts.get();
+                    counts.set((count.wrapping_add(1), bytes.wrapping_add(size as u64)));
+                });
+            }
+
+            /// The system allocator, counting every allocation of the current thread.
+            pub struct CountingAllocator;
+
+            unsafe impl GlobalAlloc for CountingAllocator {
+                unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+                    count(layout.size());
+                    unsafe { System.alloc(layout) }
+                }
+
+                unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+                    count(layout.size());
+                    unsafe { System.alloc_zeroed(layout) }
+                }
+
+                unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+                    count(new_size);
+                    unsafe { System.realloc(ptr, layout, new_size) }
+                }
+
+                unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+                    unsafe { System.dealloc(ptr, layout) }
+                }
+            }
+
+            #[global_allocator]
+            static ALLOCATOR: CountingAllocator = CountingAllocator;
+        }
+    })
+}
+
+/// Number of buckets of the `quantiles` histogram: 16 for the durations below 16ns, and 16 for each
+/// power of two above, up to `u64::MAX` nanoseconds.
+const QUANTILE_BUCKETS: usize = 976;
+
+/// Index of the `quantiles` bucket holding `__duration`.
+///
+/// Durations below 16ns get a bucket each. Above, each power of two is split into 16 buckets of
+/// equal width, keyed by the four bits following the leading one, so a bucket is at most 1/16th of
+/// the durations it holds wide.
+fn quantile_bucket() -> TokenStream {
+    quote! {{
+        let __nanos = <u64 as ::core::convert::TryFrom<u128>>::try_from(__duration.as_nanos()).unwrap_or(u64::MAX);
+        if __nanos < 16 {
+            __nanos as usize
+        } else {
+            let __exponent = 63 - __nanos.leading_zeros();
+            let __mantissa = (__nanos >> (__exponent - 4)) & 15;
+            (__exponent as usize - 3) * 16 + __mantissa as usize
+        }
+    }}
+}
+
+/// Wraps `report` so it only runs while the switch toggled by `time_it::set_enabled` is enabled,
+/// with the `runtime_switch` feature.
+fn switched(report: TokenStream) -> TokenStream {
+    if cfg!(feature = "runtime_switch") {
+        quote! {
+            if ::time_it::__private::is_enabled() {
+                #report
+            }
+        }
+    } else {
+        report
+    }
+}
+
+/// Expands into the body of the `Drop` impl of `time_it::Timer`, reporting the timer at the level
+/// it was created with.
+///
+/// The event is built as for a function named after the timer with default arguments, once for
+/// each level, and the one matching the timer's level is emitted.
+pub(crate) fn timer_report() -> TokenStream {
+    let backend = Backend::current();
+    let args = Args::default();
+    let cx = Context {
+        args: &args,
+        name: quote! {__name},
+        location: Span::call_site(),
+        backend,
+        log_result: false,
+        log_args: false,
+        stats: None,
+        ring: None,
+        quantiles: None,
+        metric: None,
+        env_gate: None,
+        kind: None,
+    };
+    let [error, warn, info, debug, trace] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ]
+    .map(|level| cx.event(level, false));
+    let tracing = tracing_crate();
+    let events = match backend {
+        Backend::Log => quote! {
+            ::log::Level::Error => { #error }
+            ::log::Level::Warn => { #warn }
+            ::log::Level::Info => { #info }
+            ::log::Level::Debug => { #debug }
+            ::log::Level::Trace => { #trace }
+        },
+        _ => quote! {
+            #tracing::Level::ERROR => { #error }
+            #tracing::Level::WARN => { #warn }
+            #tracing::Level::INFO => { #info }
+            #tracing::Level::DEBUG => { #debug }
+            _ => { #trace }
+        },
+    };
+    let hook = hooked(&quote! {__name}, || quote! {self.level});
+    let report = switched(quote! {
+        #hook
+        match self.level {
+            #events
+        }
+    });
+    let scoped = scoped(&quote! {__name});
+    quote! {
+        let __duration = self.start.elapsed();
+        let __name = self.name;
+        #scoped
+        #report
+    }
+}
+
+/// Expands `block` so it runs inside a scope named `label`, opened in the registry of `time_it`.
+/// When the block completes, the scope emits a single event with its own duration and
+/// the breakdown of the timed calls made in it.
+pub(crate) fn scope(args: &Args, label: &LitStr, block: &Block) -> syn::Result<TokenStream> {
+    if !cfg!(feature = "scopes") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`scope!` requires the `scopes` feature",
+        ));
+    }
+    if cfg!(feature = "no_std") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`scope!` requires `std`, which the `no_std` feature rules out",
+        ));
+    }
+    let backend = Backend::current();
+    if backend == Backend::Defmt {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`scope!` is not supported by the `defmt` backend",
+        ));
+    }
+    let cx = Context {
+        args,
+        name: quote! {#label},
+        location: label.span(),
+        backend,
+        log_result: false,
+        log_args: false,
+        stats: None,
+        ring: None,
+        quantiles: None,
+        metric: None,
+        env_gate: None,
+        kind: None,
+    };
+    let event_macro = cx.event_macro(args.level.unwrap_or_default());
+    let target = cx.event_target();
+    let hook = hooked(&quote! {#label}, || cx.hook_level());
+    let report = switched(quote! {
+        #hook
+        #event_macro!(
+            #target "[{}]: Execution time: {:?}, breakdown: {}",
+            #label,
+            __duration,
+            __breakdown,
+        );
+    });
+    let stmts = &block.stmts;
+    Ok(quote! {
+        {
+            struct __TimeItScope(::std::time::Instant);
+            impl ::core::ops::Drop for __TimeItScope {
+                fn drop(&mut self) {
+                    let __duration = self.0.elapsed();
+                    let __breakdown = ::time_it::__private::exit_scope(#label, __duration);
+                    #report
+                }
+            }
+            ::time_it::__private::enter_scope();
+            let __scope = __TimeItScope(::std::time::Instant::now());
+            #(#stmts)*
+        }
+    })
+}
+
+/// Expands into the `time_it_exclusions` module, holding the time registered by `exclude!` per
+/// thread and name.
+pub(crate) fn exclusions() -> syn::Result<TokenStream> {
+    if cfg!(feature = "no_std") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`exclusions!` requires `std`, which the `no_std` feature rules out",
+        ));
+    }
+    Ok(quote! {
+        /// Time left out of the measurements of the functions timed with `exclude` in this crate.
+        pub mod time_it_exclusions {
+            use ::core::cell::RefCell;
+            use ::core::iter::Iterator;
+            use ::core::option::Option::{None, Some};
+            use ::core::time::Duration;
+            use ::std::vec::Vec;
+
+            ::std::thread_local! {
+                /// Time excluded under each name since the current call timed with it started.
+                static EXCLUDED: RefCell<Vec<(&'static str, Duration)>> =
+                    const { RefCell::new(Vec::new()) };
+            }
+
+            /// Time excluded under `name` on the current thread since the innermost call timed
+            /// with it started.
+            pub fn excluded(name: &str) -> Duration {
+                EXCLUDED.with_borrow(|excluded| {
+                    excluded
+                        .iter()
+                        .find(|(excluded, _)| *excluded == name)
+                        .map_or(Duration::ZERO, |(_, total)| *total)
+                })
+            }
+
+            /// Adds `duration` to the time excluded under `name` on the current thread.
+            pub fn add(name: &'static str, duration: Duration) {
+                EXCLUDED.with_borrow_mut(|excluded| {
+                    match excluded.iter_mut().find(|(excluded, _)| *excluded == name) {
+                        Some((_, total)) => *total += duration,
+                        None => excluded.push((name, duration)),
+                    }
+                });
+            }
+
+            /// Starts a call timed with `name`, returning the time excluded so far by the calls
+            /// it is nested in.
+            #[doc(hidden)]
+            pub fn enter(name: &'static str) -> Duration {
+                let outer = excluded(name);
+                EXCLUDED.with_borrow_mut(|excluded| {
+                    if let Some((_, total)) =
+                        excluded.iter_mut().find(|(excluded, _)| *excluded == name)
+                    {
+                        *total = Duration::ZERO;
+                    }
+                });
+                outer
+            }
+
+            /// Completes a call timed with `name`, returning the time it excluded. That time is
+            /// also part of the calls it is nested in, so it's added back to theirs.
+            #[doc(hidden)]
+            pub fn exit(name: &'static str, outer: Duration) -> Duration {
+                let inner = excluded(name);
+                EXCLUDED.with_borrow_mut(|excluded| {
+                    if let Some((_, total)) =
+                        excluded.iter_mut().find(|(excluded, _)| *excluded == name)
+                    {
+                        *total = outer + inner;
+                    }
+                });
+                inner
+            }
+        }
+    })
+}
+
+/// Expands `expr` so the time it takes is registered under `name` in the module defined by
+/// `exclusions!`, and left out of the calls timed with `exclude = name` it runs in.
+pub(crate) fn exclude(name: &LitStr, expr: &Expr) -> TokenStream {
+    quote! {
+        {
+            let __start = ::std::time::Instant::now();
+            let __value = #expr;
+            crate::time_it_exclusions::add(#name, __start.elapsed());
+            __value
+        }
+    }
+}
+
+/// Adds the measurement of `name` to the innermost `scope!` of the current thread, with the
+/// `scopes` feature.
+fn scoped(name: &TokenStream) -> Option<TokenStream> {
+    cfg!(feature = "scopes")
+        .then(|| quote! {::time_it::__private::record_in_scope(#name, __duration);})
+}
+
+/// Passes the measurement of `name` to the hook installed by `time_it::set_global_hook` at `level`,
+/// with the `global_hook` feature. `level` is only built with the feature.
+fn hooked(name: &TokenStream, level: impl FnOnce() -> TokenStream) -> Option<TokenStream> {
+    cfg!(feature = "global_hook").then(|| {
+        let level = level();
+        quote! {::time_it::__private::hook_call(#name, __duration, #level);}
+    })
+}
+
+/// Error for an item that isn't a function or impl block: a trait method declared without a
+/// default body, which has nothing to time, or another kind of item.
+///
+/// Returns `None` if `item` isn't a recognized item, in which case the original parse error
+/// applies.
+pub(crate) fn unsupported_item(item: TokenStream) -> Option<syn::Error> {
+    if let Ok(method) = syn::parse2::<TraitItemFn>(item.clone()) {
+        let semi = method.semi_token.filter(|_| method.default.is_none())?;
+        return Some(syn::Error::new_spanned(
+            semi,
+            "`#[time_it]` requires a function body, annotate the implementations of this method \
+             or give it a default body instead",
+        ));
+    }
+    let (keyword, kind) = match syn::parse2::<Item>(item).ok()? {
+        Item::Static(item) => (item.static_token.span, "statics"),
+        Item::Const(item) => (item.const_token.span, "constants"),
+        Item::Struct(item) => (item.struct_token.span, "structs"),
+        Item::Enum(item) => (item.enum_token.span, "enums"),
+        Item::Union(item) => (item.union_token.span, "unions"),
+        Item::Trait(item) => (item.trait_token.span, "traits"),
+        Item::Type(item) => (item.type_token.span, "type aliases"),
+        Item::ForeignMod(item) => (item.abi.extern_token.span, "extern blocks"),
+        Item::Use(item) => (item.use_token.span, "imports"),
+        Item::Macro(item) => (item.mac.path.span(), "macro invocations"),
+        _ => return None,
+    };
+    // A static or constant computed by a function is timed by annotating that function instead.
+    let hint = match kind {
+        "statics" | "constants" => ", annotate the function computing the value instead",
+        _ => "",
+    };
+    Some(syn::Error::new(
+        keyword,
+        format!(
+            "`#[time_it]` can only be applied to functions and impl blocks, not to {kind}{hint}"
+        ),
+    ))
+}
+
+/// Ensures the arguments only use what the enabled features support.
+fn check_features(args: &Args, backend: Backend) -> syn::Result<()> {
+    if backend != Backend::Tracing {
+        for (enabled, flag) in [
+            (args.structured, "structured"),
+            (args.span, "span"),
+            (args.in_span, "in_span"),
+            (args.raw_ns, "raw_ns"),
+            (args.otel, "otel"),
+            (args.kind, "kind"),
+            (args.chrome_trace, "chrome_trace"),
+            (args.fields.is_some(), "fields"),
+            (args.category.is_some(), "category"),
+            (args.parent.is_some(), "parent"),
+            (args.level_fn.is_some(), "level_fn"),
+        ] {
+            if enabled {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    format!("`{flag}` requires the `tracing` backend"),
+                ));
+            }
+        }
+    }
+    if cfg!(feature = "no_std") {
+        if args.clock_fn.is_none() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "the `no_std` feature requires `clock_fn`, as `std::time::Instant` is unavailable",
+            ));
+        }
+        if cfg!(feature = "scopes") {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "the `scopes` feature requires `std`, which the `no_std` feature rules out",
+            ));
+        }
+        if cfg!(feature = "global_hook") {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "the `global_hook` feature requires `std`, which the `no_std` feature rules out",
+            ));
+        }
+        let human = matches!(args.format, Some(DurationFormat::Human));
+        for (enabled, arg) in [
+            (args.log_result, "log_result"),
+            (args.log_err_value, "log_err_value"),
+            (args.log_args, "log_args"),
+            (human, "format = \"human\""),
+            (args.output.is_some(), "output"),
+            (args.thread, "thread"),
+            (args.runtime, "runtime"),
+            (args.bytes_arg.is_some(), "bytes_arg"),
+            (args.bytes_ret, "bytes_ret"),
+            (args.env_gate, "env_gate"),
+            (args.since_start, "since_start"),
+            (args.chrome_trace, "chrome_trace"),
+            (args.allocs, "allocs"),
+            (args.ring.is_some(), "ring"),
+            (args.corr_id, "corr_id"),
+            (args.depth, "depth"),
+            (args.exclude.is_some(), "exclude"),
+            (args.rate_limit.is_some(), "rate_limit"),
+            (args.sink.is_some(), "sink"),
+            (args.label_arg.is_some(), "label_arg"),
+        ] {
+            if enabled {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    format!("`{arg}` requires `std`, which the `no_std` feature rules out"),
+                ));
+            }
+        }
+    }
+    if backend == Backend::Defmt && cfg!(feature = "global_hook") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "the `global_hook` feature is not supported by the `defmt` backend, whose levels \
+             can't be passed to the hook",
+        ));
+    }
+    if backend == Backend::Defmt {
+        for (enabled, arg) in [
+            (args.target.is_some(), "target"),
+            (args.format.is_some(), "format"),
+            (args.precision.is_some(), "precision"),
+            (args.log_result, "log_result"),
+            (args.log_err_value, "log_err_value"),
+            (args.log_args, "log_args"),
+            (args.thread, "thread"),
+            (args.runtime, "runtime"),
+            (args.bytes_arg.is_some(), "bytes_arg"),
+            (args.bytes_ret, "bytes_ret"),
+            (args.since_start, "since_start"),
+            (args.corr_id, "corr_id"),
+            (args.split_await, "split_await"),
+            (args.both_clocks, "both_clocks"),
+            (args.summarize.is_some(), "summarize"),
+        ] {
+            if enabled {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    format!("`{arg}` is not supported by the `defmt` backend"),
+                ));
+            }
+        }
+    }
+    if args.metric.is_some() && !cfg!(feature = "metrics") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`metric` requires the `metrics` feature",
+        ));
+    }
+    if args.otel && !cfg!(feature = "opentelemetry") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`otel` requires the `opentelemetry` feature",
+        ));
+    }
+    if args.clock == Some(Clock::Cpu) && !cfg!(feature = "cpu_time") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`clock = \"cpu\"` requires the `cpu_time` feature",
+        ));
+    }
+    if args.clock == Some(Clock::HighRes) && !cfg!(feature = "high_res") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`clock = \"high_res\"` requires the `high_res` feature",
+        ));
+    }
+    if args.runtime && !cfg!(feature = "tokio") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`runtime` requires the `tokio` feature",
+        ));
+    }
+    if args.both_clocks && !cfg!(feature = "cpu_time") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`both_clocks` requires the `cpu_time` feature",
+        ));
+    }
+    if args.allocs && !cfg!(feature = "alloc_stats") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`allocs` requires the `alloc_stats` feature",
+        ));
+    }
+    Ok(())
+}
+
+impl Context<'_> {
+    /// Code run once the measurement is done, with the elapsed time bound to `__duration`.
+    fn log_line(&self) -> TokenStream {
+        // Histograms and handlers need every sample, so they run regardless of the threshold.
+        let record = self.metric.as_ref().map(|metric| {
+            quote! {::metrics::histogram!(#metric).record(__duration.as_secs_f64());}
+        });
+        let otel = self.args.otel.then(|| {
+            let tracing = tracing_crate();
+            quote! {
+                ::tracing_opentelemetry::OpenTelemetrySpanExt::set_attribute(
+                    &#tracing::Span::current(),
+                    "duration_ms",
+                    __duration.as_secs_f64() * 1000.0,
+                );
+            }
+        });
+        // Spanned at the path so an unresolved or mismatching function is reported there.
+        let on_complete = self.args.on_complete.as_ref().map(|path| {
+            let name = &self.name;
+            quote_spanned! {path.span()=> #path(#name, __duration);}
+        });
+        let collector = self.args.collector.as_ref().map(|path| {
+            let name = &self.name;
+            quote_spanned! {path.span()=> ::time_it::TimeItCollector::record(&#path, #name, __duration);}
+        });
+        let scoped = scoped(&self.name);
+        // Passed every measurement, before the reporting modes and the filters of `report`.
+        let hook = hooked(&self.name, || self.hook_level());
+        let quantiles = self.quantiles.as_ref().map(|quantiles| {
+            let rmw = self.args.ordering.unwrap_or_default().rmw();
+            let bucket = quantile_bucket();
+            quote! {#quantiles[#bucket].fetch_add(1, #rmw);}
+        });
+        let regression = self
+            .args
+            .regression
+            .map(|factor| regressed(factor, self.args.ordering.unwrap_or_default()));
+        let zero = self.args.warn_zero.then(|| self.zero_warning());
+        let report = self.report();
+        let mut log_line = quote! {
+            #record
+            #quantiles
+            #zero
+            #regression
+            #otel
+            #on_complete
+            #collector
+            #scoped
+            #hook
+            #report
+        };
+        if let Some(min_report) = self.args.min_report {
+            let min_report = duration_tokens(min_report);
+            log_line = quote! {
+                if __duration >= #min_report {
+                    #log_line
+                }
+            };
+        }
+        log_line = self.top_only(quote! {*__depth}, log_line);
+        let ordering = self.args.ordering.unwrap_or_default();
+        let (rmw, load) = (ordering.rmw(), ordering.load());
+        if let Some(first) = self.args.first {
+            // Once the limit is reached, completing calls only load the counter.
+            log_line = quote! {
+                static __REPORTED: ::core::sync::atomic::AtomicU64 =
+                    ::core::sync::atomic::AtomicU64::new(0);
+                if __REPORTED.load(#load) < #first && __REPORTED.fetch_add(1, #rmw) < #first
+                {
+                    #log_line
+                }
+            };
+        }
+        if let Some(sample) = self.args.sample {
+            // Counting every call makes the sampling deterministic: the first call is reported,
+            // then every `sample`th one.
+            log_line = quote! {
+                static __SAMPLED: ::core::sync::atomic::AtomicU64 =
+                    ::core::sync::atomic::AtomicU64::new(0);
+                if __SAMPLED.fetch_add(1, #rmw) % #sample == 0 {
+                    #log_line
+                }
+            };
+        }
+        log_line = match &self.env_gate {
+            // The variable is only looked up once, the first time the code completes.
+            Some(var) => quote! {
+                static __ENABLED: ::std::sync::OnceLock<bool> = ::std::sync::OnceLock::new();
+                if *__ENABLED.get_or_init(|| ::std::env::var_os(#var).is_some()) {
+                    #log_line
+                }
+            },
+            None => log_line,
+        };
+        if self.args.allocs {
+            // Counted first, so allocations made while reporting aren't included.
+            log_line = quote! {
+                let __allocs = {
+                    let (__count, __bytes) = crate::__time_it_allocs::counts();
+                    (__count.wrapping_sub(__allocs.0), __bytes.wrapping_sub(__allocs.1))
+                };
+                #log_line
+            };
+        }
+        if self.args.both_clocks {
+            // Read first, so reporting isn't part of the CPU time.
+            log_line = quote! {
+                let __cpu = __cpu.elapsed();
+                #log_line
+            };
+        }
+        let log_line = switched(log_line);
+        // Checked after reporting, so the call is still logged, and however it's reported.
+        let budget = self.args.budget.map(|budget| self.budget_check(budget));
+        quote! {
+            #log_line
+            #budget
+        }
+    }
+
+    /// Panics when a call takes longer than `budget` in the tests of the crate the code is
+    /// expanded in, or in every build with the `enforce_budgets` feature, unless it's already
+    /// unwinding. It's a no-op in other builds.
+    fn budget_check(&self, budget: Duration) -> TokenStream {
+        let name = &self.name;
+        let panicking = panicking();
+        let budget = duration_tokens(budget);
+        // `cfg(test)` is only set in the unit tests of the crate defining the timed code, so the
+        // feature extends the check to the crates testing it.
+        let test_only =
+            (!cfg!(feature = "enforce_budgets")).then(|| quote! {::core::cfg!(test) &&});
+        quote! {
+            if #test_only !#panicking && __duration > #budget {
+                ::core::panic!(
+                    "[{}]: Execution time {:?} exceeded the budget of {:?}",
+                    #name,
+                    __duration,
+                    #budget,
+                );
+            }
+        }
+    }
+
+    /// Warning emitted the first time a call measures no time in `warn_zero` mode, which means the
+    /// call ran below the resolution of the clock and its duration is meaningless.
+    fn zero_warning(&self) -> TokenStream {
+        let name = &self.name;
+        let message = "[{}]: Measured no time, below the resolution of the clock";
+        let warning = match self.args.output {
+            Some(output) => {
+                let print = output.macro_ident();
+                quote! {::std::#print!(#message, #name);}
+            }
+            None => {
+                let event_macro = self.event_macro(LogLevel::Warn);
+                let target = self.event_target();
+                quote! {#event_macro!(#target #message, #name);}
+            }
+        };
+        // Only the flag itself is shared, so `Relaxed` is enough whatever the `ordering`.
+        quote! {
+            static __WARNED_ZERO: ::core::sync::atomic::AtomicBool =
+                ::core::sync::atomic::AtomicBool::new(false);
+            if __duration.is_zero()
+                && !__WARNED_ZERO.swap(true, ::core::sync::atomic::Ordering::Relaxed)
+            {
+                #warning
+            }
+        }
+    }
+
+    /// Wraps `report` so it only runs for the outermost calls in `top_only` mode, given the
+    /// `depth` of the call.
+    fn top_only(&self, depth: TokenStream, report: TokenStream) -> TokenStream {
+        if self.args.top_only {
+            quote! {
+                if #depth == 0 {
+                    #report
+                }
+            }
+        } else {
+            report
+        }
+    }
+
+    /// Reports the measurement through the selected event, span, aggregate or ring buffer.
+    fn report(&self) -> TokenStream {
+        let args = self.args;
+        let level = args.level.unwrap_or_default();
+        let log_line = if self.stats.is_some() || self.ring.is_some() {
+            let ordering = args.ordering.unwrap_or_default();
+            let rmw = ordering.rmw();
+            let nanos = quote! {<u64 as ::core::convert::TryFrom<u128>>::try_from(__duration.as_nanos()).unwrap_or(u64::MAX)};
+            // Both counters wrap around on overflow, which takes about 584 years of total time.
+            let stats = self.stats.as_ref().map(|stats| {
+                quote! {
+                    #stats.0.fetch_add(1, #rmw);
+                    #stats.1.fetch_add(#nanos, #rmw);
+                }
+            });
+            // Each call claims the next slot, overwriting the oldest duration once the buffer is
+            // full.
+            let ring = self.ring.as_ref().map(|ring| {
+                let store = ordering.store();
+                quote! {
+                    let __slot = #ring.1.fetch_add(1, #rmw) % #ring.0.len();
+                    #ring.0[__slot].store(#nanos, #store);
+                }
+            });
+            quote! {
+                #stats
+                #ring
+            }
+        } else if args.silent {
+            TokenStream::new()
+        } else if let Some(handler) = &args.handler {
+            // Passed to a function so the closure's argument types are inferred from its bound,
+            // and spanned at the closure so mismatching types are reported there.
+            let name = &self.name;
+            quote_spanned! {handler.span()=>
+                fn __time_it_handle(
+                    handler: impl ::core::ops::FnOnce(&'static str, ::core::time::Duration),
+                    name: &'static str,
+                    duration: ::core::time::Duration,
+                ) {
+                    handler(name, duration);
+                }
+                __time_it_handle(#handler, #name, __duration);
+            }
+        } else if args.sink.is_some() {
+            let name = &self.name;
+            quote! {::time_it::__private::send(#name, __duration);}
+        } else if args.in_span {
+            // The span has to declare the field, recording an undeclared field is a no-op.
+            let field = match &args.record {
+                Some(record) => quote! {#record},
+                None => quote! {"duration_ms"},
+            };
+            let tracing = tracing_crate();
+            quote! {
+                #tracing::Span::current().record(#field, __duration.as_secs_f64() * 1000.0);
+            }
+        } else if args.span {
+            let raw_ns = args
+                .raw_ns
+                .then(|| quote! {__span.record("duration_ns", __duration.as_nanos());});
+            let thread = args.thread.then(|| {
+                let thread = thread_name();
+                quote! {__span.record("thread", #thread.as_str());}
+            });
+            let runtime = args.runtime.then(|| {
+                let runtime = runtime_flavor();
+                quote! {__span.record("runtime", #runtime);}
+            });
+            let throughput = (args.bytes_arg.is_some() || args.bytes_ret).then(|| {
+                let throughput = throughput();
+                quote! {
+                    if let ::core::option::Option::Some(__bytes) = *__bytes {
+                        __span.record("bytes", __bytes);
+                    }
+                    if let ::core::option::Option::Some(__throughput) = #throughput {
+                        __span.record("throughput_mb_s", __throughput);
+                    }
+                }
+            });
+            let panicking = panicking();
+            let cancelled = args.on_cancel.then(|| {
+                quote! {
+                    else if !*__completed {
+                        __span.record("cancelled", true);
+                    }
+                }
+            });
+            let since_start = args.since_start.then(|| {
+                quote! {__span.record("t_ms", __since_start.as_secs_f64() * 1000.0);}
+            });
+            let polls = args
+                .poll_count
+                .then(|| quote! {__span.record("polls", *__polls);});
+            let sync_prep = args.split_await.then(|| {
+                quote! {
+                    __span.record(
+                        "sync_prep_ms",
+                        __sync_prep.unwrap_or(__duration).as_secs_f64() * 1000.0,
+                    );
+                }
+            });
+            let cpu = args.both_clocks.then(|| {
+                quote! {
+                    __span.record("wall_ms", __duration.as_secs_f64() * 1000.0);
+                    __span.record("cpu_ms", __cpu.as_secs_f64() * 1000.0);
+                }
+            });
+            let allocs = args.allocs.then(|| {
+                quote! {
+                    __span.record("allocations", __allocs.0);
+                    __span.record("allocated_bytes", __allocs.1);
+                }
+            });
+            // With a separate span level, the level given to `level` is used by a completion
+            // event, emitted in the span as it's no longer entered when the guard is dropped.
+            let event = args.span_level.is_some().then(|| {
+                let event = self.event(level, false);
+                quote! {__span.in_scope(|| { #event });}
+            });
+            quote! {
+                __span.record("duration_ms", __duration.as_secs_f64() * 1000.0);
+                #raw_ns
+                if #panicking {
+                    __span.record("panicked", true);
+                } #cancelled
+                #thread
+                #runtime
+                #throughput
+                #since_start
+                #polls
+                #sync_prep
+                #cpu
+                #allocs
+                #event
+            }
+        } else {
+            // Durations below the first tier use the default level, each tier overrides it from
+            // its duration on. The chain checks the longest duration first.
+            let tiered = |failed| {
+                let mut event = match args.slow_level {
+                    Some(slow_level) => self.event(slow_level, failed),
+                    None => self.leveled(|level| self.event(level, failed)),
+                };
+                if args.regression.is_some() {
+                    let warn_event = self.event(LogLevel::Warn, failed);
+                    event = quote! {
+                        if __regressed {
+                            #warn_event
+                        } else {
+                            #event
+                        }
+                    };
+                }
+                for (min, tier_level) in args.tiers.iter().flatten() {
+                    let min = duration_tokens(*min);
+                    let tier_event = self.event(*tier_level, failed);
+                    event = quote! {
+                        if __duration >= #min {
+                            #tier_event
+                        } else {
+                            #event
+                        }
+                    };
+                }
+                event
+            };
+            let ok_event = tiered(false);
+            // An `err_template` alone only changes the message, so failures keep the usual levels.
+            let err_event = match (args.err_level, &args.err_template) {
+                (Some(err_level), _) => Some(self.event(err_level, true)),
+                (None, Some(_)) => Some(tiered(true)),
+                (None, None) => None,
+            };
+            match err_event {
+                Some(err_event) => quote! {
+                    if *__failed {
+                        #err_event
+                    } else {
+                        #ok_event
+                    }
+                },
+                None => ok_event,
+            }
+        };
+
+        let log_line = match args.rate_limit {
+            Some(window) => rate_limited(window, args.ordering.unwrap_or_default(), &log_line),
+            None => log_line,
+        };
+        let log_line = match args.threshold {
+            Some(threshold) => {
+                let threshold = duration_tokens(threshold);
+                let summary = args.summarize.map(|calls| {
+                    let summary = self.summary(calls);
+                    quote! {else { #summary }}
+                });
+                quote! {
+                    if __duration >= #threshold {
+                        #log_line
+                    } #summary
+                }
+            }
+            None => log_line,
+        };
+        if args.skip_err {
+            quote! {
+                if !*__failed {
+                    #log_line
+                }
+            }
+        } else {
+            log_line
+        }
+    }
+
+    /// Adds a call below the `threshold` to the totals of `summarize` mode, reporting them
+    /// together every `calls` calls.
+    ///
+    /// The totals are updated independently, so a concurrent call may be counted in one summary
+    /// and have its duration added to the next.
+    fn summary(&self, calls: u64) -> TokenStream {
+        let rmw = self.args.ordering.unwrap_or_default().rmw();
+        let name = &self.name;
+        let target = self.event_target();
+        let event = if let Some(output) = self.args.output {
+            let print = output.macro_ident();
+            quote! {
+                ::std::#print!("[{}]: {} fast calls totaling {:?}", #name, #calls, __total);
+            }
+        } else if self.args.structured {
+            let static_fields = self.static_fields();
+            let name_field = self.name_field();
+            self.leveled(|level| {
+                let event_macro = self.event_macro(level);
+                quote! {
+                    #event_macro!(
+                        #target #name_field = #name, fast_calls = #calls,
+                        total_ms = __total.as_secs_f64() * 1000.0, #(#static_fields,)*
+                        "fast calls summarized"
+                    );
+                }
+            })
+        } else {
+            self.leveled(|level| {
+                let event_macro = self.event_macro(level);
+                quote! {
+                    #event_macro!(
+                        #target "[{}]: {} fast calls totaling {:?}", #name, #calls, __total
+                    );
+                }
+            })
+        };
+        quote! {
+            static __SUMMARY: (::core::sync::atomic::AtomicU64, ::core::sync::atomic::AtomicU64) = (
+                ::core::sync::atomic::AtomicU64::new(0),
+                ::core::sync::atomic::AtomicU64::new(0),
+            );
+            __SUMMARY
+                .1
+                .fetch_add(<u64 as ::core::convert::TryFrom<u128>>::try_from(__duration.as_nanos()).unwrap_or(u64::MAX), #rmw);
+            if __SUMMARY.0.fetch_add(1, #rmw) % #calls == #calls - 1 {
+                let __total = ::core::time::Duration::from_nanos(__SUMMARY.1.swap(0, #rmw));
+                #event
+            }
+        }
+    }
+
+    /// Event emitted at `level`, in the shape selected by the arguments. `failed` marks the event
+    /// as reporting a call that returned an error.
+    fn event(&self, level: LogLevel, failed: bool) -> TokenStream {
+        if self.args.structured {
+            self.structured_event(level, failed)
+        } else {
+            self.message_event(level, failed)
+        }
+    }
+
+    /// Level the measurement is passed to the global hook at: the level its event is emitted at, or
+    /// would be if the call were reported with an event and not filtered out. With `level_fn`, the
+    /// function is called for the hook too.
+    fn hook_level(&self) -> TokenStream {
+        let args = self.args;
+        let backend_level = |level: LogLevel| match self.backend {
+            Backend::Log => level.log_level(),
+            _ => level.tracing_level(),
+        };
+        let mut level = match &args.level_fn {
+            Some(level_fn) => quote_spanned! {level_fn.span()=> #level_fn()},
+            None => backend_level(args.level.unwrap_or_default()),
+        };
+        // Built in the order of `report`, so the levels override each other the same way.
+        if let (Some(slow_level), Some(threshold)) = (args.slow_level, args.threshold) {
+            let slow_level = backend_level(slow_level);
+            let threshold = duration_tokens(threshold);
+            level = quote! {if __duration >= #threshold { #slow_level } else { #level }};
+        }
+        if args.regression.is_some() {
+            let warn = backend_level(LogLevel::Warn);
+            level = quote! {if __regressed { #warn } else { #level }};
+        }
+        for (min, tier_level) in args.tiers.iter().flatten() {
+            let tier_level = backend_level(*tier_level);
+            let min = duration_tokens(*min);
+            level = quote! {if __duration >= #min { #tier_level } else { #level }};
+        }
+        if let Some(err_level) = args.err_level {
+            let err_level = backend_level(err_level);
+            level = quote! {if *__failed { #err_level } else { #level }};
+        }
+        level
+    }
+
+    /// Event built by `event` at the level of the arguments. With `level_fn`, the function is called
+    /// when the event is emitted and its result is matched to the event built for each level,
+    /// otherwise the event is built once at the fixed `level`.
+    fn leveled(&self, event: impl Fn(LogLevel) -> TokenStream) -> TokenStream {
+        let Some(level_fn) = &self.args.level_fn else {
+            return event(self.args.level.unwrap_or_default());
+        };
+        let tracing = tracing_crate();
+        let [error, warn, info, debug, trace] = [
+            LogLevel::Error,
+            LogLevel::Warn,
+            LogLevel::Info,
+            LogLevel::Debug,
+            LogLevel::Trace,
+        ]
+        .map(event);
+        // Spanned at the path so a function returning something else is reported there.
+        let level_tracing = tracing_crate_at(level_fn.span());
+        let level = quote_spanned! {level_fn.span()=>
+            let __level: #level_tracing::Level = #level_fn();
+        };
+        quote! {
+            #level
+            match __level {
+                #tracing::Level::ERROR => { #error }
+                #tracing::Level::WARN => { #warn }
+                #tracing::Level::INFO => { #info }
+                #tracing::Level::DEBUG => { #debug }
+                _ => { #trace }
+            }
+        }
+    }
+
+    /// Event with the name and duration interpolated into a formatted message.
+    fn message_event(&self, level: LogLevel, failed: bool) -> TokenStream {
+        let args = self.args;
+        let name = &self.name;
+        let precision = args.precision;
+        let format = args.format.unwrap_or_default();
+        // The unit set at build time only replaces the default `Debug` formatting. `defmt` has no
+        // `Debug` formatting of durations, so they are logged in microseconds otherwise.
+        let unit = args
+            .unit
+            .or_else(|| args.format.is_none().then(TimeUnit::configured).flatten())
+            .or((self.backend == Backend::Defmt).then_some(TimeUnit::Us));
+        let (duration_spec, duration) = match (&args.fmt_fn, unit, format) {
+            // Spanned at the path so an unresolved or mismatching function is reported there.
+            (Some(path), _, _) => (
+                "{}".to_owned(),
+                quote_spanned! {path.span()=> #path(__duration)},
+            ),
+            (None, Some(unit), _) => (unit.spec(precision), unit.accessor(precision)),
+            (None, None, DurationFormat::Debug) => ("{:?}".to_owned(), quote! {__duration}),
+            // A fixed number of decimals and a minimum width keep the durations of consecutive
+            // lines in the same columns, as long as they fit.
+            (None, None, DurationFormat::Aligned) => {
+                let width = args.width.unwrap_or(ALIGNED_WIDTH);
+                let precision = precision.unwrap_or(ALIGNED_PRECISION);
+                (
+                    format!("{{:>{width}.{precision}}} ms"),
+                    quote! {__duration.as_nanos() as f64 / 1_000_000.0},
+                )
+            }
+            (None, None, DurationFormat::Human) if args.sig_figs.is_some() => (
+                "{}".to_owned(),
+                significant_human(args.sig_figs.unwrap_or_default(), args.min_unit),
+            ),
+            (None, None, DurationFormat::Human) => {
+                // Each unit has its own default number of decimals, which a `precision` replaces.
+                let [secs, millis, micros] =
+                    [2, 1, 0].map(|default| usize::from(precision.unwrap_or(default)));
+                let floor = args.min_unit.map_or(0, TimeUnit::exponent);
+                let scaled = [
+                    (9, "s", secs, quote! {__duration.as_secs_f64()}),
+                    (6, "ms", millis, quote! {__nanos as f64 / 1_000_000.0}),
+                    (3, "µs", micros, quote! {__nanos as f64 / 1_000.0}),
+                ];
+                let (bounds, rendered): (Vec<_>, Vec<_>) = scaled
+                    .into_iter()
+                    .filter(|(exponent, ..)| *exponent >= floor)
+                    .map(|(exponent, suffix, decimals, value)| {
+                        let spec = format!("{{:.*}}{suffix}");
+                        (
+                            Literal::u128_unsuffixed(10u128.pow(exponent)),
+                            quote! {::std::format!(#spec, #decimals, #value)},
+                        )
+                    })
+                    .unzip();
+                // Durations below the `min_unit` are rendered as a fraction of it with a
+                // `precision`, and only as less than one of it otherwise.
+                let below = match (floor, precision) {
+                    (0, _) => quote! {::std::format!("{}ns", __nanos)},
+                    (_, Some(precision)) => {
+                        let spec = format!("{{:.{precision}}}{}", human_suffix(floor));
+                        let scale = Literal::f64_unsuffixed(10f64.powi(floor as i32));
+                        quote! {::std::format!(#spec, __nanos as f64 / #scale)}
+                    }
+                    (_, None) => {
+                        let below = format!("<1{}", human_suffix(floor));
+                        quote! {::std::borrow::ToOwned::to_owned(#below)}
+                    }
+                };
+                (
+                    "{}".to_owned(),
+                    quote! {
+                        {
+                            let __nanos = __duration.as_nanos();
+                            #(if __nanos >= #bounds { #rendered } else)* { #below }
+                        }
+                    },
+                )
+            }
+        };
+        let clock = match args.clock.unwrap_or_default() {
+            Clock::Wall | Clock::HighRes => "Execution time",
+            Clock::Cpu => "CPU time",
+        };
+        // Failures use the `err_template` if given, which replaces the `(failed)` marker.
+        let err_template = args.err_template.as_ref().filter(|_| failed);
+        let (mut message, mut message_args) = match err_template.or(args.template.as_ref()) {
+            Some(template) => {
+                let mut message = String::new();
+                let mut message_args = Vec::new();
+                for piece in template {
+                    match piece {
+                        TemplatePiece::Text(text) => message.push_str(text),
+                        TemplatePiece::Name => {
+                            message.push_str("{}");
+                            message_args.push(quote! {#name});
+                        }
+                        TemplatePiece::Duration => {
+                            message.push_str(&duration_spec);
+                            message_args.push(duration.clone());
+                        }
+                    }
+                }
+                (message, message_args)
+            }
+            None => (
+                format!("[{{}}]: {clock}: {duration_spec}"),
+                vec![quote! {#name}, duration],
+            ),
+        };
+        if failed && err_template.is_none() {
+            message.push_str(" (failed)");
+        }
+        // The guard also reports calls that unwind or are cancelled, which are told apart at
+        // runtime.
+        let panicking = panicking();
+        let cancelled = args
+            .on_cancel
+            .then(|| quote! {else if !*__completed { " (cancelled)" }});
+        message.push_str("{}");
+        message_args.push(quote! {
+            if #panicking { " (panicked)" } #cancelled else { "" }
+        });
+        // In `span` mode the arguments are a field of the span instead.
+        if self.log_args && !args.span {
+            message.push_str(", args: {}");
+            message_args.push(quote! {__args});
+        }
+        if self.log_result {
+            message.push_str(", returned: {}");
+            message_args.push(quote! {__result.as_deref().unwrap_or("<no value>")});
+        }
+        // Only failed calls carry the error, so successful ones stay as they are.
+        if failed && args.log_err_value {
+            message.push_str(", error: {}");
+            message_args.push(quote! {__error.as_deref().unwrap_or("<no value>")});
+        }
+        if args.thread {
+            message.push_str(", thread: {}");
+            message_args.push(thread_name());
+        }
+        if args.runtime {
+            message.push_str(", runtime: {}");
+            message_args.push(runtime_flavor());
+        }
+        if args.bytes_arg.is_some() || args.bytes_ret {
+            let throughput = throughput();
+            message.push_str(", throughput: {}");
+            message_args.push(quote! {
+                #throughput.map_or_else(
+                    || ::std::borrow::ToOwned::to_owned("unknown"),
+                    |__throughput| ::std::format!("{:.2} MB/s", __throughput),
+                )
+            });
+        }
+        if args.seq && !args.span {
+            message.push_str(", seq: {}");
+            message_args.push(quote! {__seq});
+        }
+        if args.corr_id && !args.span {
+            message.push_str(", corr_id: {:016x}");
+            message_args.push(quote! {__corr_id});
+        }
+        if args.depth && !args.span {
+            message.push_str(", depth: {}");
+            message_args.push(quote! {__depth});
+        }
+        if args.since_start {
+            message.push_str(", t: {:?}");
+            message_args.push(quote! {__since_start});
+        }
+        if args.poll_count && !args.span {
+            message.push_str(", polls: {}");
+            message_args.push(quote! {__polls});
+        }
+        // A future completing without suspending spent all of its time in synchronous code.
+        if args.split_await && !args.span {
+            message.push_str(", sync_prep: {:?}, total: {:?}");
+            message_args.push(quote! {__sync_prep.unwrap_or(__duration)});
+            message_args.push(quote! {__duration});
+        }
+        if args.both_clocks && !args.span {
+            message.push_str(", wall: {:?}, cpu: {:?}");
+            message_args.push(quote! {__duration});
+            message_args.push(quote! {__cpu});
+        }
+        if args.allocs {
+            message.push_str(", allocations: {} ({} bytes)");
+            message_args.push(quote! {__allocs.0});
+            message_args.push(quote! {__allocs.1});
+        }
+        if args.location {
+            // Spanned at the function name so the line points at the definition.
+            message.push_str(" at {}:{}");
+            message_args.push(quote_spanned! {self.location=> file!()});
+            message_args.push(quote_spanned! {self.location=> line!()});
+        }
+        let message = LitStr::new(&message, Span::call_site());
+        if let Some(output) = args.output {
+            let print = output.macro_ident();
+            return quote! {
+                ::std::#print!(#message, #(#message_args),*);
+            };
+        }
+        let event_macro = self.event_macro(level);
+        let target = self.event_target();
+        let raw_ns = args
+            .raw_ns
+            .then(|| quote! {duration_ns = __duration.as_nanos(),});
+        let chrome_trace = args.chrome_trace.then(|| {
+            let dur = duration_micros();
+            quote! {ts = *__ts, dur = #dur,}
+        });
+        let kind = self.kind.map(|kind| quote! {kind = #kind,});
+        let static_fields = self.static_fields();
+        quote! {
+            #event_macro!(
+                #target #raw_ns #chrome_trace #kind #(#static_fields,)* #message,
+                #(#message_args),*
+            );
+        }
+    }
+
+    /// Event emitted when the timed code starts, in `trace_entry` mode, at the same level and in
+    /// the same shape as the completion event.
+    fn entry_event(&self) -> TokenStream {
+        let args = self.args;
+        let name = &self.name;
+        let target = self.event_target();
+        let static_fields = self.static_fields();
+        if args.structured {
+            let name_field = self.name_field();
+            let mut fields = vec![quote! {#name_field = #name}];
+            fields.extend(static_fields.iter().cloned());
+            if self.log_args {
+                fields.push(quote! {args = __args.as_str()});
+            }
+            if args.seq {
+                fields.push(quote! {seq = __seq});
+            }
+            if args.corr_id {
+                fields.push(quote! {corr_id = %format_args!("{:016x}", __corr_id)});
+            }
+            return self.leveled(|level| {
+                let event_macro = self.event_macro(level);
+                quote! {
+                    #event_macro!(#target #(#fields,)* "execution started");
+                }
+            });
+        }
+        let mut message = String::from("[{}]: Started");
+        let mut message_args = vec![quote! {#name}];
+        if self.log_args {
+            message.push_str(", args: {}");
+            message_args.push(quote! {__args});
+        }
+        if args.seq {
+            message.push_str(", seq: {}");
+            message_args.push(quote! {__seq});
+        }
+        if args.corr_id {
+            message.push_str(", corr_id: {:016x}");
+            message_args.push(quote! {__corr_id});
+        }
+        let message = LitStr::new(&message, Span::call_site());
+        if let Some(output) = args.output {
+            let print = output.macro_ident();
+            return quote! {
+                ::std::#print!(#message, #(#message_args),*);
+            };
+        }
+        self.leveled(|level| {
+            let event_macro = self.event_macro(level);
+            quote! {
+                #event_macro!(#target #(#static_fields,)* #message, #(#message_args),*);
+            }
+        })
+    }
+
+    /// `tracing` event carrying the name and duration as structured fields.
+    fn structured_event(&self, level: LogLevel, failed: bool) -> TokenStream {
+        let name = &self.name;
+        let name_field = self.name_field();
+        let mut fields = vec![
+            quote! {#name_field = #name},
+            quote! {duration_ms = __duration.as_secs_f64() * 1000.0},
+        ];
+        if failed {
+            fields.push(quote! {failed = true});
+        }
+        // Recorded as `None`, i.e. left out, unless the call unwinds.
+        let panicking = panicking();
+        fields.push(quote! {panicked = #panicking.then_some(true)});
+        if self.args.on_cancel {
+            fields.push(quote! {
+                cancelled = (!*__completed && !#panicking).then_some(true)
+            });
+        }
+        if self.args.raw_ns {
+            fields.push(quote! {duration_ns = __duration.as_nanos()});
+        }
+        if self.args.chrome_trace {
+            let dur = duration_micros();
+            fields.push(quote! {ts = *__ts});
+            fields.push(quote! {dur = #dur});
+        }
+        if let Some(kind) = self.kind {
+            fields.push(quote! {kind = #kind});
+        }
+        fields.extend(self.static_fields());
+        if let Some(Clock::Cpu) = self.args.clock {
+            fields.push(quote! {clock = "cpu"});
+        }
+        if self.log_args {
+            fields.push(quote! {args = __args.as_str()});
+        }
+        if self.log_result {
+            fields.push(quote! {result = __result.as_deref()});
+        }
+        if failed && self.args.log_err_value {
+            fields.push(quote! {error = __error.as_deref()});
+        }
+        if self.args.thread {
+            let thread = thread_name();
+            fields.push(quote! {thread = #thread.as_str()});
+        }
+        if self.args.runtime {
+            let runtime = runtime_flavor();
+            fields.push(quote! {runtime = #runtime});
+        }
+        // Recorded as `None`, i.e. left out, while unknown.
+        if self.args.bytes_arg.is_some() || self.args.bytes_ret {
+            let throughput = throughput();
+            fields.push(quote! {bytes = *__bytes});
+            fields.push(quote! {throughput_mb_s = #throughput});
+        }
+        if self.args.seq {
+            fields.push(quote! {seq = *__seq});
+        }
+        if self.args.corr_id {
+            fields.push(quote! {corr_id = %format_args!("{:016x}", __corr_id)});
+        }
+        if self.args.depth {
+            fields.push(quote! {depth = *__depth});
+        }
+        if self.args.since_start {
+            fields.push(quote! {t_ms = __since_start.as_secs_f64() * 1000.0});
+        }
+        if self.args.poll_count {
+            fields.push(quote! {polls = *__polls});
+        }
+        if self.args.split_await {
+            fields.push(quote! {
+                sync_prep_ms = __sync_prep.unwrap_or(__duration).as_secs_f64() * 1000.0
+            });
+        }
+        if self.args.both_clocks {
+            fields.push(quote! {wall_ms = __duration.as_secs_f64() * 1000.0});
+            fields.push(quote! {cpu_ms = __cpu.as_secs_f64() * 1000.0});
+        }
+        if self.args.allocs {
+            fields.push(quote! {allocations = __allocs.0});
+            fields.push(quote! {allocated_bytes = __allocs.1});
+        }
+        if self.args.location {
+            fields.push(quote_spanned! {self.location=> file = file!()});
+            fields.push(quote_spanned! {self.location=> line = line!()});
+        }
+        let event_macro = self.event_macro(level);
+        let target = self.event_target();
+        quote! {
+            #event_macro!(#target #(#fields,)* "execution timed");
+        }
+    }
+
+    /// Creates `__span`, the span covering the function body in `span` mode.
+    ///
+    /// The `duration_ms` field is left empty and recorded once the body completes.
+    fn span(&self) -> TokenStream {
+        let name = &self.name;
+        let tracing = tracing_crate();
+        let level = self
+            .args
+            .span_level
+            .or(self.args.level)
+            .unwrap_or_default()
+            .tracing_level();
+        let target = self.event_target();
+        let mut fields = vec![
+            quote! {duration_ms = #tracing::field::Empty},
+            quote! {panicked = #tracing::field::Empty},
+        ];
+        if self.args.on_cancel {
+            fields.push(quote! {cancelled = #tracing::field::Empty});
+        }
+        if self.args.raw_ns {
+            fields.push(quote! {duration_ns = #tracing::field::Empty});
+        }
+        if self.args.thread {
+            fields.push(quote! {thread = #tracing::field::Empty});
+        }
+        if self.args.runtime {
+            fields.push(quote! {runtime = #tracing::field::Empty});
+        }
+        if self.args.bytes_arg.is_some() || self.args.bytes_ret {
+            fields.push(quote! {bytes = #tracing::field::Empty});
+            fields.push(quote! {throughput_mb_s = #tracing::field::Empty});
+        }
+        if let Some(kind) = self.kind {
+            fields.push(quote! {kind = #kind});
+        }
+        fields.extend(self.static_fields());
+        if let Some(Clock::Cpu) = self.args.clock {
+            fields.push(quote! {clock = "cpu"});
+        }
+        if self.log_args {
+            fields.push(quote! {args = __args.as_str()});
+        }
+        if self.args.seq {
+            fields.push(quote! {seq = __seq});
+        }
+        if self.args.corr_id {
+            fields.push(quote! {corr_id = %format_args!("{:016x}", __corr_id)});
+        }
+        if self.args.depth {
+            fields.push(quote! {depth = __depth});
+        }
+        if self.args.since_start {
+            fields.push(quote! {t_ms = #tracing::field::Empty});
+        }
+        if self.args.poll_count {
+            fields.push(quote! {polls = #tracing::field::Empty});
+        }
+        if self.args.split_await {
+            fields.push(quote! {sync_prep_ms = #tracing::field::Empty});
+        }
+        if self.args.both_clocks {
+            fields.push(quote! {wall_ms = #tracing::field::Empty});
+            fields.push(quote! {cpu_ms = #tracing::field::Empty});
+        }
+        if self.args.allocs {
+            fields.push(quote! {allocations = #tracing::field::Empty});
+            fields.push(quote! {allocated_bytes = #tracing::field::Empty});
+        }
+        if self.args.location {
+            fields.push(quote_spanned! {self.location=> file = file!()});
+            fields.push(quote_spanned! {self.location=> line = line!()});
+        }
+        quote! {
+            let __span = #tracing::span!(#target #level, #name, #(#fields),*);
+        }
+    }
+
+    /// Key of the field naming the function in structured events, `function` unless `name_field`
+    /// is given.
+    fn name_field(&self) -> Ident {
+        match &self.args.name_field {
+            Some(name_field) => Ident::new(&name_field.value(), name_field.span()),
+            None => ident("function"),
+        }
+    }
+
+    /// The constant fields given with `category` and `fields`, as `key = literal` pairs.
+    fn static_fields(&self) -> Vec<TokenStream> {
+        let category = self
+            .args
+            .category
+            .iter()
+            .map(|category| quote! {category = #category});
+        let fields = self
+            .args
+            .fields
+            .iter()
+            .flatten()
+            .map(|(key, value)| quote! {#key = #value});
+        category.chain(fields).collect()
+    }
+
+    /// Path of the macro emitting events at `level`: the one given with `via`, which is invoked for
+    /// every level, or the logging macro of the backend.
+    fn event_macro(&self, level: LogLevel) -> TokenStream {
+        if let Some(path) = &self.args.via {
+            return quote! {#path};
+        }
+        let backend = self.backend.path();
+        let level = level.macro_ident();
+        quote! {#backend::#level}
+    }
+
+    /// `target:` and `parent:` prefixes of the event macro invocation. The target is the one given
+    /// with `target`, `TIME_IT_DEFAULT_TARGET` if set at build time, or [`DEFAULT_TARGET`].
+    fn event_target(&self) -> TokenStream {
+        let target = match &self.args.target {
+            Some(target) => Some(quote! {target: #target,}),
+            // Macros given with `via` may not accept a target, and `defmt` has none.
+            None if self.args.via.is_none() && self.backend != Backend::Defmt => {
+                let target = DEFAULT_TARGET_VAR.unwrap_or(DEFAULT_TARGET);
+                Some(quote! {target: #target,})
+            }
+            None => None,
+        };
+        // Only events are given a `parent`, as it can't be combined with `span`. The guard only
+        // holds a reference to it, which the argument is coerced from.
+        let parent = self.args.parent.is_some().then(|| {
+            let tracing = tracing_crate();
+            quote! {
+                parent: <::core::option::Option<#tracing::Id> as ::core::clone::Clone>::clone(
+                    &__parent
+                ),
+            }
+        });
+        quote! {#target #parent}
+    }
+}
+
+/// A value stored in the guard so it's available when the event is emitted.
+///
+/// While the event is emitted, the value is bound by reference under `ident`.
+struct GuardField {
+    ident: Ident,
+    ty: TokenStream,
+    init: TokenStream,
+}
+
+/// The drop guard measuring the body, declared and created as `__guard`.
+struct Guard {
+    fields: Vec<GuardField>,
+    /// Whether the body updates fields after running.
+    mutable: bool,
+    /// Whether the measurement can be stopped early by setting the `__stop` field.
+    stoppable: bool,
+    /// Whether the time accumulated in the `__busy` field is reported instead of the elapsed
+    /// time, in `exclude_idle` mode.
+    busy: bool,
+    /// Name of the exclusions subtracted from the elapsed time, in `exclude` mode.
+    excluded: Option<LitStr>,
+    clock: Clock,
+    /// Function replacing `clock`, whose result provides the elapsed time.
+    clock_fn: Option<Path>,
+    /// Code run when the guard is dropped, whether or not the measurement is reported.
+    exit: Option<TokenStream>,
+}
+
+impl Guard {
+    /// Declares and creates `__guard`, which emits `log_line` with the elapsed time once dropped.
+    fn expand(&self, log_line: &TokenStream) -> TokenStream {
+        let mut fields: Vec<_> = self.fields.iter().collect();
+        let stop = GuardField {
+            ident: ident("__stop"),
+            ty: quote! {::core::option::Option<::core::time::Duration>},
+            init: quote! {::core::option::Option::None},
+        };
+        let elapsed = elapsed(quote! {self}, self.clock_fn.is_some(), self.clock);
+        let duration = if self.stoppable {
+            fields.push(&stop);
+            quote! {self.__stop.unwrap_or_else(|| #elapsed)}
+        } else {
+            elapsed
+        };
+        let duration = if self.busy {
+            quote! {self.__busy}
+        } else {
+            duration
+        };
+        let duration = match &self.excluded {
+            Some(name) => quote! {
+                ::core::time::Duration::saturating_sub(
+                    #duration,
+                    crate::time_it_exclusions::exit(#name, self.__outer_excluded),
+                )
+            },
+            None => duration,
+        };
+        let idents: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+        let types = fields.iter().map(|field| &field.ty);
+        let inits = fields.iter().map(|field| &field.init);
+        let mutability = self.mutable.then(|| quote! {mut});
+        let exit = &self.exit;
+        // The type returned by a `clock_fn` can't be named, so the guard instead stores a closure
+        // reading the elapsed time from it.
+        let (params, args, start_ty, start) = match &self.clock_fn {
+            Some(now) => (
+                Some(quote! {<__Elapsed: ::core::ops::Fn() -> ::core::time::Duration>}),
+                Some(quote! {<__Elapsed>}),
+                quote! {__Elapsed},
+                // Spanned at the path so an unresolved function or missing `elapsed` is reported there.
+                quote_spanned! {now.span()=>
+                    {
+                        let __now = #now();
+                        move || __now.elapsed()
+                    }
+                },
+            ),
+            None => {
+                let clock = self.clock.instant_type();
+                (None, None, clock.clone(), quote! {#clock::now()})
+            }
+        };
+        quote! {
+            struct __TimeItGuard #params {
+                __start: #start_ty,
+                #(#idents: #types,)*
+            }
+            impl #params ::core::ops::Drop for __TimeItGuard #args {
+                fn drop(&mut self) {
+                    let __duration = #duration;
+                    #exit
+                    #(let #idents = &self.#idents;)*
+                    #log_line
+                }
+            }
+            // `__start` is initialized last so setting up the other fields isn't measured.
+            let #mutability __guard = __TimeItGuard {
+                #(#idents: #inits,)*
+                __start: #start,
+            };
+        }
+    }
+}
+
+/// Calls `closure` right away, through a function taking an `FnOnce` so the closure is one too.
+///
+/// A closure called directly is inferred as `FnMut` when its body mutates what it captures, and
+/// `FnMut` closures can't return references borrowed from their captures, such as those of a
+/// method taking `&mut self` and returning `&mut` into `self`.
+fn call_once(closure: TokenStream) -> TokenStream {
+    quote! {
+        {
+            fn __time_it_call<__R>(f: impl ::core::ops::FnOnce() -> __R) -> __R {
+                f()
+            }
+            __time_it_call(#closure)
+        }
+    }
+}
+
+/// Expression reading the time elapsed since `guard` was created, through the closure stored in
+/// it if a `clock_fn` is used.
+fn elapsed(guard: TokenStream, clock_fn: bool, clock: Clock) -> TokenStream {
+    if clock_fn {
+        quote! {(#guard.__start)()}
+    } else {
+        clock.elapsed(quote! {#guard.__start})
+    }
+}
+
+/// How a function timed in `future` mode returns its future.
+#[derive(Clone, Copy)]
+enum ReturnedFuture {
+    /// As an `impl Future`.
+    Opaque,
+    /// As a `Pin<Box<dyn Future>>`.
+    Pinned,
+}
+
+/// Checks that a function timed in `future` mode returns a future it can wrap.
+fn returned_future(sig: &Signature) -> syn::Result<ReturnedFuture> {
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "`future` is only needed for functions returning a future without being `async`, \
+             `async fn`s are already timed until they complete",
+        ));
+    }
+    let returned = match &sig.output {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::ImplTrait(_) => Some(ReturnedFuture::Opaque),
+            Type::Path(path)
+                if path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "Pin") =>
+            {
+                Some(ReturnedFuture::Pinned)
+            }
+            _ => None,
+        },
+        ReturnType::Default => None,
+    };
+    returned.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &sig.output,
+            "`future` requires a function returning `impl Future` or `Pin<Box<dyn Future>>`",
+        )
+    })
+}
+
+/// Whether `attr` is a `#[time_it]` attribute, possibly written with a path to this crate.
+fn is_time_it(attr: &syn::Attribute) -> bool {
+    attr.path()
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "time_it")
+}
+
+/// Whether the function is declared as returning a type named `Result`, such as
+/// `std::result::Result` or `std::io::Result`.
+fn returns_result(output: &ReturnType) -> bool {
+    let ReturnType::Type(_, ty) = output else {
+        return false;
+    };
+    let Type::Path(path) = &**ty else {
+        return false;
+    };
+    path.path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Result")
+}
+
+/// The argument bound to the name given to `key`, e.g. `label_arg`.
+fn named_arg<'a>(sig: &'a Signature, name: &LitStr, key: &str) -> syn::Result<&'a Ident> {
+    let names = named_args(sig);
+    if let Some(arg) = names.iter().find(|arg| arg.unraw() == name.value()) {
+        return Ok(arg);
+    }
+    let names: Vec<_> = names
+        .iter()
+        .map(|arg| format!("`{}`", arg.unraw()))
+        .collect();
+    let hint = if names.is_empty() {
+        "which has no argument bound to a plain name".to_owned()
+    } else {
+        format!("expected one of {}", names.join(", "))
+    };
+    Err(syn::Error::new_spanned(
+        name,
+        format!(
+            "`{key}` names no argument of `{}`, {hint}",
+            sig.ident.unraw()
+        ),
+    ))
+}
+
+/// Identifiers of the arguments bound to a plain name, excluding `self`.
+///
+/// Arguments bound through a destructuring pattern have no single name and are skipped.
+fn named_args(sig: &Signature) -> Vec<&Ident> {
+    sig.inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(PatType { pat, .. }) => match &**pat {
+                Pat::Ident(pat) => Some(&pat.ident),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+/// Moves the arguments bound to a plain name into the body, for `include_drops`. Arguments are
+/// dropped once the function returns, after its body, and so after the guard. Rebinding them at
+/// the start of the body drops them with its locals instead, before the guard. Their `mut` moves
+/// along, as the arguments themselves are then never mutated.
+fn rebind_args(input: &ItemFn) -> ItemFn {
+    let mut input = input.clone();
+    let mut rebinds: Vec<Stmt> = Vec::new();
+    for arg in &mut input.sig.inputs {
+        let FnArg::Typed(PatType { pat, .. }) = arg else {
+            continue;
+        };
+        let Pat::Ident(pat) = &mut **pat else {
+            continue;
+        };
+        if pat.by_ref.is_some() || pat.subpat.is_some() {
+            continue;
+        }
+        let mutability = pat.mutability.take();
+        let ident = &pat.ident;
+        rebinds.push(parse_quote! {let #mutability #ident = #ident;});
+    }
+    input.block.stmts.splice(0..0, rebinds);
+    input
+}
+
+/// Moves the body of `input` into a nested function, for `inner_fn`. Returns the function calling
+/// it with its arguments, which is then timed as written, and the nested function.
+///
+/// The nested function takes the signature of `input`, so the body is checked exactly as written.
+/// Its type and const parameters are passed explicitly, as they may only appear in the return type,
+/// while lifetimes are left to inference, as late bound ones can't be given. Arguments bound by
+/// patterns are given a name to pass them on under.
+fn hoist_body(input: &ItemFn) -> syn::Result<(ItemFn, ItemFn)> {
+    let sig = &input.sig;
+    if let Some(receiver) = sig.receiver() {
+        return Err(syn::Error::new_spanned(
+            receiver,
+            "`inner_fn` cannot be used on methods taking `self`, which a nested function can't \
+             receive",
+        ));
+    }
+    // Nested functions can't refer to the `Self` of the item they are defined in.
+    if mentions_ident(input.to_token_stream(), "Self") {
+        return Err(syn::Error::new_spanned(
+            &sig.ident,
+            "`inner_fn` cannot be used on functions mentioning `Self`, which a nested function \
+             can't refer to",
+        ));
+    }
+    let inner_ident = Ident::new("__time_it_inner", Span::mixed_site());
+    let mut inner = ItemFn {
+        attrs: vec![parse_quote! {#[inline]}],
+        vis: syn::Visibility::Inherited,
+        sig: sig.clone(),
+        block: input.block.clone(),
+    };
+    inner.sig.ident = inner_ident.clone();
+    inner.sig.abi = None;
+
+    let mut outer = input.clone();
+    let mut call_args: Vec<Ident> = Vec::new();
+    for (index, arg) in outer.sig.inputs.iter_mut().enumerate() {
+        let FnArg::Typed(PatType { pat, .. }) = arg else {
+            continue;
+        };
+        let ident = match &**pat {
+            Pat::Ident(pat) if pat.subpat.is_none() => pat.ident.clone(),
+            _ => format_ident!("__time_it_arg{}", index),
+        };
+        // Only the nested function binds the argument as written, e.g. `mut`.
+        **pat = parse_quote! {#ident};
+        call_args.push(ident);
+    }
+    let params: Vec<_> = sig
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            syn::GenericParam::Type(param) => Some(&param.ident),
+            syn::GenericParam::Const(param) => Some(&param.ident),
+            syn::GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+    let turbofish = (!params.is_empty()).then(|| quote! {::<#(#params),*>});
+    let mut call = quote! {#inner_ident #turbofish(#(#call_args),*)};
+    if sig.unsafety.is_some() {
+        call = quote! {unsafe { #call }};
+    }
+    if sig.asyncness.is_some() {
+        call = quote! {#call.await};
+    }
+    outer.block = parse_quote! {{ #call }};
+    Ok((outer, inner))
+}
+
+/// Whether `tokens` contain the identifier `name`, at any depth.
+fn mentions_ident(tokens: TokenStream, name: &str) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == name,
+        TokenTree::Group(group) => mentions_ident(group.stream(), name),
+        _ => false,
+    })
+}
+
+/// Whether the function returns `()`, either implicitly or explicitly, or never returns.
+fn returns_nothing(output: &ReturnType) -> bool {
+    match output {
+        ReturnType::Default => true,
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Tuple(tuple) => tuple.elems.is_empty(),
+            Type::Never(_) => true,
+            _ => false,
+        },
+    }
+}
+
+/// Name of the environment variable enabling the code named `name` in `env_gate` mode: `name`
+/// in upper case, with anything but ASCII letters and digits replaced by `_`, prefixed by
+/// `TIME_IT_`.
+fn env_gate_var(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("TIME_IT_{name}")
+}
+
+/// Binds `__seq` to the sequence number of the current call, counted from 0 by a `static` local to
+/// the timed code.
+fn seq_number(args: &Args) -> TokenStream {
+    let rmw = args.ordering.unwrap_or_default().rmw();
+    quote! {
+        static __SEQ: ::core::sync::atomic::AtomicU64 = ::core::sync::atomic::AtomicU64::new(0);
+        let __seq = __SEQ.fetch_add(1, #rmw);
+    }
+}
+
+/// Wraps `report` so it runs at most once per `window`, in `rate_limit` mode.
+///
+/// The time of the last report is kept in nanoseconds since the first check, and a report is
+/// claimed by swapping it for the current time, so concurrent calls can't both report. The window
+/// starts at the last report rather than at fixed intervals.
+fn rate_limited(window: Duration, ordering: MemoryOrdering, report: &TokenStream) -> TokenStream {
+    let window = u64::try_from(window.as_nanos()).unwrap_or(u64::MAX);
+    let (rmw, load) = (ordering.rmw(), ordering.load());
+    quote! {
+        static __BASE: ::std::sync::OnceLock<::std::time::Instant> = ::std::sync::OnceLock::new();
+        // `u64::MAX` until the first report.
+        static __LAST_REPORT: ::core::sync::atomic::AtomicU64 =
+            ::core::sync::atomic::AtomicU64::new(u64::MAX);
+        let __now = <u64 as ::core::convert::TryFrom<u128>>::try_from(__BASE.get_or_init(::std::time::Instant::now).elapsed().as_nanos())
+            .unwrap_or(u64::MAX);
+        let __last = __LAST_REPORT.load(#load);
+        if (__last == u64::MAX || __now.saturating_sub(__last) >= #window)
+            && __LAST_REPORT
+                .compare_exchange(__last, __now, #rmw, #load)
+                .is_ok()
+        {
+            #report
+        }
+    }
+}
+
+/// Number of calls only recording the baseline of `regression` mode, before any is compared to it.
+const REGRESSION_WARMUP: u64 = 10;
+
+/// Binds `__regressed` to whether the call took more than `factor` times the baseline, in
+/// `regression` mode.
+///
+/// The baseline is the fastest call so far, which is steady once the caches are warm and isn't
+/// dragged up by the slow calls it should flag. The first calls only record it, as the fastest
+/// of a handful of calls, some of them cold, is not representative yet.
+fn regressed(factor: f64, ordering: MemoryOrdering) -> TokenStream {
+    let rmw = ordering.rmw();
+    quote! {
+        let __regressed = {
+            static __CALLS: ::core::sync::atomic::AtomicU64 =
+                ::core::sync::atomic::AtomicU64::new(0);
+            // `u64::MAX` until the first call.
+            static __FASTEST: ::core::sync::atomic::AtomicU64 =
+                ::core::sync::atomic::AtomicU64::new(u64::MAX);
+            let __nanos = <u64 as ::core::convert::TryFrom<u128>>::try_from(__duration.as_nanos())
+                .unwrap_or(u64::MAX);
+            let __baseline = __FASTEST.fetch_min(__nanos, #rmw);
+            __CALLS.fetch_add(1, #rmw) >= #REGRESSION_WARMUP
+                && __nanos as f64 > __baseline as f64 * #factor
+        };
+    }
+}
+
+/// Binds `__depth` to the number of calls to the function the current thread is already in,
+/// counted by a thread local the guard decrements again when dropped.
+fn call_depth() -> TokenStream {
+    quote! {
+        ::std::thread_local! {
+            static __DEPTH: ::core::cell::Cell<u64> = const { ::core::cell::Cell::new(0) };
+        }
+        let __depth = __DEPTH.with(|depth| {
+            let outer = depth.get();
+            depth.set(outer + 1);
+            outer
+        });
+    }
+}
+
+/// Binds `__corr_id` to the correlation id of the current call: the hash of nothing under a new
+/// `RandomState`, whose keys are randomly seeded per thread and change with every instance. Ids are
+/// then unique across calls, functions and processes with overwhelming probability, without
+/// any shared state.
+fn corr_id() -> TokenStream {
+    quote! {
+        let __corr_id = ::std::hash::BuildHasher::hash_one(&::std::hash::RandomState::new(), ());
+    }
+}
+
+/// Binds `__parent`, the span given to `parent`, converted to its id when the timed code starts.
+///
+/// Spanned at the expression so a value that isn't a span is reported there.
+fn parent(parent: &Expr) -> TokenStream {
+    let tracing = tracing_crate();
+    quote_spanned! {parent.span()=>
+        let __parent: ::core::option::Option<#tracing::Id> = ::core::convert::Into::into(#parent);
+    }
+}
+
+/// Guard field keeping the parent span id bound by [`parent`].
+fn parent_field() -> GuardField {
+    let tracing = tracing_crate();
+    GuardField {
+        ident: ident("__parent"),
+        ty: quote! {::core::option::Option<#tracing::Id>},
+        init: quote! {__parent},
+    }
+}
+
+/// Guard field keeping the correlation id bound by [`corr_id`].
+fn corr_id_field() -> GuardField {
+    GuardField {
+        ident: ident("__corr_id"),
+        ty: quote! {u64},
+        init: quote! {__corr_id},
+    }
+}
+
+/// Guard field keeping the sequence number bound by [`seq_number`].
+fn seq_field() -> GuardField {
+    GuardField {
+        ident: ident("__seq"),
+        ty: quote! {u64},
+        init: quote! {__seq},
+    }
+}
+
+/// Guard field keeping the CPU time of the current thread from before the call, in `both_clocks`
+/// mode.
+fn cpu_start_field() -> GuardField {
+    GuardField {
+        ident: ident("__cpu"),
+        ty: quote! {::cpu_time::ThreadTime},
+        init: quote! {::cpu_time::ThreadTime::now()},
+    }
+}
+
+/// Guard field keeping the allocation counts of the current thread from before the call.
+fn allocs_field() -> GuardField {
+    GuardField {
+        ident: ident("__allocs"),
+        ty: quote! {(u64, u64)},
+        init: quote! {crate::__time_it_allocs::counts()},
+    }
+}
+
+/// Guard field keeping the wall clock time at which the call started, in microseconds since the
+/// Unix epoch, or 0 if the system clock is set before it.
+fn start_time_field() -> GuardField {
+    GuardField {
+        ident: ident("__ts"),
+        ty: quote! {u64},
+        init: quote! {
+            ::std::time::SystemTime::now()
+                .duration_since(::std::time::UNIX_EPOCH)
+                .map_or(0, |__since| <u64 as ::core::convert::TryFrom<u128>>::try_from(__since.as_micros()).unwrap_or(u64::MAX))
+        },
+    }
+}
+
+/// Guard field keeping the time the call started at, relative to the baseline of `since_start`.
+fn since_start_field() -> GuardField {
+    GuardField {
+        ident: ident("__since_start"),
+        ty: quote! {::core::time::Duration},
+        init: quote! {::time_it::__private::since_start()},
+    }
+}
+
+/// Expression converting `__duration` to whole microseconds, saturating at `u64::MAX`.
+fn duration_micros() -> TokenStream {
+    quote! {<u64 as ::core::convert::TryFrom<u128>>::try_from(__duration.as_micros()).unwrap_or(u64::MAX)}
+}
+
+/// Expression evaluating to whether the current thread is unwinding. Panics can't be detected
+/// without `std`, and usually abort there anyway, so this is always `false` with `no_std`.
+fn panicking() -> TokenStream {
+    if cfg!(feature = "no_std") {
+        quote! {false}
+    } else {
+        quote! {::std::thread::panicking()}
+    }
+}
+
+/// Expression evaluating to the name of the current thread, or its id for unnamed threads.
+fn thread_name() -> TokenStream {
+    quote! {
+        {
+            let __thread = ::std::thread::current();
+            __thread
+                .name()
+                .map_or_else(
+                    || ::std::format!("{:?}", __thread.id()),
+                    ::std::borrow::ToOwned::to_owned,
+                )
+        }
+    }
+}
+
+/// Expression evaluating to the flavor of the tokio runtime driving the current thread, e.g.
+/// `"tokio_multi_thread"`, or `"unknown"` outside of any runtime.
+fn runtime_flavor() -> TokenStream {
+    quote! {
+        match ::tokio::runtime::Handle::try_current() {
+            ::core::result::Result::Ok(__handle) => match __handle.runtime_flavor() {
+                ::tokio::runtime::RuntimeFlavor::CurrentThread => "tokio_current_thread",
+                ::tokio::runtime::RuntimeFlavor::MultiThread => "tokio_multi_thread",
+                // The enum is non-exhaustive, with unstable flavors behind `tokio_unstable`.
+                _ => "tokio",
+            },
+            ::core::result::Result::Err(_) => "unknown",
+        }
+    }
+}
+
+/// Expression evaluating to the number of megabytes, of a million bytes each, processed per second
+/// of `__duration`, or `None` if the number of bytes is unknown or no time was measured.
+fn throughput() -> TokenStream {
+    quote! {
+        __bytes
+            .filter(|_| !__duration.is_zero())
+            .map(|__bytes| __bytes as f64 / 1_000_000.0 / __duration.as_secs_f64())
+    }
+}
+
+/// Expression rendering `__duration` in the human format, rounded to `figures` significant
+/// figures, e.g. `1.23ms`, `123µs` or `1.23s` with 3.
+///
+/// The nanoseconds are rounded before the unit is chosen, so a duration rounding up to the next
+/// unit is rendered in it, as `1.00ms` rather than `1000µs`. Nanoseconds are whole, so they are
+/// rendered without decimals. Durations below the `min_unit` are rendered as a fraction of it,
+/// as `0.500µs`.
+fn significant_human(figures: u8, min_unit: Option<TimeUnit>) -> TokenStream {
+    let figures = u32::from(figures);
+    let floor = min_unit.map_or(0, TimeUnit::exponent);
+    let (bounds, exponents): (Vec<_>, Vec<_>) = [9, 6, 3]
+        .into_iter()
+        .filter(|exponent| *exponent > floor)
+        .map(|exponent| (Literal::u128_unsuffixed(10u128.pow(exponent)), exponent))
+        .unzip();
+    let suffixes = exponents.iter().map(|exponent| human_suffix(*exponent));
+    let floor_suffix = human_suffix(floor);
+    quote! {
+        {
+            let __digits = |nanos: u128| nanos.checked_ilog10().map_or(1, |log| log + 1);
+            let mut __nanos = __duration.as_nanos();
+            if __digits(__nanos) > #figures {
+                let __scale = 10u128.pow(__digits(__nanos) - #figures);
+                __nanos = (__nanos + __scale / 2) / __scale * __scale;
+            }
+            let (__exponent, __suffix): (u32, &str) = #(if __nanos >= #bounds {
+                (#exponents, #suffixes)
+            } else)* {
+                (#floor, #floor_suffix)
+            };
+            let __decimals = if __exponent == 0 {
+                0
+            } else {
+                (#figures + __exponent).saturating_sub(__digits(__nanos)) as usize
+            };
+            let __value = __nanos as f64 / 10f64.powi(__exponent as i32);
+            ::std::format!("{:.*}{}", __decimals, __value, __suffix)
+        }
+    }
+}
+
+/// Suffix of the unit whose nanoseconds are the `exponent`th power of ten in the human format.
+fn human_suffix(exponent: u32) -> &'static str {
+    match exponent {
+        9 => "s",
+        6 => "ms",
+        3 => "µs",
+        _ => "ns",
+    }
+}
+
+/// Expression constructing `duration` as a `core::time::Duration`.
+fn duration_tokens(duration: Duration) -> TokenStream {
+    let secs = duration.as_secs();
+    let nanos = duration.subsec_nanos();
+    quote! {::core::time::Duration::new(#secs, #nanos)}
+}
+
+fn ident(name: &str) -> Ident {
+    Ident::new(name, Span::call_site())
+}
+
+/// The crate whose logging macros are used to emit events.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Tracing,
+    Log,
+    Defmt,
+}
+
+impl Backend {
+    /// `tracing` is used unless the `log` or `defmt` feature is enabled without the `tracing`
+    /// feature, with `log` taking precedence over `defmt`.
+    fn current() -> Self {
+        if cfg!(feature = "tracing") {
+            Self::Tracing
+        } else if cfg!(feature = "log") {
+            Self::Log
+        } else if cfg!(feature = "defmt") {
+            Self::Defmt
+        } else {
+            Self::Tracing
+        }
+    }
+
+    /// Path of the crate in the generated code.
+    fn path(self) -> TokenStream {
+        match self {
+            Self::Tracing => tracing_crate(),
+            Self::Log => quote! {::log},
+            Self::Defmt => quote! {::defmt},
+        }
+    }
+}
+
+/// Path of `tracing` in the generated code: the re-export of `time_it`, so the crates using the
+/// macros don't need to depend on `tracing` themselves.
+pub(crate) fn tracing_crate() -> TokenStream {
+    tracing_crate_at(Span::call_site())
+}
+
+/// `tracing_crate`, spanned at `span` so errors involving it are reported there.
+fn tracing_crate_at(span: Span) -> TokenStream {
+    quote_spanned! {span=> ::time_it::__tracing}
+}
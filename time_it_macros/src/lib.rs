@@ -0,0 +1,140 @@
+//! The procedural macros of `time_it`, which re-exports them along with the crates the generated
+//! code relies on. Use them through `time_it`, which documents them, so that their examples are
+//! tested against it.
+
+mod args;
+mod expand;
+
+use args::{Args, BlockInput, ClosureInput, ExcludeInput, GroupInput, ScopeInput};
+use proc_macro::TokenStream;
+use quote::quote;
+
+#[proc_macro_attribute]
+pub fn time_it(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if let Ok(input) = syn::parse::<syn::ItemImpl>(item.clone()) {
+        let args = syn::parse_macro_input!(attr as Args);
+        if args.skip || cfg!(feature = "disabled") || args.statically_disabled() {
+            return expand::untimed_impl(&input).into();
+        }
+        return expand::time_impl(&args, &input)
+            .unwrap_or_else(syn::Error::into_compile_error)
+            .into();
+    }
+    if let Ok(input) = syn::parse::<syn::ItemMod>(item.clone()) {
+        let args = syn::parse_macro_input!(attr as Args);
+        if args.skip || cfg!(feature = "disabled") || args.statically_disabled() {
+            return expand::untimed_mod(&input).into();
+        }
+        return expand::time_mod(&args, &input)
+            .unwrap_or_else(syn::Error::into_compile_error)
+            .into();
+    }
+    let input = match syn::parse::<syn::ItemFn>(item.clone()) {
+        Ok(input) => input,
+        Err(err) => {
+            return expand::unsupported_item(item.into())
+                .unwrap_or(err)
+                .into_compile_error()
+                .into();
+        }
+    };
+    let args = syn::parse_macro_input!(attr as Args);
+    if args.skip || cfg!(feature = "disabled") || args.statically_disabled() {
+        return quote! {#input}.into();
+    }
+
+    expand::time_it(&args, &input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn time_block(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as BlockInput);
+    if cfg!(feature = "disabled") || input.args.statically_disabled() {
+        return expand::untimed_block(&input.block).into();
+    }
+
+    expand::time_block(&input.args, &input.label, &input.block)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn time_scope(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as ScopeInput);
+    if cfg!(feature = "disabled") || input.args.statically_disabled() {
+        return expand::untimed_block(&input.block).into();
+    }
+
+    expand::time_scope(&input.args, &input.label, &input.block)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn time_closure(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as ClosureInput);
+    if cfg!(feature = "disabled") || input.args.statically_disabled() {
+        let closure = input.closure;
+        return quote! {#closure}.into();
+    }
+
+    expand::time_closure(&input.args, &input.label, &input.closure)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn count_allocations(input: TokenStream) -> TokenStream {
+    syn::parse_macro_input!(input as syn::parse::Nothing);
+    if cfg!(feature = "disabled") {
+        return TokenStream::new();
+    }
+
+    expand::count_allocations()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Reports a `time_it::Timer` when it's dropped, expanding into the body of its `Drop` impl. Only
+/// meant for `time_it`, which defines the type.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __report_timer(input: TokenStream) -> TokenStream {
+    syn::parse_macro_input!(input as syn::parse::Nothing);
+
+    expand::timer_report().into()
+}
+
+#[proc_macro]
+pub fn scope(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as GroupInput);
+    if cfg!(feature = "disabled") || input.args.statically_disabled() {
+        return expand::untimed_block(&input.block).into();
+    }
+
+    expand::scope(&input.args, &input.label, &input.block)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn exclusions(input: TokenStream) -> TokenStream {
+    syn::parse_macro_input!(input as syn::parse::Nothing);
+
+    expand::exclusions()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn exclude(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as ExcludeInput);
+    if cfg!(feature = "disabled") {
+        let expr = input.expr;
+        return quote! {#expr}.into();
+    }
+
+    expand::exclude(&input.name, &input.expr).into()
+}